@@ -0,0 +1,20 @@
+-- migrations/20260105143022_add_finance_permissions.sql
+
+-- 1. Permissões do Módulo Financeiro
+INSERT INTO permissions (slug, description, module) VALUES
+    ('finance:read',  'Visualizar contas, categorias e títulos financeiros', 'FINANCE'),
+    ('finance:write', 'Cadastrar contas, categorias e lançar títulos', 'FINANCE')
+ON CONFLICT (slug) DO NOTHING;
+
+-- 2. Os cargos "Dono" já existentes foram criados com TODAS as permissões da época.
+-- Estendemos para as novas, mantendo a regra "Dono tem acesso total".
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+CROSS JOIN permissions p
+WHERE r.name = 'Dono'
+  AND p.module = 'FINANCE'
+ON CONFLICT DO NOTHING;
+
+-- 3. Índice para listagem de títulos por vencimento/tipo
+CREATE INDEX IF NOT EXISTS idx_titles_kind_duedate ON financial_titles(tenant_id, kind, due_date);
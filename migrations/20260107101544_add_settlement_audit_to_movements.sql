@@ -0,0 +1,24 @@
+-- migrations/20260107101544_add_settlement_audit_to_movements.sql
+
+-- Baixa de Títulos (Pagamentos / Recebimentos) e Estornos
+-- Cada baixa gera uma linha em financial_movements. O estorno NÃO apaga a linha:
+-- gera uma nova linha com o valor invertido apontando para a original (trilha de auditoria).
+
+ALTER TABLE financial_movements
+    ADD COLUMN description VARCHAR(255),
+
+    -- Quem lançou (Auditoria)
+    ADD COLUMN created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    -- Se esta linha é um estorno, aponta para a movimentação estornada
+    ADD COLUMN reversal_of UUID REFERENCES financial_movements(id),
+
+    -- Preenchido na movimentação ORIGINAL quando ela é estornada
+    ADD COLUMN reversed_at TIMESTAMPTZ,
+    ADD COLUMN reversal_reason TEXT;
+
+-- Uma movimentação só pode ser estornada uma vez
+CREATE UNIQUE INDEX idx_movements_reversal_of ON financial_movements(reversal_of) WHERE reversal_of IS NOT NULL;
+
+-- Histórico de baixas de um título
+CREATE INDEX idx_movements_title ON financial_movements(tenant_id, title_id);
@@ -0,0 +1,20 @@
+-- migrations/20260109090000_add_overdue_charges.sql
+
+-- Juros e Multa por Atraso
+-- As taxas ficam nas configurações da loja e são aplicadas no momento da baixa.
+
+-- 1. Taxas por loja (em %)
+ALTER TABLE tenant_settings
+    ADD COLUMN late_fee_percent NUMERIC(5, 2) NOT NULL DEFAULT 0,        -- Multa (aplicada uma vez). Ex: 2.00
+    ADD COLUMN daily_interest_percent NUMERIC(7, 4) NOT NULL DEFAULT 0,  -- Juros ao dia. Ex: 0.0333 (~1% a.m.)
+    ADD CONSTRAINT chk_settings_late_fee CHECK (late_fee_percent >= 0),
+    ADD CONSTRAINT chk_settings_daily_interest CHECK (daily_interest_percent >= 0);
+
+-- 2. Quanto de cada movimentação foi juros/multa (o resto abate o saldo do título)
+ALTER TABLE financial_movements
+    ADD COLUMN interest_amount NUMERIC(15, 2) NOT NULL DEFAULT 0,
+    ADD COLUMN fee_amount NUMERIC(15, 2) NOT NULL DEFAULT 0;
+
+-- 3. Varredura de vencidos (status em aberto + vencimento)
+CREATE INDEX IF NOT EXISTS idx_titles_open_duedate ON financial_titles(due_date)
+    WHERE status IN ('PENDING', 'PARTIAL');
@@ -0,0 +1,54 @@
+-- migrations/20260111143510_create_payment_terms.sql
+
+-- Condições de Pagamento (Parcelamento)
+-- Ex: "3x sem entrada", "Entrada 30% + 6x"
+-- Ao gerar o Contas a Receber de um pedido, o valor é dividido em N títulos vinculados.
+
+CREATE TABLE payment_terms (
+                               id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                               tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+                               name VARCHAR(100) NOT NULL,
+
+    -- Quantidade de parcelas (sem contar a entrada)
+                               installments INTEGER NOT NULL DEFAULT 1 CHECK (installments BETWEEN 1 AND 120),
+
+    -- Dias entre uma parcela e outra
+                               interval_days INTEGER NOT NULL DEFAULT 30 CHECK (interval_days > 0),
+
+    -- Dias até a 1ª parcela (contados da geração do título). 0 = vence hoje
+                               first_due_days INTEGER NOT NULL DEFAULT 30 CHECK (first_due_days >= 0),
+
+    -- Entrada (% do total, vence na geração). 0 = sem entrada
+                               down_payment_percent NUMERIC(5, 2) NOT NULL DEFAULT 0
+                                   CHECK (down_payment_percent >= 0 AND down_payment_percent < 100),
+
+                               is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                               created_at TIMESTAMPTZ DEFAULT NOW(),
+
+                               CONSTRAINT uq_payment_terms_name UNIQUE (tenant_id, name)
+);
+
+-- Vínculos: o pedido tem prioridade sobre a etapa do pipeline
+ALTER TABLE orders
+    ADD COLUMN payment_term_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+
+ALTER TABLE pipeline_stages
+    ADD COLUMN payment_term_id UUID REFERENCES payment_terms(id) ON DELETE SET NULL;
+
+-- Parcelas: títulos do mesmo parcelamento compartilham o installment_group_id
+-- installment_number = 0 é a entrada
+ALTER TABLE financial_titles
+    ADD COLUMN installment_group_id UUID,
+    ADD COLUMN installment_number SMALLINT,
+    ADD COLUMN installment_count SMALLINT;
+
+CREATE INDEX idx_titles_installment_group ON financial_titles(tenant_id, installment_group_id)
+    WHERE installment_group_id IS NOT NULL;
+
+-- RLS
+ALTER TABLE payment_terms ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_payment_terms ON payment_terms FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON payment_terms TO "user";
@@ -0,0 +1,71 @@
+-- migrations/20260113094520_create_cash_sessions.sql
+
+-- Caixa (Abertura / Fechamento)
+-- Uma sessão de caixa é aberta por um operador em um local, sobre uma conta financeira (a "gaveta").
+-- Vendas, sangrias e suprimentos viram financial_movements vinculados à sessão.
+
+CREATE TYPE cash_session_status AS ENUM ('OPEN', 'CLOSED');
+
+-- Natureza da movimentação dentro do caixa
+-- SALE: Venda/Recebimento (+) | PAYMENT: Pagamento feito com dinheiro do caixa (-)
+-- DEPOSIT: Suprimento (+)     | WITHDRAWAL: Sangria (-)
+-- ADJUSTMENT: Quebra/Sobra apurada no fechamento (+/-)
+CREATE TYPE cash_movement_kind AS ENUM ('SALE', 'PAYMENT', 'DEPOSIT', 'WITHDRAWAL', 'ADJUSTMENT');
+
+CREATE TABLE cash_sessions (
+                               id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                               tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+                               account_id UUID NOT NULL REFERENCES financial_accounts(id),
+                               location_id UUID NOT NULL REFERENCES locations(id),
+
+                               status cash_session_status NOT NULL DEFAULT 'OPEN',
+
+    -- Abertura
+                               opened_by UUID NOT NULL REFERENCES users(id),
+                               opening_float NUMERIC(15, 2) NOT NULL DEFAULT 0, -- Troco inicial (contado)
+                               opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Fechamento (Contado x Esperado)
+                               closed_by UUID REFERENCES users(id),
+                               closed_at TIMESTAMPTZ,
+                               expected_amount NUMERIC(15, 2),
+                               counted_amount NUMERIC(15, 2),
+                               difference NUMERIC(15, 2), -- counted - expected (negativo = falta)
+
+                               notes TEXT,
+
+                               CONSTRAINT chk_cash_opening_float CHECK (opening_float >= 0)
+);
+
+-- Um operador só tem um caixa aberto por local, e uma gaveta só tem uma sessão aberta
+CREATE UNIQUE INDEX idx_cash_sessions_open_user ON cash_sessions(tenant_id, opened_by, location_id) WHERE status = 'OPEN';
+CREATE UNIQUE INDEX idx_cash_sessions_open_account ON cash_sessions(account_id) WHERE status = 'OPEN';
+CREATE INDEX idx_cash_sessions_opened_at ON cash_sessions(tenant_id, opened_at DESC);
+
+-- Vínculo das movimentações com o caixa
+ALTER TABLE financial_movements
+    ADD COLUMN cash_session_id UUID REFERENCES cash_sessions(id),
+    ADD COLUMN cash_kind cash_movement_kind;
+
+CREATE INDEX idx_movements_cash_session ON financial_movements(cash_session_id) WHERE cash_session_id IS NOT NULL;
+
+-- RLS
+ALTER TABLE cash_sessions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_cash_sessions ON cash_sessions FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON cash_sessions TO "user";
+
+-- Permissão do operador de caixa (não precisa enxergar todo o financeiro)
+INSERT INTO permissions (slug, description, module) VALUES
+    ('cash:operate', 'Abrir, movimentar e fechar o caixa', 'FINANCE')
+ON CONFLICT (slug) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+CROSS JOIN permissions p
+WHERE r.name = 'Dono'
+  AND p.slug = 'cash:operate'
+ON CONFLICT DO NOTHING;
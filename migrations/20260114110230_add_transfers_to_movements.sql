@@ -0,0 +1,15 @@
+-- migrations/20260114110230_add_transfers_to_movements.sql
+
+-- Transferências entre Contas (ex: Gaveta -> Banco)
+-- Uma transferência gera um PAR de movimentações com o mesmo transfer_id:
+-- saída (-) na conta de origem e entrada (+) na conta de destino, sempre somando zero.
+-- Aparecem no extrato das contas, mas não são receita nem despesa (não têm título).
+
+ALTER TABLE financial_movements
+    ADD COLUMN transfer_id UUID,
+
+    -- Perna de transferência nunca baixa título
+    ADD CONSTRAINT chk_movements_transfer_without_title
+        CHECK (transfer_id IS NULL OR title_id IS NULL);
+
+CREATE INDEX idx_movements_transfer ON financial_movements(tenant_id, transfer_id) WHERE transfer_id IS NOT NULL;
@@ -0,0 +1,72 @@
+-- migrations/20260116083015_create_bank_reconciliation.sql
+
+-- Conciliação Bancária (Importação de Extrato OFX / CSV)
+-- Cada arquivo importado gera um bank_statement_imports e N bank_statement_lines.
+-- As linhas são conciliadas com financial_movements já lançadas ou sugeridas contra
+-- títulos em aberto. O que sobra fica na fila de revisão (PENDING).
+
+CREATE TYPE bank_statement_format AS ENUM ('OFX', 'CSV');
+
+-- PENDING: Aguardando revisão | MATCHED: Conciliada com uma movimentação | IGNORED: Descartada
+CREATE TYPE bank_line_status AS ENUM ('PENDING', 'MATCHED', 'IGNORED');
+
+CREATE TABLE bank_statement_imports (
+                                        id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                        tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                        account_id UUID NOT NULL REFERENCES financial_accounts(id),
+
+                                        format bank_statement_format NOT NULL,
+                                        file_name VARCHAR(255),
+
+    -- Resumo do processamento
+                                        total_lines INT NOT NULL DEFAULT 0,
+                                        duplicated_lines INT NOT NULL DEFAULT 0, -- Já importadas antes (mesmo FITID)
+                                        matched_lines INT NOT NULL DEFAULT 0,
+
+                                        imported_by UUID REFERENCES users(id) ON DELETE SET NULL,
+                                        imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE bank_statement_lines (
+                                      id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                      tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                      import_id UUID NOT NULL REFERENCES bank_statement_imports(id) ON DELETE CASCADE,
+                                      account_id UUID NOT NULL REFERENCES financial_accounts(id),
+
+    -- Dados do banco
+                                      external_id VARCHAR(255) NOT NULL, -- FITID do OFX (ou chave gerada para CSV)
+                                      posted_date DATE NOT NULL,
+                                      amount NUMERIC(15, 2) NOT NULL, -- Positivo = Crédito, Negativo = Débito
+                                      description VARCHAR(255),
+
+                                      status bank_line_status NOT NULL DEFAULT 'PENDING',
+
+    -- Conciliação
+                                      matched_movement_id UUID REFERENCES financial_movements(id),
+                                      suggested_title_id UUID REFERENCES financial_titles(id) ON DELETE SET NULL,
+
+                                      reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+                                      reviewed_at TIMESTAMPTZ,
+                                      created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+                                      CONSTRAINT chk_bank_line_matched CHECK (status <> 'MATCHED' OR matched_movement_id IS NOT NULL)
+);
+
+-- Reimportar o mesmo extrato não duplica linhas
+CREATE UNIQUE INDEX idx_bank_lines_external ON bank_statement_lines(account_id, external_id);
+
+-- Uma movimentação só concilia com uma linha do banco
+CREATE UNIQUE INDEX idx_bank_lines_movement ON bank_statement_lines(matched_movement_id) WHERE matched_movement_id IS NOT NULL;
+
+-- Fila de revisão
+CREATE INDEX idx_bank_lines_queue ON bank_statement_lines(tenant_id, account_id, status, posted_date);
+
+-- RLS
+ALTER TABLE bank_statement_imports ENABLE ROW LEVEL SECURITY;
+ALTER TABLE bank_statement_lines ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_bank_imports ON bank_statement_imports FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_bank_lines ON bank_statement_lines FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON bank_statement_imports TO "user";
+GRANT ALL ON bank_statement_lines TO "user";
@@ -0,0 +1,56 @@
+-- migrations/20260119091540_create_recurring_titles.sql
+
+-- Títulos Recorrentes (Aluguel, Salários, Mensalidades de clientes)
+-- Um modelo com a regra de recorrência gera os financial_titles com antecedência.
+-- Editar o modelo vale para as ocorrências ainda não geradas; as já geradas são títulos comuns.
+
+CREATE TYPE recurrence_frequency AS ENUM ('WEEKLY', 'MONTHLY', 'YEARLY');
+
+CREATE TABLE recurring_titles (
+                                  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+    -- Modelo do título
+                                  description VARCHAR(255) NOT NULL,
+                                  kind title_kind NOT NULL,
+                                  amount NUMERIC(15, 2) NOT NULL CHECK (amount > 0),
+                                  category_id UUID REFERENCES financial_categories(id) ON DELETE RESTRICT,
+                                  customer_id UUID REFERENCES customers(id) ON DELETE SET NULL,
+
+    -- Regra: a cada `interval_count` semanas/meses/anos, a partir de start_date (1º vencimento)
+                                  frequency recurrence_frequency NOT NULL,
+                                  interval_count INTEGER NOT NULL DEFAULT 1 CHECK (interval_count BETWEEN 1 AND 52),
+                                  start_date DATE NOT NULL,
+                                  end_date DATE, -- Último vencimento possível. NULL = sem fim
+
+    -- Antecedência: gera as ocorrências que vencem até hoje + lead_days
+                                  lead_days INTEGER NOT NULL DEFAULT 30 CHECK (lead_days BETWEEN 0 AND 366),
+
+    -- Controle da geração. A ocorrência N vence em start_date + N intervalos
+                                  generated_count INTEGER NOT NULL DEFAULT 0,
+                                  next_due_date DATE, -- NULL = recorrência encerrada
+
+                                  is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                                  created_by UUID REFERENCES users(id),
+                                  created_at TIMESTAMPTZ DEFAULT NOW(),
+                                  updated_at TIMESTAMPTZ DEFAULT NOW(),
+
+                                  CONSTRAINT chk_recurring_end_date CHECK (end_date IS NULL OR end_date >= start_date)
+);
+
+CREATE INDEX idx_recurring_titles_next_due ON recurring_titles(tenant_id, next_due_date)
+    WHERE is_active AND next_due_date IS NOT NULL;
+
+-- Vínculo do título gerado com o modelo (um vencimento por modelo)
+ALTER TABLE financial_titles
+    ADD COLUMN recurring_title_id UUID REFERENCES recurring_titles(id) ON DELETE SET NULL;
+
+CREATE UNIQUE INDEX idx_titles_recurring_due ON financial_titles(recurring_title_id, due_date)
+    WHERE recurring_title_id IS NOT NULL;
+
+-- RLS
+ALTER TABLE recurring_titles ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_recurring_titles ON recurring_titles FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON recurring_titles TO "user";
@@ -0,0 +1,15 @@
+-- migrations/20260120143005_add_category_hierarchy.sql
+
+-- Plano de Contas hierárquico
+-- Ex: 2 Despesas > 2.4 Ocupação > 2.4.01 Aluguel
+-- Os relatórios somam as subcategorias no nó pai. Filhos têm o mesmo tipo (kind) do pai.
+
+ALTER TABLE financial_categories
+    ADD COLUMN code VARCHAR(20),
+    ADD COLUMN parent_id UUID REFERENCES financial_categories(id) ON DELETE RESTRICT,
+    ADD CONSTRAINT chk_financial_categories_parent CHECK (parent_id IS NULL OR parent_id <> id);
+
+CREATE UNIQUE INDEX uq_financial_categories_code ON financial_categories(tenant_id, code)
+    WHERE code IS NOT NULL;
+
+CREATE INDEX idx_financial_categories_parent ON financial_categories(tenant_id, parent_id);
@@ -0,0 +1,5 @@
+-- migrations/20260122101230_add_city_to_tenant_settings.sql
+
+-- Cidade do recebedor: obrigatória no BR Code do Pix (campo 60 do payload EMV)
+ALTER TABLE tenant_settings
+    ADD COLUMN city VARCHAR(60);
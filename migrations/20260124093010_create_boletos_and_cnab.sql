@@ -0,0 +1,105 @@
+-- migrations/20260124093010_create_boletos_and_cnab.sql
+
+-- Cobrança por Boleto + Arquivos CNAB (remessa / retorno)
+-- Cada conta bancária que emite boletos tem um billing_configs (dados do convênio).
+-- O boleto nasce PENDING, vai para o banco num arquivo de remessa (REMITTED) e
+-- o arquivo de retorno confirma o registro (REGISTERED), rejeita ou liquida (PAID).
+
+CREATE TYPE cnab_layout AS ENUM ('CNAB240', 'CNAB400');
+
+CREATE TYPE cnab_file_kind AS ENUM ('REMITTANCE', 'RETURN');
+
+-- PENDING: Aguardando remessa | REMITTED: Enviado ao banco | REGISTERED: Entrada confirmada
+-- REJECTED: Recusado pelo banco | PAID: Liquidado | CANCELLED: Baixado
+CREATE TYPE boleto_status AS ENUM ('PENDING', 'REMITTED', 'REGISTERED', 'REJECTED', 'PAID', 'CANCELLED');
+
+CREATE TABLE billing_configs (
+                                 account_id UUID PRIMARY KEY REFERENCES financial_accounts(id) ON DELETE CASCADE,
+                                 tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+    -- Dados do beneficiário no banco
+                                 bank_code CHAR(3) NOT NULL,             -- 001 BB | 237 Bradesco | 341 Itaú
+                                 agency VARCHAR(5) NOT NULL,
+                                 agency_digit VARCHAR(1),
+                                 account_number VARCHAR(12) NOT NULL,
+                                 account_digit VARCHAR(1),
+                                 wallet VARCHAR(3) NOT NULL,             -- Carteira
+                                 agreement_code VARCHAR(20) NOT NULL,    -- Convênio / Código do beneficiário
+
+                                 layout cnab_layout NOT NULL DEFAULT 'CNAB240',
+
+    -- Sequências controladas pelo sistema
+                                 next_our_number BIGINT NOT NULL DEFAULT 1 CHECK (next_our_number > 0),
+                                 next_remittance_number INT NOT NULL DEFAULT 1 CHECK (next_remittance_number > 0),
+
+                                 updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE cnab_files (
+                            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                            tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                            account_id UUID NOT NULL REFERENCES financial_accounts(id),
+
+                            kind cnab_file_kind NOT NULL,
+                            layout cnab_layout NOT NULL,
+                            sequence_number INT,                 -- Nº da remessa (só REMITTANCE)
+                            file_name VARCHAR(255),
+                            content TEXT NOT NULL,
+
+    -- Resumo: boletos enviados (remessa) ou registros processados / liquidados (retorno)
+                            record_count INT NOT NULL DEFAULT 0,
+                            settled_count INT NOT NULL DEFAULT 0,
+
+                            created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+                            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE boletos (
+                         id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                         tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                         title_id UUID NOT NULL REFERENCES financial_titles(id) ON DELETE CASCADE,
+                         account_id UUID NOT NULL REFERENCES financial_accounts(id),
+
+                         our_number VARCHAR(20) NOT NULL,     -- Nosso Número (sem DV), como vai na remessa
+                         amount NUMERIC(15, 2) NOT NULL CHECK (amount > 0),
+                         due_date DATE NOT NULL,
+                         barcode CHAR(44) NOT NULL,
+                         digitable_line VARCHAR(60) NOT NULL,
+
+                         status boleto_status NOT NULL DEFAULT 'PENDING',
+                         remittance_id UUID REFERENCES cnab_files(id) ON DELETE SET NULL,
+
+    -- Liquidação (arquivo de retorno)
+                         paid_amount NUMERIC(15, 2),
+                         paid_date DATE,
+                         movement_id UUID REFERENCES financial_movements(id) ON DELETE SET NULL,
+                         bank_message VARCHAR(255),           -- Última ocorrência do retorno
+
+                         created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+                         created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                         updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Nosso Número é único por conta (é a chave do retorno)
+CREATE UNIQUE INDEX idx_boletos_our_number ON boletos(account_id, our_number);
+
+-- Um boleto vivo por título (rejeitados e baixados liberam um novo)
+CREATE UNIQUE INDEX idx_boletos_active_title ON boletos(title_id) WHERE status NOT IN ('REJECTED', 'CANCELLED');
+
+-- Fila da próxima remessa
+CREATE INDEX idx_boletos_queue ON boletos(tenant_id, account_id, status);
+
+CREATE INDEX idx_cnab_files_account ON cnab_files(tenant_id, account_id, created_at);
+
+-- RLS
+ALTER TABLE billing_configs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE cnab_files ENABLE ROW LEVEL SECURITY;
+ALTER TABLE boletos ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_billing_configs ON billing_configs FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_cnab_files ON cnab_files FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_boletos ON boletos FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON billing_configs TO "user";
+GRANT ALL ON cnab_files TO "user";
+GRANT ALL ON boletos TO "user";
@@ -0,0 +1,35 @@
+-- migrations/20260126102040_create_category_budgets.sql
+
+-- Orçamento mensal por categoria financeira
+-- O orçamento de uma categoria cobre ela e as subcategorias. Sem orçamento próprio,
+-- a categoria herda a soma do orçado nas filhas.
+
+CREATE TABLE category_budgets (
+                                  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                  category_id UUID NOT NULL REFERENCES financial_categories(id) ON DELETE CASCADE,
+
+    -- Sempre o 1º dia do mês
+                                  month DATE NOT NULL CHECK (EXTRACT(DAY FROM month) = 1),
+                                  amount NUMERIC(15, 2) NOT NULL CHECK (amount >= 0),
+
+                                  created_by UUID REFERENCES users(id),
+                                  created_at TIMESTAMPTZ DEFAULT NOW(),
+                                  updated_at TIMESTAMPTZ DEFAULT NOW(),
+
+                                  CONSTRAINT uq_category_budget_month UNIQUE (category_id, month)
+);
+
+CREATE INDEX idx_category_budgets_month ON category_budgets(tenant_id, month);
+
+-- Alerta no painel: categoria de despesa que já consumiu X% do orçamento do mês
+ALTER TABLE tenant_settings
+    ADD COLUMN budget_alert_percent NUMERIC(5, 2) NOT NULL DEFAULT 100,
+    ADD CONSTRAINT chk_settings_budget_alert CHECK (budget_alert_percent > 0);
+
+-- RLS
+ALTER TABLE category_budgets ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_category_budgets ON category_budgets FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON category_budgets TO "user";
@@ -0,0 +1,64 @@
+-- migrations/20260128091530_create_stock_transfers.sql
+
+-- Transferências de estoque entre locais
+-- A saída (TRANSFER_OUT) e a entrada (TRANSFER_IN) ficam ligadas pelo transfer_id.
+-- Em trânsito: a mercadoria já saiu da origem e ainda não entrou no destino.
+
+CREATE TYPE stock_transfer_status AS ENUM ('IN_TRANSIT', 'COMPLETED', 'CANCELLED');
+
+CREATE TABLE stock_transfers (
+                                 id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                 tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+                                 from_location_id UUID NOT NULL REFERENCES locations(id) ON DELETE RESTRICT,
+                                 to_location_id UUID NOT NULL REFERENCES locations(id) ON DELETE RESTRICT,
+
+                                 status stock_transfer_status NOT NULL,
+                                 notes TEXT,
+
+                                 created_by UUID REFERENCES users(id),
+                                 shipped_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                 received_at TIMESTAMPTZ, -- Entrada no destino (ou retorno à origem, se cancelada)
+                                 received_by UUID REFERENCES users(id),
+
+                                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                 updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+                                 CONSTRAINT chk_transfer_locations CHECK (from_location_id <> to_location_id)
+);
+
+CREATE INDEX idx_stock_transfers_status ON stock_transfers(tenant_id, status, shipped_at DESC);
+
+-- O que saiu de cada lote/posição da origem, com o custo do lote
+CREATE TABLE stock_transfer_items (
+                                      id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                      tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                      transfer_id UUID NOT NULL REFERENCES stock_transfers(id) ON DELETE CASCADE,
+                                      item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+
+                                      batch_number VARCHAR(255) NOT NULL,
+                                      expiration_date DATE,
+                                      from_position VARCHAR(100) NOT NULL,
+                                      to_position VARCHAR(100) NOT NULL,
+
+                                      quantity NUMERIC(10, 2) NOT NULL CHECK (quantity > 0),
+                                      unit_cost NUMERIC(10, 2) NOT NULL DEFAULT 0.00
+);
+
+CREATE INDEX idx_stock_transfer_items_transfer ON stock_transfer_items(transfer_id);
+
+-- Vínculo das movimentações com a transferência
+ALTER TABLE stock_movements
+    ADD COLUMN transfer_id UUID REFERENCES stock_transfers(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_stock_movements_transfer ON stock_movements(transfer_id) WHERE transfer_id IS NOT NULL;
+
+-- RLS
+ALTER TABLE stock_transfers ENABLE ROW LEVEL SECURITY;
+ALTER TABLE stock_transfer_items ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_stock_transfers ON stock_transfers FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_stock_transfer_items ON stock_transfer_items FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON stock_transfers TO "user";
+GRANT ALL ON stock_transfer_items TO "user";
@@ -0,0 +1,61 @@
+-- migrations/20260130100420_create_inventory_counts.sql
+
+-- Inventário físico (contagem cíclica) por local
+-- Na abertura, congelamos o saldo esperado de cada lote/posição.
+-- A equipe lança as quantidades contadas (de um ou vários aparelhos) e,
+-- na aprovação, as diferenças viram movimentações de CORRECTION.
+
+CREATE TYPE inventory_count_status AS ENUM ('OPEN', 'APPROVED', 'CANCELLED');
+
+CREATE TABLE inventory_counts (
+                                  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                  location_id UUID NOT NULL REFERENCES locations(id) ON DELETE RESTRICT,
+
+                                  status inventory_count_status NOT NULL DEFAULT 'OPEN',
+                                  notes TEXT,
+
+                                  created_by UUID REFERENCES users(id),
+                                  closed_by UUID REFERENCES users(id), -- Quem aprovou ou cancelou
+                                  closed_at TIMESTAMPTZ,
+
+                                  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Apenas uma contagem aberta por local
+CREATE UNIQUE INDEX uq_inventory_counts_open ON inventory_counts(tenant_id, location_id) WHERE status = 'OPEN';
+CREATE INDEX idx_inventory_counts_status ON inventory_counts(tenant_id, status, created_at DESC);
+
+CREATE TABLE inventory_count_lines (
+                                       id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                       tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                       count_id UUID NOT NULL REFERENCES inventory_counts(id) ON DELETE CASCADE,
+                                       item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+
+                                       batch_number VARCHAR(255) NOT NULL,
+                                       position VARCHAR(100) NOT NULL,
+                                       expiration_date DATE,
+
+                                       expected_quantity NUMERIC(10, 2) NOT NULL DEFAULT 0.00, -- Congelado na abertura
+                                       counted_quantity NUMERIC(10, 2),                        -- NULL = ainda não contado
+                                       unit_cost NUMERIC(10, 2) NOT NULL DEFAULT 0.00,         -- Custo médio congelado na abertura
+
+                                       counted_by UUID REFERENCES users(id),
+                                       counted_at TIMESTAMPTZ,
+
+                                       CONSTRAINT uq_count_line UNIQUE (count_id, item_id, batch_number, position),
+                                       CONSTRAINT chk_counted_not_negative CHECK (counted_quantity IS NULL OR counted_quantity >= 0)
+);
+
+CREATE INDEX idx_inventory_count_lines_count ON inventory_count_lines(count_id);
+
+-- RLS
+ALTER TABLE inventory_counts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE inventory_count_lines ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_inventory_counts ON inventory_counts FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_inventory_count_lines ON inventory_count_lines FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON inventory_counts TO "user";
+GRANT ALL ON inventory_count_lines TO "user";
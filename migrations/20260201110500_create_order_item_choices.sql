@@ -0,0 +1,24 @@
+-- migrations/20260201110500_create_order_item_choices.sql
+
+-- Escolhas de composição feitas no pedido (itens com ficha técnica):
+-- acessório incluído (replaces_item_id NULL) ou substituto no lugar de um componente.
+-- Usadas quando a etapa baixa o estoque (explosão da ficha técnica).
+
+CREATE TABLE order_item_choices (
+                                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                    order_item_id UUID NOT NULL REFERENCES order_items(id) ON DELETE CASCADE,
+
+                                    child_item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+                                    replaces_item_id UUID REFERENCES items(id) ON DELETE RESTRICT,
+
+                                    UNIQUE (order_item_id, child_item_id)
+);
+
+CREATE INDEX idx_order_item_choices_item ON order_item_choices(order_item_id);
+
+ALTER TABLE order_item_choices ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_order_item_choices ON order_item_choices FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON order_item_choices TO "user";
@@ -0,0 +1,69 @@
+-- migrations/20260203094015_create_production_orders.sql
+
+-- Ordens de produção (cozinha, oficina): consomem os COMPONENT da ficha técnica
+-- e geram um lote novo do item pai, com custo = soma dos componentes consumidos.
+
+ALTER TYPE stock_movement_reason ADD VALUE IF NOT EXISTS 'PRODUCTION_CONSUMPTION';
+ALTER TYPE stock_movement_reason ADD VALUE IF NOT EXISTS 'PRODUCTION_OUTPUT';
+
+CREATE TYPE production_order_status AS ENUM ('PLANNED', 'COMPLETED', 'CANCELLED');
+
+CREATE TABLE production_orders (
+                                   id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                   tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                   item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+                                   location_id UUID NOT NULL REFERENCES locations(id) ON DELETE RESTRICT,
+
+                                   status production_order_status NOT NULL DEFAULT 'PLANNED',
+
+                                   planned_quantity NUMERIC(15, 4) NOT NULL CHECK (planned_quantity > 0),
+                                   produced_quantity NUMERIC(15, 4),
+
+    -- Rendimento real: produzido / planejado. Perda: o que faltou para 100%.
+                                   yield_percent NUMERIC(7, 2),
+                                   loss_percent NUMERIC(7, 2),
+
+    -- Lote gerado do item pai
+                                   batch_number VARCHAR(255),
+                                   position VARCHAR(100),
+                                   expiration_date DATE,
+
+                                   total_cost NUMERIC(15, 4),
+                                   unit_cost NUMERIC(15, 4),
+
+                                   notes TEXT,
+                                   created_by UUID REFERENCES users(id),
+                                   closed_by UUID REFERENCES users(id),
+                                   closed_at TIMESTAMPTZ,
+
+                                   created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                   updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_production_orders_status ON production_orders(tenant_id, status, created_at DESC);
+
+-- Componentes congelados na abertura (ficha técnica x quantidade planejada)
+CREATE TABLE production_order_components (
+                                             id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                             tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                             production_order_id UUID NOT NULL REFERENCES production_orders(id) ON DELETE CASCADE,
+                                             item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+
+                                             planned_quantity NUMERIC(15, 4) NOT NULL,
+                                             consumed_quantity NUMERIC(15, 4),
+                                             total_cost NUMERIC(15, 4),
+
+                                             UNIQUE (production_order_id, item_id)
+);
+
+CREATE INDEX idx_production_components_order ON production_order_components(production_order_id);
+
+-- RLS
+ALTER TABLE production_orders ENABLE ROW LEVEL SECURITY;
+ALTER TABLE production_order_components ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_production_orders ON production_orders FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+CREATE POLICY tenant_iso_production_components ON production_order_components FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON production_orders TO "user";
+GRANT ALL ON production_order_components TO "user";
@@ -0,0 +1,45 @@
+-- migrations/20260205143020_create_unit_conversions.sql
+
+-- Conversões entre unidades de medida: 1 from_unit = factor to_unit.
+-- Com item_id, vale só para aquele item (ex: CX do refrigerante = 12 UN);
+-- sem item_id, vale para a loja toda (ex: 1 kg = 1000 g).
+-- O inverso (to -> from) é deduzido, não precisa ser cadastrado.
+
+CREATE TABLE unit_conversions (
+                                  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+                                  item_id UUID REFERENCES items(id) ON DELETE CASCADE,
+
+                                  from_unit_id UUID NOT NULL REFERENCES units_of_measure(id) ON DELETE RESTRICT,
+                                  to_unit_id UUID NOT NULL REFERENCES units_of_measure(id) ON DELETE RESTRICT,
+                                  factor NUMERIC(18, 6) NOT NULL CHECK (factor > 0),
+
+                                  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+                                  CONSTRAINT chk_conversion_units CHECK (from_unit_id <> to_unit_id)
+);
+
+-- Uma conversão por par de unidades (geral ou do item)
+CREATE UNIQUE INDEX idx_unit_conversions_pair ON unit_conversions(
+    tenant_id, COALESCE(item_id, '00000000-0000-0000-0000-000000000000'::uuid), from_unit_id, to_unit_id
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON unit_conversions FOR EACH ROW EXECUTE FUNCTION trigger_set_timestamp();
+
+-- Unidades de compra e de venda do item (vazio = unidade base)
+ALTER TABLE items
+    ADD COLUMN purchase_unit_id UUID REFERENCES units_of_measure(id) ON DELETE SET NULL,
+    ADD COLUMN sale_unit_id UUID REFERENCES units_of_measure(id) ON DELETE SET NULL;
+
+-- Unidade em que o item foi lançado no pedido. quantity/unit_price ficam na unidade base.
+ALTER TABLE order_items
+    ADD COLUMN unit_id UUID REFERENCES units_of_measure(id) ON DELETE SET NULL,
+    ADD COLUMN unit_quantity NUMERIC(15, 4);
+
+-- RLS
+ALTER TABLE unit_conversions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_unit_conversions ON unit_conversions FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON unit_conversions TO "user";
@@ -0,0 +1,40 @@
+-- migrations/20260207101530_create_stock_reservations.sql
+
+-- Reservas de estoque por item de pedido (etapa com stock_action = 'RESERVE').
+-- Enquanto ACTIVE, a quantidade está somada em inventory_levels.reserved_quantity.
+-- DEDUCT consome (CONSUMED); cancelamento libera (RELEASED); o prazo vencido expira (EXPIRED).
+
+CREATE TYPE stock_reservation_status AS ENUM ('ACTIVE', 'CONSUMED', 'RELEASED', 'EXPIRED');
+
+CREATE TABLE stock_reservations (
+                                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+
+                                    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+                                    order_item_id UUID NOT NULL REFERENCES order_items(id) ON DELETE CASCADE,
+                                    item_id UUID NOT NULL REFERENCES items(id) ON DELETE RESTRICT,
+                                    location_id UUID NOT NULL REFERENCES locations(id) ON DELETE RESTRICT,
+
+                                    quantity NUMERIC(15, 4) NOT NULL CHECK (quantity > 0), -- Unidade base
+                                    status stock_reservation_status NOT NULL DEFAULT 'ACTIVE',
+                                    expires_at TIMESTAMPTZ, -- Vazio = não expira
+                                    closed_at TIMESTAMPTZ,
+
+                                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Uma reserva ativa por item de pedido (reentrar na etapa não reserva de novo)
+CREATE UNIQUE INDEX idx_reservations_active_line ON stock_reservations(order_item_id) WHERE status = 'ACTIVE';
+CREATE INDEX idx_reservations_order ON stock_reservations(tenant_id, order_id);
+CREATE INDEX idx_reservations_expiring ON stock_reservations(tenant_id, expires_at) WHERE status = 'ACTIVE';
+
+-- Validade das reservas feitas pela etapa (em horas; vazio = sem prazo)
+ALTER TABLE pipeline_stages ADD COLUMN reservation_hours INTEGER CHECK (reservation_hours > 0);
+
+-- RLS
+ALTER TABLE stock_reservations ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_iso_stock_reservations ON stock_reservations FOR ALL USING (tenant_id = current_setting('app.tenant_id')::uuid);
+
+GRANT ALL ON stock_reservations TO "user";
@@ -0,0 +1,17 @@
+-- migrations/20260209113045_add_order_fulfilment_location.sql
+
+-- Local de atendimento do pedido (substitui o "primeiro local da loja").
+-- O item do pedido pode sobrescrever o local do pedido.
+ALTER TABLE orders ADD COLUMN location_id UUID REFERENCES locations(id) ON DELETE RESTRICT;
+ALTER TABLE order_items ADD COLUMN location_id UUID REFERENCES locations(id) ON DELETE RESTRICT;
+
+-- Estratégia de atendimento:
+--   FIXED      = sai todo do local escolhido
+--   POOL       = um único local do mesmo pool com saldo suficiente (o escolhido tem preferência)
+--   POOL_SPLIT = divide a linha entre os locais do pool, até cobrir a quantidade
+ALTER TABLE orders ADD COLUMN fulfilment_strategy VARCHAR(20) NOT NULL DEFAULT 'FIXED'
+    CHECK (fulfilment_strategy IN ('FIXED', 'POOL', 'POOL_SPLIT'));
+
+-- Uma linha dividida tem uma reserva ativa por local
+DROP INDEX idx_reservations_active_line;
+CREATE UNIQUE INDEX idx_reservations_active_line ON stock_reservations(order_item_id, location_id) WHERE status = 'ACTIVE';
@@ -0,0 +1,6 @@
+-- migrations/20260211090510_add_shared_pool_selling.sql
+
+-- Venda compartilhada: a venda em um local pode usar o saldo dos outros locais
+-- do mesmo stock_pool (o local da venda tem preferência).
+ALTER TABLE tenant_settings
+    ADD COLUMN shared_pool_selling BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,22 @@
+-- migrations/20260213101500_order_item_price_in_sold_unit.sql
+
+-- O preço do item do pedido passa a ficar na unidade em que foi lançado
+-- (total = unit_quantity * unit_price). Antes era convertido para a unidade base
+-- e arredondado, o que fazia o total divergir do digitado (ex: 1000 CX de 12 a 10,00 = 9.999,60).
+UPDATE order_items
+SET unit_price = ROUND(unit_price * quantity / unit_quantity, 2)
+WHERE unit_quantity IS NOT NULL
+  AND unit_quantity > 0
+  AND unit_quantity <> quantity;
+
+-- Recalcula o total dos pedidos afetados
+UPDATE orders o
+SET total_amount = (
+    SELECT COALESCE(SUM(COALESCE(oi.unit_quantity, oi.quantity) * oi.unit_price - oi.discount), 0)
+    FROM order_items oi
+    WHERE oi.order_id = o.id
+)
+WHERE EXISTS (
+    SELECT 1 FROM order_items oi
+    WHERE oi.order_id = o.id AND oi.unit_quantity IS NOT NULL AND oi.unit_quantity <> oi.quantity
+);
@@ -0,0 +1,26 @@
+-- migrations/20260215093000_add_stock_movement_sequence.sql
+
+-- Ordem de lançamento dos movimentos. created_at (NOW()) é o mesmo para todos os
+-- movimentos de uma transação, então não serve para ordenar o kardex.
+ALTER TABLE stock_movements ADD COLUMN sequence_number BIGINT;
+
+-- Movimentos existentes: mantém a ordem que o kardex já mostrava
+WITH ordered AS (
+    SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, id) AS n
+    FROM stock_movements
+)
+UPDATE stock_movements m
+SET sequence_number = ordered.n
+FROM ordered
+WHERE m.id = ordered.id;
+
+ALTER TABLE stock_movements ALTER COLUMN sequence_number SET NOT NULL;
+ALTER TABLE stock_movements ALTER COLUMN sequence_number ADD GENERATED BY DEFAULT AS IDENTITY;
+
+SELECT setval(
+    pg_get_serial_sequence('stock_movements', 'sequence_number'),
+    COALESCE((SELECT MAX(sequence_number) FROM stock_movements), 0) + 1,
+    false
+);
+
+CREATE INDEX idx_movements_item_sequence ON stock_movements (tenant_id, item_id, sequence_number);
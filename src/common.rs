@@ -1,2 +1,5 @@
 pub mod error;
-pub mod db_utils;
\ No newline at end of file
+pub mod db_utils;
+pub mod pix;
+pub mod boleto;
+pub mod cnab;
\ No newline at end of file
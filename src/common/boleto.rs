@@ -0,0 +1,417 @@
+// src/common/boleto.rs
+
+//! Boleto de cobrança (padrão FEBRABAN): código de barras de 44 posições,
+//! linha digitável e o "campo livre" de cada banco.
+//!
+//! Código de barras: Banco(3) + Moeda(1) + DV(1) + Fator de vencimento(4) + Valor(10) + Campo livre(25)
+
+use chrono::NaiveDate;
+use image::{DynamicImage, GrayImage, Luma};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{common::error::AppError, models::finance::BillingConfig};
+
+// Itaú: nessas carteiras o DAC do Nosso Número não leva agência/conta
+const ITAU_WALLETS_WITHOUT_ACCOUNT: &[&str] = &["126", "131", "146", "150", "168"];
+
+pub fn bank_name(bank_code: &str) -> &'static str {
+    match bank_code {
+        "001" => "Banco do Brasil S.A.",
+        "237" => "Bradesco",
+        "341" => "Banco Itaú S.A.",
+        _ => "",
+    }
+}
+
+/// Código do banco com o dígito, como no cabeçalho do boleto (ex: "237-2").
+pub fn bank_code_label(bank_code: &str) -> String {
+    let digit = match bank_code {
+        "001" => "9",
+        "237" => "2",
+        "341" => "7",
+        _ => "X",
+    };
+    format!("{}-{}", bank_code, digit)
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::InvalidBillingConfig(message.to_string())
+}
+
+fn digits_only(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Só dígitos, completado com zeros à esquerda. Erro se não couber.
+fn zero_pad(value: &str, len: usize, field: &str) -> Result<String, AppError> {
+    let digits = digits_only(value);
+    if digits.is_empty() || digits.len() > len {
+        return Err(invalid(&format!("{} deve ter até {} dígitos", field, len)));
+    }
+    Ok(format!("{:0>width$}", digits, width = len))
+}
+
+/// Módulo 10 (pesos 2,1 da direita para a esquerda; soma os dígitos dos produtos).
+pub fn mod10(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            let product = if i % 2 == 0 { d * 2 } else { d };
+            product / 10 + product % 10
+        })
+        .sum();
+
+    (10 - sum % 10) % 10
+}
+
+/// Módulo 11 com pesos 2..=max_weight (da direita para a esquerda). Retorna o resto.
+fn mod11_rest(digits: &str, max_weight: u32) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| d * (2 + (i as u32) % (max_weight - 1)))
+        .sum();
+
+    sum % 11
+}
+
+/// DV geral do código de barras (posição 5). Resultados 0, 10 e 11 viram 1.
+fn barcode_digit(digits: &str) -> u32 {
+    let dv = 11 - mod11_rest(digits, 9);
+    if dv == 0 || dv > 9 { 1 } else { dv }
+}
+
+/// Dias desde 07/10/1997. Ao chegar em 9999 (21/02/2025) o fator recomeça em 1000.
+pub fn due_date_factor(due_date: NaiveDate) -> Result<u32, AppError> {
+    let base = NaiveDate::from_ymd_opt(1997, 10, 7).unwrap();
+    let days = (due_date - base).num_days();
+    if days < 1000 {
+        return Err(invalid("vencimento anterior a 03/07/2000"));
+    }
+
+    let factor = if days > 9999 { (days - 10000) % 9000 + 1000 } else { days };
+    Ok(factor as u32)
+}
+
+/// Valor em centavos, 10 posições.
+fn amount_field(amount: Decimal) -> Result<String, AppError> {
+    let cents = (amount.round_dp(2) * Decimal::ONE_HUNDRED).to_i64().unwrap_or(0);
+    if cents <= 0 || cents > 9_999_999_999 {
+        return Err(invalid("valor do boleto fora do limite"));
+    }
+    Ok(format!("{:010}", cents))
+}
+
+/// Confere se o convênio tem o que o banco exige para montar o campo livre.
+pub fn validate_config(config: &BillingConfig) -> Result<(), AppError> {
+    match config.bank_code.as_str() {
+        "001" => {
+            if digits_only(&config.agreement_code).len() != 7 {
+                return Err(invalid("Banco do Brasil: o convênio deve ter 7 dígitos"));
+            }
+            zero_pad(&config.wallet, 2, "carteira")?;
+        }
+        "237" => {
+            zero_pad(&config.agency, 4, "agência")?;
+            zero_pad(&config.account_number, 7, "conta")?;
+            zero_pad(&config.wallet, 2, "carteira")?;
+        }
+        "341" => {
+            zero_pad(&config.agency, 4, "agência")?;
+            zero_pad(&config.account_number, 5, "conta")?;
+            zero_pad(&config.wallet, 3, "carteira")?;
+        }
+        other => return Err(invalid(&format!("banco {} não suportado", other))),
+    }
+    Ok(())
+}
+
+/// Nosso Número (sem DV) a partir do sequencial da conta.
+/// BB: convênio(7) + sequencial(10) | Bradesco: 11 dígitos | Itaú: 8 dígitos
+pub fn format_our_number(config: &BillingConfig, sequence: i64) -> Result<String, AppError> {
+    let sequence = sequence.to_string();
+    match config.bank_code.as_str() {
+        "001" => Ok(format!(
+            "{}{}",
+            digits_only(&config.agreement_code),
+            zero_pad(&sequence, 10, "Nosso Número")?
+        )),
+        "237" => zero_pad(&sequence, 11, "Nosso Número"),
+        "341" => zero_pad(&sequence, 8, "Nosso Número"),
+        other => Err(invalid(&format!("banco {} não suportado", other))),
+    }
+}
+
+/// Dígito do Nosso Número (vazio no BB com convênio de 7 dígitos, que não tem DV).
+pub fn our_number_digit(config: &BillingConfig, our_number: &str) -> Result<String, AppError> {
+    match config.bank_code.as_str() {
+        "237" => {
+            // Módulo 11 base 7 sobre carteira + Nosso Número. Resto 1 = "P"
+            let wallet = zero_pad(&config.wallet, 2, "carteira")?;
+            let dv = match mod11_rest(&format!("{}{}", wallet, our_number), 7) {
+                0 => "0".to_string(),
+                1 => "P".to_string(),
+                rest => (11 - rest).to_string(),
+            };
+            Ok(dv)
+        }
+        "341" => {
+            let wallet = zero_pad(&config.wallet, 3, "carteira")?;
+            let base = if ITAU_WALLETS_WITHOUT_ACCOUNT.contains(&wallet.as_str()) {
+                format!("{}{}", wallet, our_number)
+            } else {
+                format!(
+                    "{}{}{}{}",
+                    zero_pad(&config.agency, 4, "agência")?,
+                    zero_pad(&config.account_number, 5, "conta")?,
+                    wallet,
+                    our_number
+                )
+            };
+            Ok(mod10(&base).to_string())
+        }
+        _ => Ok(String::new()),
+    }
+}
+
+/// Nosso Número como é impresso no boleto (ex: "09/00000000001-P").
+pub fn our_number_label(config: &BillingConfig, our_number: &str) -> Result<String, AppError> {
+    let digit = our_number_digit(config, our_number)?;
+    Ok(match config.bank_code.as_str() {
+        "001" => our_number.to_string(),
+        _ => format!("{}/{}-{}", config.wallet, our_number, digit),
+    })
+}
+
+/// Campo livre (25 posições), específico de cada banco.
+fn free_field(config: &BillingConfig, our_number: &str) -> Result<String, AppError> {
+    let field = match config.bank_code.as_str() {
+        // Convênio de 7 dígitos: zeros(6) + Nosso Número(17) + carteira(2)
+        "001" => format!("000000{}{}", our_number, zero_pad(&config.wallet, 2, "carteira")?),
+        // Agência(4) + carteira(2) + Nosso Número(11) + conta(7) + zero
+        "237" => format!(
+            "{}{}{}{}0",
+            zero_pad(&config.agency, 4, "agência")?,
+            zero_pad(&config.wallet, 2, "carteira")?,
+            our_number,
+            zero_pad(&config.account_number, 7, "conta")?
+        ),
+        // Carteira(3) + Nosso Número(8) + DAC + agência(4) + conta(5) + DAC agência/conta + 000
+        "341" => {
+            let agency = zero_pad(&config.agency, 4, "agência")?;
+            let account = zero_pad(&config.account_number, 5, "conta")?;
+            format!(
+                "{}{}{}{}{}{}000",
+                zero_pad(&config.wallet, 3, "carteira")?,
+                our_number,
+                our_number_digit(config, our_number)?,
+                agency,
+                account,
+                mod10(&format!("{}{}", agency, account))
+            )
+        }
+        other => return Err(invalid(&format!("banco {} não suportado", other))),
+    };
+
+    if field.len() != 25 {
+        return Err(invalid("Nosso Número incompatível com o convênio"));
+    }
+    Ok(field)
+}
+
+/// Código de barras (44 dígitos).
+pub fn build_barcode(
+    config: &BillingConfig,
+    our_number: &str,
+    amount: Decimal,
+    due_date: NaiveDate,
+) -> Result<String, AppError> {
+    let factor = due_date_factor(due_date)?;
+    let value = amount_field(amount)?;
+    let free = free_field(config, our_number)?;
+
+    let without_dv = format!("{}9{:04}{}{}", config.bank_code, factor, value, free);
+    let dv = barcode_digit(&without_dv);
+
+    Ok(format!("{}{}{}", &without_dv[..4], dv, &without_dv[4..]))
+}
+
+/// Linha digitável (47 dígitos) formatada: "AAAAA.AAAAA BBBBB.BBBBBB CCCCC.CCCCCC D EEEEEEEEEEEEEE".
+pub fn digitable_line(barcode: &str) -> String {
+    let free = &barcode[19..44];
+
+    let field1 = format!("{}{}", &barcode[..4], &free[..5]);
+    let field1 = format!("{}{}", field1, mod10(&field1));
+    let field2 = format!("{}{}", &free[5..15], mod10(&free[5..15]));
+    let field3 = format!("{}{}", &free[15..25], mod10(&free[15..25]));
+
+    format!(
+        "{}.{} {}.{} {}.{} {} {}",
+        &field1[..5], &field1[5..],
+        &field2[..5], &field2[5..],
+        &field3[..5], &field3[5..],
+        &barcode[4..5],
+        &barcode[5..19]
+    )
+}
+
+/// Código de barras Intercalado 2 de 5 (ITF), como no boleto impresso.
+/// Barra fina = 1 módulo, grossa = 3 módulos; cada par de dígitos: o 1º nas barras, o 2º nos espaços.
+pub fn render_barcode_image(barcode: &str) -> DynamicImage {
+    const PATTERNS: [&[u8; 5]; 10] = [
+        b"nnwwn", b"wnnnw", b"nwnnw", b"wwnnn", b"nnwnw",
+        b"wnwnn", b"nwwnn", b"nnnww", b"wnnwn", b"nwnwn",
+    ];
+    const MODULE_PX: u32 = 2;
+    const HEIGHT_PX: u32 = 100;
+    const QUIET_ZONE: u32 = 10;
+
+    let width_of = |w: u8| if w == b'w' { 3 } else { 1 };
+
+    // (é barra?, largura em módulos)
+    let mut elements: Vec<(bool, u32)> = vec![(true, 1), (false, 1), (true, 1), (false, 1)];
+    let digits: Vec<usize> = barcode.chars().filter_map(|c| c.to_digit(10)).map(|d| d as usize).collect();
+    for pair in digits.chunks(2) {
+        let bars = PATTERNS[pair[0]];
+        let spaces = PATTERNS[*pair.get(1).unwrap_or(&0)];
+        for i in 0..5 {
+            elements.push((true, width_of(bars[i])));
+            elements.push((false, width_of(spaces[i])));
+        }
+    }
+    elements.extend([(true, 3), (false, 1), (true, 1)]);
+
+    let modules: u32 = elements.iter().map(|(_, w)| w).sum::<u32>() + QUIET_ZONE * 2;
+    let mut image = GrayImage::from_pixel(modules * MODULE_PX, HEIGHT_PX, Luma([255u8]));
+
+    let mut x = QUIET_ZONE * MODULE_PX;
+    for (is_bar, width) in elements {
+        let width_px = width * MODULE_PX;
+        if is_bar {
+            for px in x..x + width_px {
+                for y in 0..HEIGHT_PX {
+                    image.put_pixel(px, y, Luma([0u8]));
+                }
+            }
+        }
+        x += width_px;
+    }
+
+    DynamicImage::ImageLuma8(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use uuid::Uuid;
+
+    use crate::models::finance::CnabLayout;
+
+    fn config(bank_code: &str, agency: &str, account_number: &str, wallet: &str, agreement_code: &str) -> BillingConfig {
+        BillingConfig {
+            account_id: Uuid::nil(),
+            tenant_id: Uuid::nil(),
+            bank_code: bank_code.to_string(),
+            agency: agency.to_string(),
+            agency_digit: None,
+            account_number: account_number.to_string(),
+            account_digit: None,
+            wallet: wallet.to_string(),
+            agreement_code: agreement_code.to_string(),
+            layout: CnabLayout::Cnab240,
+            next_our_number: 1,
+            next_remittance_number: 1,
+            updated_at: DateTime::default(),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn mod10_weights_two_and_one_from_the_right() {
+        assert_eq!(mod10("01230067896"), 3);
+        // Soma múltipla de 10 = DV zero
+        assert_eq!(mod10("0000000000"), 0);
+    }
+
+    #[test]
+    fn mod11_barcode_digit_never_zero_or_ten() {
+        // Resto 0 ou 1 (DV 11 ou 10) vira 1
+        assert_eq!(barcode_digit("0000000000000000000000000000000000000000000"), 1);
+        assert_eq!(mod11_rest("0019101600000150000000001234567000000000117", 9), 9);
+        assert_eq!(barcode_digit("0019101600000150000000001234567000000000117"), 2);
+    }
+
+    #[test]
+    fn due_date_factor_rolls_over_in_2025() {
+        assert_eq!(due_date_factor(date(2000, 7, 3)).unwrap(), 1000);
+        assert_eq!(due_date_factor(date(2025, 2, 21)).unwrap(), 9999);
+        // 22/02/2025: o fator volta para 1000
+        assert_eq!(due_date_factor(date(2025, 2, 22)).unwrap(), 1000);
+        assert_eq!(due_date_factor(date(2025, 2, 23)).unwrap(), 1001);
+        assert_eq!(due_date_factor(date(2026, 2, 15)).unwrap(), 1358);
+        assert!(due_date_factor(date(2000, 7, 2)).is_err());
+    }
+
+    #[test]
+    fn bradesco_our_number_digit() {
+        let bradesco = config("237", "1234", "0012345", "09", "");
+        assert_eq!(our_number_digit(&bradesco, "00000000001").unwrap(), "1");
+        assert_eq!(our_number_digit(&bradesco, "00000000002").unwrap(), "P");
+        assert_eq!(our_number_digit(&bradesco, "00000000007").unwrap(), "0");
+        assert_eq!(our_number_digit(&config("237", "1234", "0012345", "19", ""), "00000000002").unwrap(), "8");
+        assert_eq!(our_number_label(&bradesco, "00000000002").unwrap(), "09/00000000002-P");
+    }
+
+    #[test]
+    fn itau_our_number_digit() {
+        assert_eq!(our_number_digit(&config("341", "0057", "12345", "109", ""), "12345678").unwrap(), "0");
+        assert_eq!(our_number_digit(&config("341", "0057", "12345", "110", ""), "12345678").unwrap(), "8");
+    }
+
+    #[test]
+    fn banco_do_brasil_barcode_and_digitable_line() {
+        let bb = config("001", "1234", "123456", "17", "1234567");
+        let our_number = format_our_number(&bb, 1).unwrap();
+        assert_eq!(our_number, "12345670000000001");
+
+        let barcode = build_barcode(&bb, &our_number, Decimal::new(15000, 2), date(2025, 3, 10)).unwrap();
+        assert_eq!(barcode, "00192101600000150000000001234567000000000117");
+        assert_eq!(digitable_line(&barcode), "00190.00009 01234.567004 00000.001172 2 10160000015000");
+    }
+
+    #[test]
+    fn bradesco_barcode_and_digitable_line() {
+        let bradesco = config("237", "1234", "0012345", "09", "");
+        let our_number = format_our_number(&bradesco, 1).unwrap();
+
+        let barcode = build_barcode(&bradesco, &our_number, Decimal::new(123456, 2), date(2024, 12, 31)).unwrap();
+        assert_eq!(barcode, "23796994700001234561234090000000000100123450");
+        assert_eq!(digitable_line(&barcode), "23791.23405 90000.000001 01001.234507 6 99470000123456");
+    }
+
+    #[test]
+    fn itau_barcode_and_digitable_line() {
+        let itau = config("341", "0057", "12345", "109", "");
+        let our_number = format_our_number(&itau, 12345678).unwrap();
+
+        let barcode = build_barcode(&itau, &our_number, Decimal::new(9990, 2), date(2026, 2, 15)).unwrap();
+        assert_eq!(barcode, "34196135800000099901091234567800057123457000");
+        assert_eq!(digitable_line(&barcode), "34191.09123 34567.800056 71234.570001 6 13580000009990");
+    }
+
+    #[test]
+    fn our_number_too_long_for_agreement_is_rejected() {
+        let bb = config("001", "1234", "123456", "17", "12345678");
+        let our_number = format_our_number(&bb, 1).unwrap();
+        assert!(build_barcode(&bb, &our_number, Decimal::new(100, 2), date(2026, 2, 15)).is_err());
+    }
+}
@@ -0,0 +1,710 @@
+// src/common/cnab.rs
+
+//! Arquivos CNAB de cobrança: remessa (boletos a registrar) e retorno
+//! (confirmações, rejeições e liquidações).
+//! CNAB 240 segue o layout FEBRABAN (segmentos P, Q e R na remessa; T e U no retorno).
+//! CNAB 400 segue o layout do Bradesco. Registros de largura fixa separados por CRLF.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{
+    common::{boleto, error::AppError, pix::ascii_text},
+    models::finance::{BillingConfig, Boleto, BoletoPayer, CnabLayout},
+};
+
+/// Beneficiário (a loja), vai no header do arquivo.
+pub struct RemittanceCompany<'a> {
+    pub name: &'a str,
+    pub document_number: &'a str,
+}
+
+/// Boleto da remessa com o pagador e os encargos por atraso.
+pub struct RemittanceItem<'a> {
+    pub boleto: &'a Boleto,
+    pub payer: &'a BoletoPayer,
+    pub late_fee_percent: Decimal,
+    pub daily_interest_percent: Decimal,
+}
+
+/// Registro de título lido do arquivo de retorno.
+#[derive(Debug)]
+pub struct ReturnRecord {
+    pub our_number: String,
+    pub occurrence_code: String,
+    pub occurrence_date: Option<NaiveDate>,
+    pub credit_date: Option<NaiveDate>,
+    // Valor recebido (com juros/multa) e a parte que é juros/multa
+    pub paid_amount: Decimal,
+    pub interest_amount: Decimal,
+}
+
+/// O que a ocorrência do retorno significa para o boleto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnOccurrence {
+    Registered,
+    Rejected,
+    Paid,
+    Cancelled,
+    Other,
+}
+
+pub fn occurrence_kind(code: &str) -> ReturnOccurrence {
+    match code {
+        "02" => ReturnOccurrence::Registered,
+        "03" => ReturnOccurrence::Rejected,
+        "06" | "15" | "17" => ReturnOccurrence::Paid,
+        "09" | "10" => ReturnOccurrence::Cancelled,
+        _ => ReturnOccurrence::Other,
+    }
+}
+
+pub fn occurrence_description(code: &str) -> String {
+    let description = match code {
+        "02" => "Entrada confirmada",
+        "03" => "Entrada rejeitada",
+        "06" => "Liquidação",
+        "09" => "Baixa",
+        "10" => "Baixa conforme instrução",
+        "12" => "Abatimento concedido",
+        "14" => "Vencimento alterado",
+        "15" => "Liquidação em cartório",
+        "17" => "Liquidação após baixa",
+        "28" => "Débito de tarifas/custas",
+        _ => "Ocorrência do banco",
+    };
+    description.to_string()
+}
+
+// =============================================================================
+//  CAMPOS DE LARGURA FIXA
+// =============================================================================
+
+/// Alfanumérico: maiúsculo, sem acento, alinhado à esquerda com brancos.
+fn alpha(value: &str, len: usize) -> String {
+    format!("{:<width$}", ascii_text(value, len).to_uppercase(), width = len)
+}
+
+/// Numérico: só dígitos, zeros à esquerda (mantém os dígitos da direita).
+fn num(value: &str, len: usize) -> String {
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = &digits[digits.len().saturating_sub(len)..];
+    format!("{:0>width$}", digits, width = len)
+}
+
+/// Valor com 2 casas implícitas.
+fn money(amount: Decimal, len: usize) -> String {
+    let cents = (amount.round_dp(2) * Decimal::ONE_HUNDRED).to_i64().unwrap_or(0).max(0);
+    num(&cents.to_string(), len)
+}
+
+/// Junta os campos de um registro.
+fn record(fields: &[&str]) -> String {
+    fields.concat()
+}
+
+fn blank(len: usize) -> String {
+    " ".repeat(len)
+}
+
+fn zeros(len: usize) -> String {
+    "0".repeat(len)
+}
+
+/// 1 = CPF, 2 = CNPJ
+fn document_kind(document: &str) -> &'static str {
+    if document.chars().filter(|c| c.is_ascii_digit()).count() == 11 { "1" } else { "2" }
+}
+
+fn payer_address(payer: &BoletoPayer) -> String {
+    match (&payer.street, &payer.number) {
+        (Some(street), Some(number)) => format!("{}, {}", street, number),
+        (Some(street), None) => street.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Juros de mora em valor por dia (é o que os bancos esperam nos dois layouts).
+fn daily_interest(item: &RemittanceItem) -> Decimal {
+    (item.boleto.amount * item.daily_interest_percent / Decimal::ONE_HUNDRED).round_dp(2)
+}
+
+// =============================================================================
+//  REMESSA
+// =============================================================================
+
+/// Monta o arquivo de remessa com os boletos informados.
+pub fn build_remittance(
+    config: &BillingConfig,
+    company: &RemittanceCompany,
+    sequence: i32,
+    generated_at: NaiveDateTime,
+    items: &[RemittanceItem],
+) -> Result<String, AppError> {
+    let lines = match config.layout {
+        CnabLayout::Cnab240 => remittance_240(config, company, sequence, generated_at, items)?,
+        CnabLayout::Cnab400 => remittance_400(config, company, sequence, generated_at, items)?,
+    };
+
+    // Registro fora da largura: o banco rejeitaria o arquivo inteiro
+    let width = match config.layout {
+        CnabLayout::Cnab240 => 240,
+        CnabLayout::Cnab400 => 400,
+    };
+    if let Some(position) = lines.iter().position(|line| line.len() != width) {
+        return Err(AppError::InvalidCnabFile(format!(
+            "registro {} da remessa com {} posições (esperado {})", position + 1, lines[position].len(), width
+        )));
+    }
+
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+/// Nosso Número no campo de 20 posições do segmento P / T, como cada banco espera.
+fn our_number_240(config: &BillingConfig, our_number: &str) -> Result<String, AppError> {
+    let digit = boleto::our_number_digit(config, our_number)?;
+    Ok(match config.bank_code.as_str() {
+        // Carteira(3) + zeros(5) + Nosso Número(11) + DV
+        "237" => format!("{}00000{}{}", num(&config.wallet, 3), our_number, digit),
+        // Carteira(3) + Nosso Número(8) + DAC
+        "341" => alpha(&format!("{}{}{}", num(&config.wallet, 3), our_number, digit), 20),
+        // BB convênio 7: Nosso Número(17)
+        _ => alpha(our_number, 20),
+    })
+}
+
+fn remittance_240(
+    config: &BillingConfig,
+    company: &RemittanceCompany,
+    sequence: i32,
+    generated_at: NaiveDateTime,
+    items: &[RemittanceItem],
+) -> Result<Vec<String>, AppError> {
+    let bank = config.bank_code.as_str();
+    let agency = num(&config.agency, 5);
+    let agency_digit = alpha(config.agency_digit.as_deref().unwrap_or(""), 1);
+    let account = num(&config.account_number, 12);
+    let account_digit = alpha(config.account_digit.as_deref().unwrap_or(""), 1);
+    let agreement = alpha(&config.agreement_code, 20);
+    let company_kind = document_kind(company.document_number);
+    let company_name = alpha(company.name, 30);
+    let date = generated_at.format("%d%m%Y").to_string();
+
+    let mut lines = Vec::new();
+
+    // Header de arquivo
+    lines.push(record(&[
+        bank, "0000", "0", &blank(9),
+        company_kind, &num(company.document_number, 14), &agreement,
+        &agency, &agency_digit, &account, &account_digit, " ",
+        &company_name, &alpha(boleto::bank_name(bank), 30), &blank(10),
+        "1", &date, &generated_at.format("%H%M%S").to_string(),
+        &num(&sequence.to_string(), 6), "103", "00000",
+        &blank(20), &blank(20), &blank(29),
+    ]));
+
+    // Header de lote (cobrança)
+    lines.push(record(&[
+        bank, "0001", "1", "R", "01", "  ", "060", " ",
+        company_kind, &num(company.document_number, 15), &agreement,
+        &agency, &agency_digit, &account, &account_digit, " ",
+        &company_name, &blank(40), &blank(40),
+        &num(&sequence.to_string(), 8), &date, &zeros(8), &blank(33),
+    ]));
+
+    let mut seq = 0;
+    let mut total = Decimal::ZERO;
+
+    for item in items {
+        let boleto = item.boleto;
+        let payer = item.payer;
+        let payer_document = payer.document_number.as_deref().unwrap_or("");
+        let interest = daily_interest(item);
+        let day_after_due = (boleto.due_date + chrono::Duration::days(1)).format("%d%m%Y").to_string();
+        total += boleto.amount;
+
+        // Segmento P: o título
+        seq += 1;
+        lines.push(record(&[
+            bank, "0001", "3", &num(&seq.to_string(), 5), "P", " ", "01",
+            &agency, &agency_digit, &account, &account_digit, " ",
+            &our_number_240(config, &boleto.our_number)?,
+            "1", "1", "1", "2", "2",
+            &alpha(&boleto.our_number, 15),
+            &boleto.due_date.format("%d%m%Y").to_string(),
+            &money(boleto.amount, 15),
+            "00000", "0", "02", "N",
+            &boleto.created_at.format("%d%m%Y").to_string(),
+            // Juros: 1 = valor por dia, 3 = isento
+            if interest > Decimal::ZERO { "1" } else { "3" },
+            &if interest > Decimal::ZERO { day_after_due.clone() } else { zeros(8) },
+            &money(interest, 15),
+            "0", &zeros(8), &zeros(15), &zeros(15), &zeros(15),
+            &alpha(&boleto.our_number, 25),
+            "3", "00", "1", "060", "09", &zeros(10), " ",
+        ]));
+
+        // Segmento Q: o pagador
+        seq += 1;
+        lines.push(record(&[
+            bank, "0001", "3", &num(&seq.to_string(), 5), "Q", " ", "01",
+            document_kind(payer_document), &num(payer_document, 15),
+            &alpha(&payer.name, 40),
+            &alpha(&payer_address(payer), 40),
+            &alpha(payer.district.as_deref().unwrap_or(""), 15),
+            &num(payer.zip_code.as_deref().unwrap_or(""), 8),
+            &alpha(payer.city.as_deref().unwrap_or(""), 15),
+            &alpha(payer.state.as_deref().unwrap_or(""), 2),
+            "0", &zeros(15), &blank(40), "000", &blank(20), &blank(8),
+        ]));
+
+        // Segmento R: multa (2 = percentual)
+        seq += 1;
+        let has_fee = item.late_fee_percent > Decimal::ZERO;
+        lines.push(record(&[
+            bank, "0001", "3", &num(&seq.to_string(), 5), "R", " ", "01",
+            "0", &zeros(8), &zeros(15), "0", &zeros(8), &zeros(15),
+            if has_fee { "2" } else { "0" },
+            &if has_fee { day_after_due } else { zeros(8) },
+            &money(if has_fee { item.late_fee_percent } else { Decimal::ZERO }, 15),
+            &blank(10), &blank(40), &blank(40), &blank(20),
+            &zeros(8), "000", "00000", " ", &zeros(12), " ", " ", "0", &blank(9),
+        ]));
+    }
+
+    // Trailer de lote: header + registros + trailer
+    let lot_records = seq + 2;
+    lines.push(record(&[
+        bank, "0001", "5", &blank(9),
+        &num(&lot_records.to_string(), 6),
+        &num(&items.len().to_string(), 6), &money(total, 17),
+        &zeros(6), &zeros(17), &zeros(6), &zeros(17), &zeros(6), &zeros(17),
+        &blank(8), &blank(117),
+    ]));
+
+    // Trailer de arquivo
+    lines.push(record(&[
+        bank, "9999", "9", &blank(9), "000001",
+        &num(&(lot_records + 2).to_string(), 6), "000000", &blank(205),
+    ]));
+
+    Ok(lines)
+}
+
+/// Layout CNAB 400 do Bradesco.
+fn remittance_400(
+    config: &BillingConfig,
+    company: &RemittanceCompany,
+    sequence: i32,
+    generated_at: NaiveDateTime,
+    items: &[RemittanceItem],
+) -> Result<Vec<String>, AppError> {
+    let mut lines = Vec::new();
+
+    lines.push(record(&[
+        "0", "1", "REMESSA", "01", &alpha("COBRANCA", 15),
+        &num(&config.agreement_code, 20), &alpha(company.name, 30),
+        "237", &alpha("BRADESCO", 15),
+        &generated_at.format("%d%m%y").to_string(), &blank(8), "MX",
+        &num(&sequence.to_string(), 7), &blank(277), &num("1", 6),
+    ]));
+
+    // Identificação da empresa no banco: zero + carteira + agência + conta + dígito
+    let company_id = record(&[
+        "0", &num(&config.wallet, 3), &num(&config.agency, 5),
+        &num(&config.account_number, 7), &alpha(config.account_digit.as_deref().unwrap_or(""), 1),
+    ]);
+
+    for (index, item) in items.iter().enumerate() {
+        let boleto = item.boleto;
+        let payer = item.payer;
+        let payer_document = payer.document_number.as_deref().unwrap_or("");
+        let has_fee = item.late_fee_percent > Decimal::ZERO;
+
+        lines.push(record(&[
+            "1", &zeros(5), "0", &zeros(5), &zeros(7), "0",
+            &company_id,
+            &alpha(&boleto.our_number, 25),
+            "000",
+            if has_fee { "2" } else { "0" },
+            &money(if has_fee { item.late_fee_percent } else { Decimal::ZERO }, 4),
+            &num(&boleto.our_number, 11),
+            &boleto::our_number_digit(config, &boleto.our_number)?,
+            &zeros(10), "2", "N", &blank(10), " ", " ", "  ",
+            "01",
+            &num(&boleto.our_number, 10),
+            &boleto.due_date.format("%d%m%y").to_string(),
+            &money(boleto.amount, 13),
+            "000", "00000", "01", "N",
+            &boleto.created_at.format("%d%m%y").to_string(),
+            "00", "00",
+            &money(daily_interest(item), 13),
+            "000000", &zeros(13), &zeros(13), &zeros(13),
+            if document_kind(payer_document) == "1" { "01" } else { "02" },
+            &num(payer_document, 14),
+            &alpha(&payer.name, 40),
+            &alpha(&payer_address(payer), 40),
+            &blank(12),
+            &num(payer.zip_code.as_deref().unwrap_or(""), 8),
+            &blank(60),
+            &num(&(index + 2).to_string(), 6),
+        ]));
+    }
+
+    lines.push(record(&["9", &blank(393), &num(&(items.len() + 2).to_string(), 6)]));
+
+    Ok(lines)
+}
+
+// =============================================================================
+//  RETORNO
+// =============================================================================
+
+/// Lê os registros de título do arquivo de retorno do banco da conta.
+pub fn parse_return(config: &BillingConfig, content: &str) -> Result<Vec<ReturnRecord>, AppError> {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|line| line.trim_end_matches(['\r', '\u{1a}']))
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+
+    if let Some(position) = lines.iter().position(|line| !line.is_ascii()) {
+        return Err(AppError::InvalidCnabFile(format!("registro {} com caracteres inválidos", position + 1)));
+    }
+
+    match config.layout {
+        CnabLayout::Cnab240 => parse_return_240(config, &lines),
+        CnabLayout::Cnab400 => parse_return_400(config, &lines),
+    }
+}
+
+/// Campo pela posição do manual (1-based, inclusiva).
+fn field(line: &str, start: usize, end: usize) -> &str {
+    &line[start - 1..end]
+}
+
+fn parse_money(value: &str) -> Decimal {
+    value.trim().parse::<i64>().map(|cents| Decimal::new(cents, 2)).unwrap_or(Decimal::ZERO)
+}
+
+/// Datas zeradas (ou em branco) = não informada.
+fn parse_date(value: &str, format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), format).ok()
+}
+
+fn check_width(lines: &[&str], width: usize) -> Result<(), AppError> {
+    match lines.iter().position(|line| line.len() < width) {
+        Some(position) => Err(AppError::InvalidCnabFile(format!(
+            "registro {} com menos de {} posições", position + 1, width
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn parse_return_240(config: &BillingConfig, lines: &[&str]) -> Result<Vec<ReturnRecord>, AppError> {
+    check_width(lines, 240)?;
+
+    let header = lines.first()
+        .ok_or(AppError::InvalidCnabFile("arquivo vazio".to_string()))?;
+    if field(header, 8, 8) != "0" || field(header, 143, 143) != "2" {
+        return Err(AppError::InvalidCnabFile("não é um arquivo de retorno CNAB 240".to_string()));
+    }
+    if field(header, 1, 3) != config.bank_code {
+        return Err(AppError::InvalidCnabFile(format!("arquivo do banco {}", field(header, 1, 3))));
+    }
+
+    let mut records = Vec::new();
+    let mut segment_t: Option<(String, String)> = None;
+
+    for line in lines.iter().filter(|line| field(line, 8, 8) == "3") {
+        match field(line, 14, 14) {
+            "T" => {
+                let company_use = field(line, 106, 130).trim();
+                let our_number = if company_use.is_empty() {
+                    // Sem o "uso da empresa": extrai do campo Nosso Número (como foi na remessa)
+                    let raw = field(line, 38, 57);
+                    match config.bank_code.as_str() {
+                        "237" => raw[8..19].to_string(),
+                        "341" => raw[3..11].to_string(),
+                        _ => raw[..17].to_string(),
+                    }
+                } else {
+                    company_use.to_string()
+                };
+                segment_t = Some((our_number, field(line, 16, 17).to_string()));
+            }
+            "U" => {
+                // O segmento U completa o T anterior
+                if let Some((our_number, occurrence_code)) = segment_t.take() {
+                    records.push(ReturnRecord {
+                        our_number,
+                        occurrence_code,
+                        occurrence_date: parse_date(field(line, 138, 145), "%d%m%Y"),
+                        credit_date: parse_date(field(line, 146, 153), "%d%m%Y"),
+                        paid_amount: parse_money(field(line, 78, 92)),
+                        interest_amount: parse_money(field(line, 18, 32)),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(records)
+}
+
+/// Retorno CNAB 400 do Bradesco.
+fn parse_return_400(config: &BillingConfig, lines: &[&str]) -> Result<Vec<ReturnRecord>, AppError> {
+    check_width(lines, 400)?;
+
+    let header = lines.first()
+        .ok_or(AppError::InvalidCnabFile("arquivo vazio".to_string()))?;
+    if field(header, 1, 2) != "02" {
+        return Err(AppError::InvalidCnabFile("não é um arquivo de retorno CNAB 400".to_string()));
+    }
+    if field(header, 77, 79) != config.bank_code {
+        return Err(AppError::InvalidCnabFile(format!("arquivo do banco {}", field(header, 77, 79))));
+    }
+
+    let records = lines
+        .iter()
+        .filter(|line| field(line, 1, 1) == "1")
+        .map(|line| {
+            let company_use = field(line, 38, 62).trim();
+            let our_number = if company_use.is_empty() {
+                field(line, 71, 81).to_string()
+            } else {
+                company_use.to_string()
+            };
+
+            ReturnRecord {
+                our_number,
+                occurrence_code: field(line, 109, 110).to_string(),
+                occurrence_date: parse_date(field(line, 111, 116), "%d%m%y"),
+                credit_date: parse_date(field(line, 296, 301), "%d%m%y"),
+                paid_amount: parse_money(field(line, 254, 266)),
+                interest_amount: parse_money(field(line, 267, 279)),
+            }
+        })
+        .collect();
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveTime};
+    use uuid::Uuid;
+
+    use crate::models::finance::BoletoStatus;
+
+    const REMITTANCE_240: &str = include_str!("../../tests/fixtures/cnab/remessa_240_itau.REM");
+    const REMITTANCE_400: &str = include_str!("../../tests/fixtures/cnab/remessa_400_bradesco.REM");
+    const RETURN_240: &str = include_str!("../../tests/fixtures/cnab/retorno_240_itau.RET");
+    const RETURN_400: &str = include_str!("../../tests/fixtures/cnab/retorno_400_bradesco.RET");
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn itau() -> BillingConfig {
+        BillingConfig {
+            account_id: Uuid::nil(),
+            tenant_id: Uuid::nil(),
+            bank_code: "341".to_string(),
+            agency: "0057".to_string(),
+            agency_digit: None,
+            account_number: "12345".to_string(),
+            account_digit: Some("7".to_string()),
+            wallet: "109".to_string(),
+            agreement_code: String::new(),
+            layout: CnabLayout::Cnab240,
+            next_our_number: 1,
+            next_remittance_number: 1,
+            updated_at: DateTime::default(),
+        }
+    }
+
+    fn bradesco() -> BillingConfig {
+        BillingConfig {
+            bank_code: "237".to_string(),
+            agency: "1234".to_string(),
+            account_number: "0012345".to_string(),
+            account_digit: Some("6".to_string()),
+            wallet: "09".to_string(),
+            agreement_code: "4567890".to_string(),
+            layout: CnabLayout::Cnab400,
+            ..itau()
+        }
+    }
+
+    fn boleto(our_number: &str, amount: Decimal, due_date: NaiveDate) -> Boleto {
+        let created_at = date(2026, 2, 2).and_time(NaiveTime::MIN).and_utc();
+        Boleto {
+            id: Uuid::nil(),
+            tenant_id: Uuid::nil(),
+            title_id: Uuid::nil(),
+            account_id: Uuid::nil(),
+            our_number: our_number.to_string(),
+            amount,
+            due_date,
+            barcode: String::new(),
+            digitable_line: String::new(),
+            status: BoletoStatus::Pending,
+            remittance_id: None,
+            paid_amount: None,
+            paid_date: None,
+            movement_id: None,
+            bank_message: None,
+            created_by: None,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    fn payers() -> [BoletoPayer; 2] {
+        [
+            BoletoPayer {
+                name: "João da Conceição".to_string(),
+                document_number: Some("123.456.789-09".to_string()),
+                street: Some("Rua das Acácias".to_string()),
+                number: Some("120".to_string()),
+                district: Some("Centro".to_string()),
+                city: Some("São Paulo".to_string()),
+                state: Some("SP".to_string()),
+                zip_code: Some("01001-000".to_string()),
+            },
+            BoletoPayer {
+                name: "Comércio Exemplo Ltda".to_string(),
+                document_number: Some("11.222.333/0001-81".to_string()),
+                street: None,
+                number: None,
+                district: None,
+                city: None,
+                state: None,
+                zip_code: None,
+            },
+        ]
+    }
+
+    fn company() -> RemittanceCompany<'static> {
+        RemittanceCompany { name: "Loja Modelo Ltda", document_number: "12.345.678/0001-95" }
+    }
+
+    fn generated_at() -> NaiveDateTime {
+        date(2026, 2, 10).and_hms_opt(8, 30, 0).unwrap()
+    }
+
+    fn remittance(config: &BillingConfig, boletos: &[Boleto; 2]) -> Result<String, AppError> {
+        let payers = payers();
+        let items = [
+            RemittanceItem {
+                boleto: &boletos[0],
+                payer: &payers[0],
+                late_fee_percent: Decimal::new(2, 0),
+                daily_interest_percent: Decimal::new(33, 3),
+            },
+            RemittanceItem {
+                boleto: &boletos[1],
+                payer: &payers[1],
+                late_fee_percent: Decimal::ZERO,
+                daily_interest_percent: Decimal::ZERO,
+            },
+        ];
+        build_remittance(config, &company(), 12, generated_at(), &items)
+    }
+
+    #[test]
+    fn remittance_240_matches_fixture() {
+        let boletos = [
+            boleto("12345678", Decimal::new(9990, 2), date(2026, 2, 15)),
+            boleto("12345679", Decimal::new(123456, 2), date(2026, 3, 1)),
+        ];
+        let content = remittance(&itau(), &boletos).unwrap();
+
+        assert!(content.split_terminator("\r\n").all(|line| line.len() == 240));
+        assert_eq!(content, REMITTANCE_240);
+    }
+
+    #[test]
+    fn remittance_400_matches_fixture() {
+        let boletos = [
+            boleto("00000000001", Decimal::new(15000, 2), date(2026, 2, 15)),
+            boleto("00000000002", Decimal::new(123456, 2), date(2026, 3, 1)),
+        ];
+        let content = remittance(&bradesco(), &boletos).unwrap();
+
+        assert!(content.split_terminator("\r\n").all(|line| line.len() == 400));
+        assert_eq!(content, REMITTANCE_400);
+    }
+
+    #[test]
+    fn remittance_with_field_overflow_is_rejected() {
+        // Nosso Número maior que o campo do segmento P: o registro passaria de 240 posições
+        let boletos = [
+            boleto("1234567890123456789012", Decimal::new(9990, 2), date(2026, 2, 15)),
+            boleto("12345679", Decimal::new(123456, 2), date(2026, 3, 1)),
+        ];
+        let config = BillingConfig { layout: CnabLayout::Cnab240, ..bradesco() };
+        assert!(matches!(remittance(&config, &boletos), Err(AppError::InvalidCnabFile(_))));
+    }
+
+    #[test]
+    fn return_240_reads_segments_t_and_u() {
+        let records = parse_return(&itau(), RETURN_240).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let paid = &records[0];
+        assert_eq!(paid.our_number, "12345678");
+        assert_eq!(paid.occurrence_code, "06");
+        assert_eq!(occurrence_kind(&paid.occurrence_code), ReturnOccurrence::Paid);
+        assert_eq!(paid.occurrence_date, Some(date(2026, 2, 16)));
+        assert_eq!(paid.credit_date, Some(date(2026, 2, 17)));
+        assert_eq!(paid.paid_amount, Decimal::new(10122, 2));
+        assert_eq!(paid.interest_amount, Decimal::new(132, 2));
+
+        // Sem o "uso da empresa": Nosso Número extraído do campo do banco
+        let confirmed = &records[1];
+        assert_eq!(confirmed.our_number, "12345679");
+        assert_eq!(confirmed.occurrence_code, "02");
+        assert_eq!(confirmed.credit_date, None);
+        assert_eq!(confirmed.paid_amount, Decimal::ZERO);
+    }
+
+    #[test]
+    fn return_400_reads_detail_records() {
+        let records = parse_return(&bradesco(), RETURN_400).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let paid = &records[0];
+        assert_eq!(paid.our_number, "00000000001");
+        assert_eq!(paid.occurrence_code, "06");
+        assert_eq!(paid.occurrence_date, Some(date(2026, 2, 16)));
+        assert_eq!(paid.credit_date, Some(date(2026, 2, 17)));
+        assert_eq!(paid.paid_amount, Decimal::new(15300, 2));
+        assert_eq!(paid.interest_amount, Decimal::new(300, 2));
+
+        assert_eq!(records[1].our_number, "00000000002");
+        assert_eq!(records[1].credit_date, None);
+        assert_eq!(records[2].occurrence_code, "03");
+        assert_eq!(occurrence_kind(&records[2].occurrence_code), ReturnOccurrence::Rejected);
+    }
+
+    #[test]
+    fn return_from_another_bank_or_layout_is_rejected() {
+        assert!(matches!(parse_return(&bradesco(), RETURN_240), Err(AppError::InvalidCnabFile(_))));
+        let itau_400 = BillingConfig { layout: CnabLayout::Cnab400, ..itau() };
+        assert!(matches!(parse_return(&itau_400, RETURN_400), Err(AppError::InvalidCnabFile(_))));
+        // Remessa não é retorno
+        assert!(matches!(parse_return(&itau(), REMITTANCE_240), Err(AppError::InvalidCnabFile(_))));
+    }
+
+    #[test]
+    fn truncated_return_record_is_rejected() {
+        let mut lines: Vec<String> = RETURN_400.lines().map(str::to_string).collect();
+        lines[1].truncate(300);
+        let truncated = lines.join("\r\n");
+        assert!(matches!(parse_return(&bradesco(), &truncated), Err(AppError::InvalidCnabFile(_))));
+    }
+}
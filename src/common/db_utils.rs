@@ -1,3 +1,5 @@
+use uuid::Uuid;
+
 use crate::common::error::{AppError};
 use crate::config::AppState;
 use crate::middleware::auth::AuthenticatedUser;
@@ -34,3 +36,24 @@ pub(crate) async fn get_rls_connection(
 
     Ok(conn)
 }
+
+
+// ---
+// Helper RLS para rotinas em segundo plano (sem usuário logado)
+// ---
+/// Abre uma transação com o tenant definido para o RLS.
+/// O `set_config(..., true)` vale apenas até o fim da transação.
+pub(crate) async fn begin_tenant_transaction(
+    pool: &sqlx::PgPool,
+    tenant_id: Uuid,
+) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, AppError> {
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+        .bind(tenant_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(tx)
+}
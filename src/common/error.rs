@@ -105,6 +105,76 @@ pub enum AppError {
     #[error("Cliente com documento duplicado")]
     CustomerDocumentAlreadyExists(String), // Recebe o número do doc
 
+    // --- Financeiro ---
+    #[error("O título '{0}' não está em aberto")]
+    TitleNotOpen(String),
+
+    #[error("O valor informado excede o saldo em aberto: {0}")]
+    AmountExceedsBalance(String),
+
+    #[error("A conta '{0}' está inativa")]
+    AccountInactive(String),
+
+    #[error("A movimentação '{0}' já foi estornada")]
+    MovementAlreadyReversed(String),
+
+    #[error("Já existe uma condição de pagamento com o nome: {0}")]
+    PaymentTermNameAlreadyExists(String),
+
+    #[error("Já existe um caixa aberto: {0}")]
+    CashSessionAlreadyOpen(String),
+
+    #[error("O caixa '{0}' está fechado")]
+    CashSessionClosed(String),
+
+    #[error("Extrato bancário inválido: {0}")]
+    InvalidBankStatement(String),
+
+    #[error("A linha do extrato '{0}' já foi revisada")]
+    BankLineAlreadyReviewed(String),
+
+    #[error("A movimentação '{0}' já está conciliada com outra linha do extrato")]
+    MovementAlreadyReconciled(String),
+
+    #[error("Já existe uma categoria financeira com o código: {0}")]
+    FinancialCategoryCodeAlreadyExists(String),
+
+    #[error("Pix não configurado: falta o campo '{0}' nas configurações")]
+    PixNotConfigured(String),
+
+    #[error("Convênio de cobrança inválido: {0}")]
+    InvalidBillingConfig(String),
+
+    #[error("Arquivo CNAB inválido: {0}")]
+    InvalidCnabFile(String),
+
+    #[error("O título '{0}' já tem um boleto em aberto")]
+    BoletoAlreadyExists(String),
+
+    #[error("Estoque insuficiente: {0}")]
+    InsufficientStock(String),
+
+    #[error("A transferência '{0}' não está em trânsito")]
+    StockTransferNotInTransit(String),
+
+    #[error("Já existe uma contagem aberta no local: {0}")]
+    InventoryCountAlreadyOpen(String),
+
+    #[error("A contagem '{0}' não está aberta")]
+    InventoryCountNotOpen(String),
+
+    #[error("A correção da contagem deixaria o estoque negativo: {0}")]
+    InventoryCountNegativeStock(String),
+
+    #[error("A ficha técnica do item '{0}' forma um ciclo")]
+    CompositionCycle(String),
+
+    #[error("A ordem de produção '{0}' não está planejada")]
+    ProductionOrderNotPlanned(String),
+
+    #[error("A reserva '{0}' não está ativa")]
+    StockReservationNotActive(String),
+
 }
 
 // --- Estrutura de Resposta da API (JSON) ---
@@ -274,6 +344,98 @@ impl AppError {
                 let t = get_template("CustomerDocumentAlreadyExists");
                 (StatusCode::CONFLICT, t.replace("{value}", &doc), None)
             }
+            AppError::TitleNotOpen(val) => {
+                let t = get_template("TitleNotOpen");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::AmountExceedsBalance(val) => {
+                let t = get_template("AmountExceedsBalance");
+                (StatusCode::BAD_REQUEST, t.replace("{value}", &val), None)
+            }
+            AppError::AccountInactive(val) => {
+                let t = get_template("AccountInactive");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::MovementAlreadyReversed(val) => {
+                let t = get_template("MovementAlreadyReversed");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::PaymentTermNameAlreadyExists(name) => {
+                let t = get_template("PaymentTermNameAlreadyExists");
+                (StatusCode::CONFLICT, t.replace("{value}", &name), None)
+            }
+            AppError::CashSessionAlreadyOpen(val) => {
+                let t = get_template("CashSessionAlreadyOpen");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::CashSessionClosed(val) => {
+                let t = get_template("CashSessionClosed");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::InvalidBankStatement(val) => {
+                let t = get_template("InvalidBankStatement");
+                (StatusCode::BAD_REQUEST, t.replace("{value}", &val), None)
+            }
+            AppError::BankLineAlreadyReviewed(val) => {
+                let t = get_template("BankLineAlreadyReviewed");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::MovementAlreadyReconciled(val) => {
+                let t = get_template("MovementAlreadyReconciled");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::FinancialCategoryCodeAlreadyExists(code) => {
+                let t = get_template("FinancialCategoryCodeAlreadyExists");
+                (StatusCode::CONFLICT, t.replace("{value}", &code), None)
+            }
+            AppError::PixNotConfigured(field) => {
+                let t = get_template("PixNotConfigured");
+                (StatusCode::BAD_REQUEST, t.replace("{value}", &field), None)
+            }
+            AppError::InvalidBillingConfig(val) => {
+                let t = get_template("InvalidBillingConfig");
+                (StatusCode::BAD_REQUEST, t.replace("{value}", &val), None)
+            }
+            AppError::InvalidCnabFile(val) => {
+                let t = get_template("InvalidCnabFile");
+                (StatusCode::BAD_REQUEST, t.replace("{value}", &val), None)
+            }
+            AppError::BoletoAlreadyExists(val) => {
+                let t = get_template("BoletoAlreadyExists");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::InsufficientStock(val) => {
+                let t = get_template("InsufficientStock");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::StockTransferNotInTransit(val) => {
+                let t = get_template("StockTransferNotInTransit");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::InventoryCountAlreadyOpen(val) => {
+                let t = get_template("InventoryCountAlreadyOpen");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::InventoryCountNotOpen(val) => {
+                let t = get_template("InventoryCountNotOpen");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::InventoryCountNegativeStock(val) => {
+                let t = get_template("InventoryCountNegativeStock");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::CompositionCycle(val) => {
+                let t = get_template("CompositionCycle");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::ProductionOrderNotPlanned(val) => {
+                let t = get_template("ProductionOrderNotPlanned");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
+            AppError::StockReservationNotActive(val) => {
+                let t = get_template("StockReservationNotActive");
+                (StatusCode::CONFLICT, t.replace("{value}", &val), None)
+            }
 
             // Erros Internos (escondemos os detalhes técnicos do usuário)
             _ => (StatusCode::INTERNAL_SERVER_ERROR, get_template("InternalServerError"), None),
@@ -0,0 +1,254 @@
+// src/common/pix.rs
+
+//! Pix "Copia e Cola" (BR Code): payload EMV-MPM estático conforme o Manual de
+//! Padrões para Iniciação do Pix (Banco Central). Cada campo é um TLV:
+//! ID (2 dígitos) + tamanho (2 dígitos) + valor. O payload termina no CRC16.
+
+use image::{DynamicImage, ImageOutputFormat, Luma};
+use qrcode::QrCode;
+use rust_decimal::Decimal;
+
+use crate::{common::error::AppError, models::settings::TenantSettings};
+
+// Limites do BR Code
+const MAX_MERCHANT_NAME: usize = 25;
+const MAX_MERCHANT_CITY: usize = 15;
+const MAX_TXID: usize = 25;
+
+/// Monta um campo TLV. Os valores são ASCII (ver `ascii_text`), então bytes = caracteres.
+fn emv_field(id: &str, value: &str) -> String {
+    format!("{}{:02}{}", id, value.len(), value)
+}
+
+/// CRC16-CCITT (polinômio 0x1021, valor inicial 0xFFFF), exigido no campo 63.
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Remove acentos e descarta o que não for ASCII imprimível (nome e cidade do recebedor).
+pub fn ascii_text(text: &str, max_len: usize) -> String {
+    text.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'É' | 'È' | 'Ê' | 'Ë' => 'E',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+            'ç' => 'c',
+            'Ç' => 'C',
+            'ñ' => 'n',
+            'Ñ' => 'N',
+            other => other,
+        })
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .take(max_len)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// O txid só aceita letras e números (até 25). Sem txid, o padrão é "***".
+fn sanitize_txid(txid: &str) -> String {
+    let txid: String = txid.chars().filter(|c| c.is_ascii_alphanumeric()).take(MAX_TXID).collect();
+    if txid.is_empty() { "***".to_string() } else { txid }
+}
+
+/// Formata a chave como o DICT espera: CPF/CNPJ só dígitos, telefone em +55...,
+/// e-mail em minúsculas. Sem tipo cadastrado, deduz pelo formato.
+pub fn normalize_pix_key(key: &str, key_type: Option<&str>) -> String {
+    let key = key.trim();
+    let digits: String = key.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let key_type = key_type.map(|t| t.trim().to_uppercase()).unwrap_or_else(|| {
+        if key.contains('@') {
+            "EMAIL".to_string()
+        } else if key.starts_with('+') {
+            "PHONE".to_string()
+        } else if (digits.len() == 11 || digits.len() == 14)
+            && key.chars().all(|c| c.is_ascii_digit() || ".-/ ".contains(c))
+        {
+            "CPF".to_string() // CPF ou CNPJ: o formato é o mesmo (só dígitos)
+        } else {
+            "EVP".to_string()
+        }
+    });
+
+    match key_type.as_str() {
+        "CPF" | "CNPJ" => digits,
+        "PHONE" if digits.starts_with("55") && key.starts_with('+') => format!("+{}", digits),
+        "PHONE" => format!("+55{}", digits),
+        "EMAIL" => key.to_lowercase(),
+        _ => key.to_lowercase(), // EVP (chave aleatória)
+    }
+}
+
+/// Payload "Copia e Cola" de um Pix estático.
+/// Sem valor, o pagador digita o valor no app do banco.
+pub fn build_pix_payload(
+    key: &str,
+    merchant_name: &str,
+    merchant_city: &str,
+    amount: Option<Decimal>,
+    txid: &str,
+) -> String {
+    let merchant_account = format!(
+        "{}{}",
+        emv_field("00", "br.gov.bcb.pix"),
+        emv_field("01", key)
+    );
+
+    let mut payload = String::new();
+    payload.push_str(&emv_field("00", "01"));                // Payload Format Indicator
+    payload.push_str(&emv_field("26", &merchant_account));   // Merchant Account Information (Pix)
+    payload.push_str(&emv_field("52", "0000"));              // Merchant Category Code
+    payload.push_str(&emv_field("53", "986"));               // Moeda: BRL
+    if let Some(amount) = amount.filter(|a| *a > Decimal::ZERO) {
+        payload.push_str(&emv_field("54", &format!("{:.2}", amount.round_dp(2))));
+    }
+    payload.push_str(&emv_field("58", "BR"));
+    payload.push_str(&emv_field("59", &ascii_text(merchant_name, MAX_MERCHANT_NAME)));
+    payload.push_str(&emv_field("60", &ascii_text(merchant_city, MAX_MERCHANT_CITY)));
+    payload.push_str(&emv_field("62", &emv_field("05", &sanitize_txid(txid))));
+
+    // O CRC cobre o payload inteiro, incluindo o ID e o tamanho do próprio campo 63
+    payload.push_str("6304");
+    let crc = crc16_ccitt(payload.as_bytes());
+    payload.push_str(&format!("{:04X}", crc));
+
+    payload
+}
+
+/// Payload a partir das configurações da loja. Exige chave Pix, nome e cidade.
+pub fn build_pix_payload_from_settings(
+    settings: &TenantSettings,
+    amount: Option<Decimal>,
+    txid: &str,
+) -> Result<String, AppError> {
+    fn non_empty(value: &Option<String>) -> Option<&str> {
+        value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+    }
+
+    let key = non_empty(&settings.pix_key)
+        .ok_or(AppError::PixNotConfigured("pixKey".to_string()))?;
+    let merchant_name = non_empty(&settings.company_name)
+        .ok_or(AppError::PixNotConfigured("companyName".to_string()))?;
+    let merchant_city = non_empty(&settings.city)
+        .ok_or(AppError::PixNotConfigured("city".to_string()))?;
+
+    let key = normalize_pix_key(key, settings.pix_key_type.as_deref());
+    if key.len() > 77 {
+        return Err(AppError::PixNotConfigured("pixKey".to_string()));
+    }
+
+    Ok(build_pix_payload(&key, merchant_name, merchant_city, amount, txid))
+}
+
+/// QR Code do payload como imagem (para o PDF).
+pub fn render_qr_image(payload: &str) -> Result<DynamicImage, AppError> {
+    let code = QrCode::new(payload.as_bytes())
+        .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
+
+    let image_buffer = code.render::<Luma<u8>>().build();
+    Ok(DynamicImage::ImageLuma8(image_buffer))
+}
+
+/// QR Code do payload em PNG.
+pub fn render_qr_png(payload: &str) -> Result<Vec<u8>, AppError> {
+    let mut png = Vec::new();
+    render_qr_image(payload)?
+        .write_to(&mut png, ImageOutputFormat::Png)
+        .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
+
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_ccitt_check_value() {
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+        assert_eq!(crc16_ccitt(b""), 0xFFFF);
+    }
+
+    #[test]
+    fn static_payload_from_the_bcb_manual() {
+        let payload = build_pix_payload("123e4567-e12b-12d1-a456-426655440000", "Fulano de Tal", "BRASILIA", None, "");
+        assert_eq!(
+            payload,
+            "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-4266554400005204000053039865802BR\
+             5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+        );
+    }
+
+    #[test]
+    fn payload_with_amount_truncates_name_city_and_txid() {
+        let payload = build_pix_payload(
+            "+5511987654321",
+            "Loja Modelo Ltda Comércio e Serviços",
+            "São José dos Campos",
+            Some(Decimal::new(12345, 1)),
+            "PEDIDO-1234",
+        );
+        assert_eq!(
+            payload,
+            "00020126360014br.gov.bcb.pix0114+551198765432152040000530398654071234.505802BR\
+             5925Loja Modelo Ltda Comercio6015Sao Jose dos Ca62140510PEDIDO12346304405E"
+        );
+
+        // O CRC confere com o próprio payload
+        let (body, crc) = payload.split_at(payload.len() - 4);
+        assert_eq!(format!("{:04X}", crc16_ccitt(body.as_bytes())), crc);
+    }
+
+    #[test]
+    fn zero_amount_is_left_to_the_payer() {
+        let payload = build_pix_payload("12345678909", "Loja", "Recife", Some(Decimal::ZERO), "***");
+        assert!(!payload.contains("5404"));
+        assert!(payload.contains("62070503***"));
+    }
+
+    #[test]
+    fn ascii_text_strips_accents_and_truncates() {
+        assert_eq!(ascii_text("Padaria Pão de Açúcar", 25), "Padaria Pao de Acucar");
+        assert_eq!(ascii_text("Ribeirão Preto", 15), "Ribeirao Preto");
+        // Corta no limite e não deixa espaço sobrando no fim
+        assert_eq!(ascii_text("São Bernardo do Campo", 15), "Sao Bernardo do");
+        assert_eq!(ascii_text("Mercado  Central", 8), "Mercado");
+        assert_eq!(ascii_text("Loja\t™ Nova", 25), "Loja Nova");
+    }
+
+    #[test]
+    fn normalize_pix_key_by_declared_type() {
+        assert_eq!(normalize_pix_key("123.456.789-09", Some("CPF")), "12345678909");
+        assert_eq!(normalize_pix_key("11.222.333/0001-81", Some("cnpj")), "11222333000181");
+        assert_eq!(normalize_pix_key("(11) 98765-4321", Some("PHONE")), "+5511987654321");
+        assert_eq!(normalize_pix_key("+55 (11) 98765-4321", Some("PHONE")), "+5511987654321");
+        assert_eq!(normalize_pix_key(" Financeiro@Loja.com.BR ", Some("EMAIL")), "financeiro@loja.com.br");
+    }
+
+    #[test]
+    fn normalize_pix_key_without_type() {
+        assert_eq!(normalize_pix_key("123.456.789-09", None), "12345678909");
+        assert_eq!(normalize_pix_key("11.222.333/0001-81", None), "11222333000181");
+        assert_eq!(normalize_pix_key("+55 11 98765-4321", None), "+5511987654321");
+        assert_eq!(normalize_pix_key("Vendas@Loja.com", None), "vendas@loja.com");
+        assert_eq!(
+            normalize_pix_key("123E4567-E12B-12D1-A456-426655440000", None),
+            "123e4567-e12b-12d1-a456-426655440000"
+        );
+    }
+}
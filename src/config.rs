@@ -10,6 +10,8 @@ use crate::services::{
     operation_service::OperationsService,
     dashboard_service::DashboardService,
     document_service::DocumentService,
+    reconciliation_service::ReconciliationService,
+    billing_service::BillingService,
 };
 
 // Importe dos repositórios
@@ -42,6 +44,8 @@ pub struct AppState {
     pub finance_service: FinanceService,
     pub dashboard_service: DashboardService,
     pub document_service: DocumentService,
+    pub reconciliation_service: ReconciliationService,
+    pub billing_service: BillingService,
     pub settings_repo: SettingsRepository,
 }
 
@@ -104,19 +108,33 @@ impl AppState {
             db_pool.clone()
         );
 
-        let finance_service = FinanceService::new(finance_repo.clone());
-        let inventory_service = InventoryService::new(inventory_repo.clone(), db_pool.clone());
+        let finance_service = FinanceService::new(finance_repo.clone(), settings_repo.clone());
+        let inventory_service = InventoryService::new(inventory_repo.clone(), settings_repo.clone(), db_pool.clone());
+        let reconciliation_service = ReconciliationService::new(
+            crate::db::ReconciliationRepository::new(db_pool.clone()),
+            finance_repo.clone(),
+            finance_service.clone()
+        );
+        let billing_service = BillingService::new(
+            crate::db::BillingRepository::new(db_pool.clone()),
+            finance_repo.clone(),
+            settings_repo.clone(),
+            finance_service.clone()
+        );
         let document_service = DocumentService::new(
             operations_repo.clone(),
-            settings_repo.clone()
+            settings_repo.clone(),
+            finance_service.clone(),
+            billing_service.clone()
         );
         let operations_service = OperationsService::new(operations_repo, inventory_service.clone(), finance_service.clone());
-        let dashboard_service = DashboardService::new(dashboard_repo);
+        let dashboard_service = DashboardService::new(dashboard_repo, finance_service.clone());
 
         // [CORREÇÃO] TenantService agora recebe rbac_repo que já foi criado acima
         let tenant_service = TenantService::new(
             tenant_repo.clone(),
             rbac_repo.clone(), // Agora esta variável existe!
+            finance_service.clone(),
             db_pool.clone()
         );
 
@@ -143,6 +161,8 @@ impl AppState {
             finance_service,
             dashboard_service,
             document_service,
+            reconciliation_service,
+            billing_service,
             settings_repo,
         })
     }
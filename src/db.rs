@@ -13,6 +13,10 @@ pub mod finance_repo;
 pub mod dashboard_repo;
 pub mod settings_repo;
 pub use settings_repo::SettingsRepository;
+pub mod reconciliation_repo;
+pub use reconciliation_repo::ReconciliationRepository;
+pub mod billing_repo;
+pub use billing_repo::BillingRepository;
 
 pub use dashboard_repo::DashboardRepository;
 
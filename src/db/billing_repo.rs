@@ -0,0 +1,618 @@
+// src/db/billing_repo.rs
+
+use sqlx::{PgPool, Postgres, Executor};
+use uuid::Uuid;
+use rust_decimal::Decimal;
+use chrono::NaiveDate;
+use crate::{
+    common::error::AppError,
+    models::finance::{
+        BillingConfig, Boleto, BoletoPayer, BoletoStatus, CnabFile, CnabFileKind, CnabLayout
+    },
+};
+
+#[derive(Clone)]
+pub struct BillingRepository {
+    pool: PgPool,
+}
+
+impl BillingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // =========================================================================
+    //  CONVÊNIO DE COBRANÇA
+    // =========================================================================
+
+    pub async fn get_config<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<BillingConfig>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let config = sqlx::query_as!(
+            BillingConfig,
+            r#"
+            SELECT
+                account_id, tenant_id, bank_code, agency, agency_digit,
+                account_number, account_digit, wallet, agreement_code,
+                layout as "layout: CnabLayout",
+                next_our_number, next_remittance_number, updated_at
+            FROM billing_configs
+            WHERE tenant_id = $1 AND account_id = $2
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(config)
+    }
+
+    /// Cria ou atualiza o convênio. Sem `next_our_number`, mantém a sequência atual.
+    pub async fn upsert_config<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        bank_code: &str,
+        agency: &str,
+        agency_digit: Option<&str>,
+        account_number: &str,
+        account_digit: Option<&str>,
+        wallet: &str,
+        agreement_code: &str,
+        layout: CnabLayout,
+        next_our_number: Option<i64>,
+    ) -> Result<BillingConfig, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let config = sqlx::query_as!(
+            BillingConfig,
+            r#"
+            INSERT INTO billing_configs (
+                account_id, tenant_id, bank_code, agency, agency_digit,
+                account_number, account_digit, wallet, agreement_code, layout, next_our_number
+            )
+            VALUES ($2, $1, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11::BIGINT, 1))
+            ON CONFLICT (account_id)
+            DO UPDATE SET
+                bank_code = EXCLUDED.bank_code,
+                agency = EXCLUDED.agency,
+                agency_digit = EXCLUDED.agency_digit,
+                account_number = EXCLUDED.account_number,
+                account_digit = EXCLUDED.account_digit,
+                wallet = EXCLUDED.wallet,
+                agreement_code = EXCLUDED.agreement_code,
+                layout = EXCLUDED.layout,
+                next_our_number = COALESCE($11, billing_configs.next_our_number),
+                updated_at = NOW()
+            RETURNING
+                account_id, tenant_id, bank_code, agency, agency_digit,
+                account_number, account_digit, wallet, agreement_code,
+                layout as "layout: CnabLayout",
+                next_our_number, next_remittance_number, updated_at
+            "#,
+            tenant_id,
+            account_id,
+            bank_code,
+            agency,
+            agency_digit,
+            account_number,
+            account_digit,
+            wallet,
+            agreement_code,
+            layout as CnabLayout,
+            next_our_number
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(config)
+    }
+
+    /// Reserva o próximo Nosso Número (o UPDATE trava a linha até o fim da transação).
+    pub async fn take_our_number<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<i64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let sequence = sqlx::query_scalar!(
+            r#"
+            UPDATE billing_configs
+            SET next_our_number = next_our_number + 1
+            WHERE tenant_id = $1 AND account_id = $2
+            RETURNING next_our_number - 1 as "sequence!"
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(sequence)
+    }
+
+    /// Reserva o próximo número de remessa.
+    pub async fn take_remittance_number<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<i32, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let sequence = sqlx::query_scalar!(
+            r#"
+            UPDATE billing_configs
+            SET next_remittance_number = next_remittance_number + 1
+            WHERE tenant_id = $1 AND account_id = $2
+            RETURNING next_remittance_number - 1 as "sequence!"
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(sequence)
+    }
+
+    // =========================================================================
+    //  BOLETOS
+    // =========================================================================
+
+    pub async fn create_boleto<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        account_id: Uuid,
+        our_number: &str,
+        amount: Decimal,
+        due_date: NaiveDate,
+        barcode: &str,
+        digitable_line: &str,
+        created_by: Option<Uuid>,
+    ) -> Result<Boleto, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boleto = sqlx::query_as!(
+            Boleto,
+            r#"
+            INSERT INTO boletos (
+                tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            title_id,
+            account_id,
+            our_number,
+            amount,
+            due_date,
+            barcode,
+            digitable_line,
+            created_by
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.constraint() == Some("idx_boletos_active_title") {
+                        return AppError::BoletoAlreadyExists(title_id.to_string());
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(boleto)
+    }
+
+    pub async fn get_boleto<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_id: Uuid,
+    ) -> Result<Option<Boleto>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boleto = sqlx::query_as!(
+            Boleto,
+            r#"
+            SELECT
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            FROM boletos
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            boleto_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(boleto)
+    }
+
+    pub async fn list_boletos<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        title_id: Option<Uuid>,
+        status: Option<BoletoStatus>,
+    ) -> Result<Vec<Boleto>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boletos = sqlx::query_as!(
+            Boleto,
+            r#"
+            SELECT
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            FROM boletos
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR account_id = $2)
+              AND ($3::UUID IS NULL OR title_id = $3)
+              AND ($4::boleto_status IS NULL OR status = $4)
+            ORDER BY due_date ASC, created_at ASC
+            "#,
+            tenant_id,
+            account_id,
+            title_id,
+            status as Option<BoletoStatus>
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(boletos)
+    }
+
+    /// Boletos da conta aguardando remessa, travados até a remessa ser gravada.
+    pub async fn list_pending_boletos_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<Boleto>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boletos = sqlx::query_as!(
+            Boleto,
+            r#"
+            SELECT
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            FROM boletos
+            WHERE tenant_id = $1 AND account_id = $2 AND status = 'PENDING'
+            ORDER BY our_number ASC
+            FOR UPDATE
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(boletos)
+    }
+
+    pub async fn find_boleto_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        our_number: &str,
+    ) -> Result<Option<Boleto>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boleto = sqlx::query_as!(
+            Boleto,
+            r#"
+            SELECT
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            FROM boletos
+            WHERE tenant_id = $1 AND account_id = $2 AND our_number = $3
+            FOR UPDATE
+            "#,
+            tenant_id,
+            account_id,
+            our_number
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(boleto)
+    }
+
+    pub async fn mark_boletos_remitted<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_ids: &[Uuid],
+        remittance_id: Uuid,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            UPDATE boletos
+            SET status = 'REMITTED', remittance_id = $3, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = ANY($2)
+            "#,
+            tenant_id,
+            boleto_ids,
+            remittance_id
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Atualiza o boleto com a ocorrência do retorno. Liquidação informa valor, data e a baixa gerada.
+    pub async fn update_boleto_status<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_id: Uuid,
+        status: BoletoStatus,
+        bank_message: &str,
+        paid_amount: Option<Decimal>,
+        paid_date: Option<NaiveDate>,
+        movement_id: Option<Uuid>,
+    ) -> Result<Boleto, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let boleto = sqlx::query_as!(
+            Boleto,
+            r#"
+            UPDATE boletos
+            SET status = $3, bank_message = $4,
+                paid_amount = COALESCE($5, paid_amount),
+                paid_date = COALESCE($6, paid_date),
+                movement_id = COALESCE($7, movement_id),
+                updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, title_id, account_id, our_number, amount, due_date,
+                barcode, digitable_line,
+                status as "status: BoletoStatus",
+                remittance_id, paid_amount, paid_date, movement_id, bank_message,
+                created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            boleto_id,
+            status as BoletoStatus,
+            bank_message,
+            paid_amount,
+            paid_date,
+            movement_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(boleto)
+    }
+
+    /// Pagador do boleto: nome, documento e o endereço do JSON do CRM.
+    pub async fn get_payer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<Option<BoletoPayer>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let payer = sqlx::query_as!(
+            BoletoPayer,
+            r#"
+            SELECT
+                full_name as "name!",
+                document_number,
+                address->>'rua' as street,
+                address->>'numero' as number,
+                address->>'bairro' as district,
+                address->>'cidade' as city,
+                address->>'estado' as state,
+                address->>'cep' as zip_code
+            FROM customers
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            customer_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(payer)
+    }
+
+    // =========================================================================
+    //  ARQUIVOS CNAB
+    // =========================================================================
+
+    pub async fn create_cnab_file<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        kind: CnabFileKind,
+        layout: CnabLayout,
+        sequence_number: Option<i32>,
+        file_name: Option<&str>,
+        content: &str,
+        record_count: i32,
+        created_by: Option<Uuid>,
+    ) -> Result<CnabFile, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let file = sqlx::query_as!(
+            CnabFile,
+            r#"
+            INSERT INTO cnab_files (
+                tenant_id, account_id, kind, layout, sequence_number, file_name, content, record_count, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id, tenant_id, account_id,
+                kind as "kind: CnabFileKind",
+                layout as "layout: CnabLayout",
+                sequence_number, file_name, content, record_count, settled_count,
+                created_by, created_at
+            "#,
+            tenant_id,
+            account_id,
+            kind as CnabFileKind,
+            layout as CnabLayout,
+            sequence_number,
+            file_name,
+            content,
+            record_count,
+            created_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(file)
+    }
+
+    pub async fn update_cnab_settled_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        file_id: Uuid,
+        settled_count: i32,
+    ) -> Result<CnabFile, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let file = sqlx::query_as!(
+            CnabFile,
+            r#"
+            UPDATE cnab_files
+            SET settled_count = $3
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, account_id,
+                kind as "kind: CnabFileKind",
+                layout as "layout: CnabLayout",
+                sequence_number, file_name, content, record_count, settled_count,
+                created_by, created_at
+            "#,
+            tenant_id,
+            file_id,
+            settled_count
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(file)
+    }
+
+    pub async fn get_cnab_file<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        file_id: Uuid,
+    ) -> Result<Option<CnabFile>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let file = sqlx::query_as!(
+            CnabFile,
+            r#"
+            SELECT
+                id, tenant_id, account_id,
+                kind as "kind: CnabFileKind",
+                layout as "layout: CnabLayout",
+                sequence_number, file_name, content, record_count, settled_count,
+                created_by, created_at
+            FROM cnab_files
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            file_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(file)
+    }
+
+    pub async fn list_cnab_files<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        kind: Option<CnabFileKind>,
+    ) -> Result<Vec<CnabFile>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let files = sqlx::query_as!(
+            CnabFile,
+            r#"
+            SELECT
+                id, tenant_id, account_id,
+                kind as "kind: CnabFileKind",
+                layout as "layout: CnabLayout",
+                sequence_number, file_name, content, record_count, settled_count,
+                created_by, created_at
+            FROM cnab_files
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR account_id = $2)
+              AND ($3::cnab_file_kind IS NULL OR kind = $3)
+            ORDER BY created_at DESC
+            "#,
+            tenant_id,
+            account_id,
+            kind as Option<CnabFileKind>
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(files)
+    }
+}
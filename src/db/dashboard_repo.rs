@@ -3,9 +3,10 @@
 use sqlx::{PgPool, Postgres, Executor, Acquire};
 use uuid::Uuid;
 use rust_decimal::Decimal;
+use chrono::NaiveDate;
 use crate::{
     common::error::AppError,
-    models::dashboard::{DashboardSummary, SalesChartEntry, TopProductEntry},
+    models::dashboard::{CashFlowDayTotal, DashboardSummary, SalesChartEntry, TopProductEntry},
 };
 
 #[derive(Clone)]
@@ -94,6 +95,21 @@ impl DashboardRepository {
             .await?
             .total.unwrap_or(Decimal::ZERO);
 
+        // E. Em Atraso (marcados pela varredura de vencidos)
+        let overdue = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(amount_balance) FILTER (WHERE kind = 'RECEIVABLE'), 0) as receivables,
+                COALESCE(SUM(amount_balance) FILTER (WHERE kind = 'PAYABLE'), 0) as payables
+            FROM financial_titles
+            WHERE tenant_id = $1
+              AND status = 'OVERDUE'
+            "#,
+            tenant_id
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
         // Fecha a transação (Commit ou Rollback tanto faz pra leitura, mas commit é clean)
         tx.commit().await?;
 
@@ -102,6 +118,10 @@ impl DashboardRepository {
             receivables_today,
             payables_today,
             current_balance,
+            overdue_receivables: overdue.receivables.unwrap_or(Decimal::ZERO),
+            overdue_payables: overdue.payables.unwrap_or(Decimal::ZERO),
+            // Preenchido pelo serviço com a mesma regra do relatório de orçamento
+            over_budget_categories: Vec::new(),
         })
     }
 
@@ -151,7 +171,7 @@ impl DashboardRepository {
             SELECT
                 i.name as item_name,
                 SUM(oi.quantity) as total_quantity,
-                SUM(oi.quantity * oi.unit_price - oi.discount) as total_revenue
+                SUM(COALESCE(oi.unit_quantity, oi.quantity) * oi.unit_price - oi.discount) as total_revenue
             FROM order_items oi
             JOIN orders o ON oi.order_id = o.id
             JOIN pipeline_stages s ON o.stage_id = s.id
@@ -169,4 +189,107 @@ impl DashboardRepository {
 
         Ok(data)
     }
-}
\ No newline at end of file
+
+    // 4. Fluxo de Caixa: saldo atual das contas ativas
+    pub async fn get_current_balance<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Decimal, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(current_balance), 0) as "total!"
+            FROM financial_accounts
+            WHERE tenant_id = $1 AND is_active = true
+            "#,
+            tenant_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(total)
+    }
+
+    // 5. Fluxo de Caixa: movimentações por dia a partir de `since` (realizado).
+    // Entradas/saídas ignoram transferências e pares de estorno; o `net` considera
+    // tudo, pois é ele que reconstrói o saldo de cada dia.
+    pub async fn get_realized_cash_flow<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        since: NaiveDate,
+    ) -> Result<Vec<CashFlowDayTotal>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let data = sqlx::query_as!(
+            CashFlowDayTotal,
+            r#"
+            SELECT
+                m.movement_date as "date!",
+                COALESCE(SUM(m.amount) FILTER (
+                    WHERE m.amount > 0 AND m.transfer_id IS NULL
+                      AND m.reversed_at IS NULL AND m.reversal_of IS NULL
+                ), 0) as "inflow!",
+                COALESCE(-SUM(m.amount) FILTER (
+                    WHERE m.amount < 0 AND m.transfer_id IS NULL
+                      AND m.reversed_at IS NULL AND m.reversal_of IS NULL
+                ), 0) as "outflow!",
+                COALESCE(SUM(m.amount), 0) as "net!"
+            FROM financial_movements m
+            JOIN financial_accounts a ON a.id = m.account_id
+            WHERE m.tenant_id = $1
+              AND a.is_active = true
+              AND m.movement_date >= $2
+            GROUP BY m.movement_date
+            ORDER BY m.movement_date ASC
+            "#,
+            tenant_id,
+            since
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(data)
+    }
+
+    // 6. Fluxo de Caixa: saldo dos títulos em aberto por vencimento (projetado).
+    // Títulos vencidos entram no dia `today`: ainda são esperados.
+    pub async fn get_projected_cash_flow<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        today: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<CashFlowDayTotal>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let data = sqlx::query_as!(
+            CashFlowDayTotal,
+            r#"
+            SELECT
+                GREATEST(due_date, $2) as "date!",
+                COALESCE(SUM(amount_balance) FILTER (WHERE kind = 'RECEIVABLE'), 0) as "inflow!",
+                COALESCE(SUM(amount_balance) FILTER (WHERE kind = 'PAYABLE'), 0) as "outflow!",
+                COALESCE(SUM(CASE WHEN kind = 'RECEIVABLE' THEN amount_balance ELSE -amount_balance END), 0) as "net!"
+            FROM financial_titles
+            WHERE tenant_id = $1
+              AND status IN ('PENDING', 'PARTIAL', 'OVERDUE')
+              AND due_date <= $3
+            GROUP BY 1
+            ORDER BY 1 ASC
+            "#,
+            tenant_id,
+            today,
+            until
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(data)
+    }
+}
@@ -6,7 +6,12 @@ use rust_decimal::Decimal;
 use chrono::NaiveDate;
 use crate::{
     common::error::AppError,
-    models::finance::{FinancialAccount, FinancialCategory, FinancialTitle, TitleKind, TitleStatus},
+    models::finance::{
+        CashMovementKind, CashSession, CashSessionStatus, CategoryAmount, CategoryBudget,
+        DreCategoryTotal, MonthlyAmount,
+        FinancialAccount, FinancialCategory, FinancialMovement, FinancialTitle, PaymentTerm,
+        RecurrenceFrequency, RecurringTitle, TitleKind, TitleStatus
+    },
 };
 
 #[derive(Clone)]
@@ -69,6 +74,78 @@ impl FinanceRepository {
         Ok(accounts)
     }
 
+    pub async fn get_account<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<FinancialAccount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let account = sqlx::query_as!(
+            FinancialAccount,
+            "SELECT * FROM financial_accounts WHERE tenant_id = $1 AND id = $2",
+            tenant_id,
+            account_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(account)
+    }
+
+    /// Busca a conta travando a linha (FOR UPDATE) para atualizar o saldo com segurança.
+    pub async fn get_account_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<FinancialAccount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let account = sqlx::query_as!(
+            FinancialAccount,
+            "SELECT * FROM financial_accounts WHERE tenant_id = $1 AND id = $2 FOR UPDATE",
+            tenant_id,
+            account_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(account)
+    }
+
+    /// Soma (ou subtrai, se negativo) o delta no cache de saldo da conta.
+    pub async fn update_account_balance<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        delta: Decimal,
+    ) -> Result<FinancialAccount, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let account = sqlx::query_as!(
+            FinancialAccount,
+            r#"
+            UPDATE financial_accounts
+            SET current_balance = current_balance + $3
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING *
+            "#,
+            tenant_id,
+            account_id,
+            delta
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(account)
+    }
+
     // =========================================================================
     //  CATEGORIAS (Plano de Contas)
     // =========================================================================
@@ -77,8 +154,10 @@ impl FinanceRepository {
         &self,
         executor: E,
         tenant_id: Uuid,
+        code: Option<&str>,
         name: &str,
         kind: TitleKind,
+        parent_id: Option<Uuid>,
     ) -> Result<FinancialCategory, AppError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -86,16 +165,68 @@ impl FinanceRepository {
         let category = sqlx::query_as!(
             FinancialCategory,
             r#"
-            INSERT INTO financial_categories (tenant_id, name, kind)
-            VALUES ($1, $2, $3)
-            RETURNING id, tenant_id, name, kind as "kind: TitleKind", is_active
+            INSERT INTO financial_categories (tenant_id, code, name, kind, parent_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, tenant_id, code, name, kind as "kind: TitleKind", parent_id, is_active
             "#,
             tenant_id,
+            code,
             name,
-            kind as TitleKind
+            kind as TitleKind,
+            parent_id
         )
             .fetch_one(executor)
-            .await?;
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::FinancialCategoryCodeAlreadyExists(code.unwrap_or_default().to_string());
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(category)
+    }
+
+    pub async fn update_category<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        category_id: Uuid,
+        code: Option<&str>,
+        name: &str,
+        parent_id: Option<Uuid>,
+        is_active: bool,
+    ) -> Result<FinancialCategory, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let category = sqlx::query_as!(
+            FinancialCategory,
+            r#"
+            UPDATE financial_categories
+            SET code = $3, name = $4, parent_id = $5, is_active = $6
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING id, tenant_id, code, name, kind as "kind: TitleKind", parent_id, is_active
+            "#,
+            tenant_id,
+            category_id,
+            code,
+            name,
+            parent_id,
+            is_active
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::FinancialCategoryCodeAlreadyExists(code.unwrap_or_default().to_string());
+                    }
+                }
+                e.into()
+            })?;
 
         Ok(category)
     }
@@ -111,10 +242,10 @@ impl FinanceRepository {
         let categories = sqlx::query_as!(
             FinancialCategory,
             r#"
-            SELECT id, tenant_id, name, kind as "kind: TitleKind", is_active
+            SELECT id, tenant_id, code, name, kind as "kind: TitleKind", parent_id, is_active
             FROM financial_categories
             WHERE tenant_id = $1
-            ORDER BY name ASC
+            ORDER BY code ASC NULLS LAST, name ASC
             "#,
             tenant_id
         )
@@ -124,6 +255,31 @@ impl FinanceRepository {
         Ok(categories)
     }
 
+    pub async fn get_category<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<FinancialCategory>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let category = sqlx::query_as!(
+            FinancialCategory,
+            r#"
+            SELECT id, tenant_id, code, name, kind as "kind: TitleKind", parent_id, is_active
+            FROM financial_categories
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            category_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(category)
+    }
+
     // =========================================================================
     //  TÍTULOS (Contas a Pagar / Receber)
     // =========================================================================
@@ -139,6 +295,7 @@ impl FinanceRepository {
         category_id: Option<Uuid>,
         customer_id: Option<Uuid>,
         order_id: Option<Uuid>,
+        competence_date: Option<NaiveDate>,
     ) -> Result<FinancialTitle, AppError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -150,9 +307,10 @@ impl FinanceRepository {
             INSERT INTO financial_titles (
                 tenant_id, description, kind,
                 amount_original, amount_balance,
-                due_date, category_id, customer_id, order_id
+                due_date, category_id, customer_id, order_id,
+                competence_date
             )
-            VALUES ($1, $2, $3, $4, $4, $5, $6, $7, $8)
+            VALUES ($1, $2, $3, $4, $4, $5, $6, $7, $8, COALESCE($9, CURRENT_DATE))
             RETURNING
                 id, tenant_id, description,
                 kind as "kind: TitleKind",
@@ -160,6 +318,7 @@ impl FinanceRepository {
                 amount_original, amount_balance,
                 due_date, competence_date,
                 category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
                 created_at, updated_at
             "#,
             tenant_id,
@@ -169,11 +328,1441 @@ impl FinanceRepository {
             due_date,
             category_id,
             customer_id,
-            order_id
+            order_id,
+            competence_date // Se não informado, o banco assume a data de hoje
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(title)
+    }
+
+    pub async fn get_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<Option<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let title = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            FROM financial_titles
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            title_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(title)
+    }
+
+    /// Lista títulos com filtros opcionais (tipo, status e faixa de vencimento).
+    pub async fn list_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        kind: Option<TitleKind>,
+        status: Option<TitleStatus>,
+        due_from: Option<NaiveDate>,
+        due_to: Option<NaiveDate>,
+        recurring_title_id: Option<Uuid>,
+    ) -> Result<Vec<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let titles = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            FROM financial_titles
+            WHERE tenant_id = $1
+              AND ($2::title_kind IS NULL OR kind = $2)
+              AND ($3::title_status IS NULL OR status = $3)
+              AND ($4::date IS NULL OR due_date >= $4)
+              AND ($5::date IS NULL OR due_date <= $5)
+              AND ($6::UUID IS NULL OR recurring_title_id = $6)
+            ORDER BY due_date ASC, created_at ASC
+            "#,
+            tenant_id,
+            kind as Option<TitleKind>,
+            status as Option<TitleStatus>,
+            due_from,
+            due_to,
+            recurring_title_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(titles)
+    }
+
+    pub async fn get_title_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<Option<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let title = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            FROM financial_titles
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            title_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(title)
+    }
+
+    pub async fn update_title_balance<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        amount_balance: Decimal,
+        status: TitleStatus,
+    ) -> Result<FinancialTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let title = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            UPDATE financial_titles
+            SET amount_balance = $3, status = $4, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            title_id,
+            amount_balance,
+            status as TitleStatus
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(title)
+    }
+
+    /// Cria uma parcela de um pedido (Contas a Receber).
+    /// As parcelas de um mesmo pedido compartilham o `installment_group_id`.
+    pub async fn create_order_installment<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        description: &str,
+        amount: Decimal,
+        due_date: NaiveDate,
+        customer_id: Option<Uuid>,
+        order_id: Uuid,
+        installment_group_id: Uuid,
+        installment_number: i16,
+        installment_count: i16,
+    ) -> Result<FinancialTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let title = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            INSERT INTO financial_titles (
+                tenant_id, description, kind,
+                amount_original, amount_balance,
+                due_date, customer_id, order_id,
+                installment_group_id, installment_number, installment_count
+            )
+            VALUES ($1, $2, 'RECEIVABLE', $3, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            description,
+            amount,
+            due_date,
+            customer_id,
+            order_id,
+            installment_group_id,
+            installment_number,
+            installment_count
         )
             .fetch_one(executor)
             .await?;
 
         Ok(title)
     }
-}
\ No newline at end of file
+
+    /// Marca como OVERDUE os títulos em aberto com vencimento anterior a `today`.
+    /// Retorna quantos títulos mudaram de status.
+    pub async fn mark_overdue_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            r#"
+            UPDATE financial_titles
+            SET status = 'OVERDUE', updated_at = NOW()
+            WHERE tenant_id = $1
+              AND status IN ('PENDING', 'PARTIAL')
+              AND due_date < $2
+            "#,
+            tenant_id,
+            today
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // =========================================================================
+    //  CONDIÇÕES DE PAGAMENTO (Parcelamento)
+    // =========================================================================
+
+    pub async fn create_payment_term<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        name: &str,
+        installments: i32,
+        interval_days: i32,
+        first_due_days: i32,
+        down_payment_percent: Decimal,
+    ) -> Result<PaymentTerm, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query_as!(
+            PaymentTerm,
+            r#"
+            INSERT INTO payment_terms (
+                tenant_id, name, installments, interval_days, first_due_days, down_payment_percent
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+            tenant_id,
+            name,
+            installments,
+            interval_days,
+            first_due_days,
+            down_payment_percent
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::PaymentTermNameAlreadyExists(name.to_string());
+                    }
+                }
+                e.into()
+            })
+    }
+
+    pub async fn list_payment_terms<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Vec<PaymentTerm>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let terms = sqlx::query_as!(
+            PaymentTerm,
+            "SELECT * FROM payment_terms WHERE tenant_id = $1 ORDER BY name ASC",
+            tenant_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(terms)
+    }
+
+    pub async fn get_payment_term<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        payment_term_id: Uuid,
+    ) -> Result<Option<PaymentTerm>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let term = sqlx::query_as!(
+            PaymentTerm,
+            "SELECT * FROM payment_terms WHERE tenant_id = $1 AND id = $2",
+            tenant_id,
+            payment_term_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(term)
+    }
+
+    // =========================================================================
+    //  TÍTULOS RECORRENTES (Modelos)
+    // =========================================================================
+
+    /// Cria o modelo. A primeira ocorrência a gerar é a própria data de início.
+    pub async fn create_recurring_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        description: &str,
+        kind: TitleKind,
+        amount: Decimal,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+        frequency: RecurrenceFrequency,
+        interval_count: i32,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+        lead_days: i32,
+        created_by: Uuid,
+    ) -> Result<RecurringTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            INSERT INTO recurring_titles (
+                tenant_id, description, kind, amount, category_id, customer_id,
+                frequency, interval_count, start_date, end_date, lead_days,
+                next_due_date, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $9, $12)
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            description,
+            kind as TitleKind,
+            amount,
+            category_id,
+            customer_id,
+            frequency as RecurrenceFrequency,
+            interval_count,
+            start_date,
+            end_date,
+            lead_days,
+            created_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    pub async fn list_recurring_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        kind: Option<TitleKind>,
+    ) -> Result<Vec<RecurringTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            FROM recurring_titles
+            WHERE tenant_id = $1
+              AND ($2::title_kind IS NULL OR kind = $2)
+            ORDER BY is_active DESC, next_due_date ASC NULLS LAST, description ASC
+            "#,
+            tenant_id,
+            kind as Option<TitleKind>
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    pub async fn get_recurring_title_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        recurring_id: Uuid,
+    ) -> Result<Option<RecurringTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            FROM recurring_titles
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            recurring_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    /// Modelos ativos com ocorrência dentro da antecedência de cada um.
+    /// SKIP LOCKED: outra instância gerando o mesmo modelo fica com ele.
+    pub async fn list_due_recurring_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<Vec<RecurringTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            FROM recurring_titles
+            WHERE tenant_id = $1
+              AND is_active = true
+              AND next_due_date IS NOT NULL
+              AND next_due_date <= $2::DATE + lead_days
+            ORDER BY next_due_date ASC
+            FOR UPDATE SKIP LOCKED
+            "#,
+            tenant_id,
+            today
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    /// Atualiza o modelo (vale para as ocorrências ainda não geradas).
+    pub async fn update_recurring_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        recurring_id: Uuid,
+        description: &str,
+        amount: Decimal,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+        end_date: Option<NaiveDate>,
+        lead_days: i32,
+        is_active: bool,
+        next_due_date: Option<NaiveDate>,
+    ) -> Result<RecurringTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            UPDATE recurring_titles
+            SET description = $3, amount = $4, category_id = $5, customer_id = $6,
+                end_date = $7, lead_days = $8, is_active = $9, next_due_date = $10,
+                updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            recurring_id,
+            description,
+            amount,
+            category_id,
+            customer_id,
+            end_date,
+            lead_days,
+            is_active,
+            next_due_date
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    pub async fn update_recurring_progress<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        recurring_id: Uuid,
+        generated_count: i32,
+        next_due_date: Option<NaiveDate>,
+    ) -> Result<RecurringTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let recurring = sqlx::query_as!(
+            RecurringTitle,
+            r#"
+            UPDATE recurring_titles
+            SET generated_count = $3, next_due_date = $4, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                amount, category_id, customer_id,
+                frequency as "frequency: RecurrenceFrequency",
+                interval_count, start_date, end_date, lead_days,
+                generated_count, next_due_date,
+                is_active, created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            recurring_id,
+            generated_count,
+            next_due_date
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(recurring)
+    }
+
+    /// Gera o título de uma ocorrência (competência = vencimento).
+    /// Se o vencimento já foi gerado para o modelo, não faz nada e retorna None.
+    pub async fn create_recurring_occurrence<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        recurring: &RecurringTitle,
+        due_date: NaiveDate,
+    ) -> Result<Option<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let title = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            INSERT INTO financial_titles (
+                tenant_id, description, kind,
+                amount_original, amount_balance,
+                due_date, competence_date, category_id, customer_id,
+                recurring_title_id
+            )
+            VALUES ($1, $2, $3, $4, $4, $5, $5, $6, $7, $8)
+            ON CONFLICT (recurring_title_id, due_date) WHERE recurring_title_id IS NOT NULL DO NOTHING
+            RETURNING
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            recurring.description,
+            recurring.kind as TitleKind,
+            recurring.amount,
+            due_date,
+            recurring.category_id,
+            recurring.customer_id,
+            recurring.id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(title)
+    }
+
+    // =========================================================================
+    //  MOVIMENTAÇÕES (O Dinheiro de verdade)
+    // =========================================================================
+
+    pub async fn create_movement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        title_id: Option<Uuid>,
+        transfer_id: Option<Uuid>,
+        amount: Decimal,
+        movement_date: NaiveDate,
+        interest_amount: Decimal,
+        fee_amount: Decimal,
+        description: Option<&str>,
+        created_by: Option<Uuid>,
+        reversal_of: Option<Uuid>,
+    ) -> Result<FinancialMovement, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movement = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            INSERT INTO financial_movements (
+                tenant_id, account_id, title_id, transfer_id, amount, movement_date,
+                interest_amount, fee_amount,
+                description, created_by, reversal_of
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            "#,
+            tenant_id,
+            account_id,
+            title_id,
+            transfer_id,
+            amount,
+            movement_date,
+            interest_amount,
+            fee_amount,
+            description,
+            created_by,
+            reversal_of
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        if let Some(original) = reversal_of {
+                            return AppError::MovementAlreadyReversed(original.to_string());
+                        }
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(movement)
+    }
+
+    pub async fn get_movement_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        movement_id: Uuid,
+    ) -> Result<Option<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movement = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            FROM financial_movements
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            movement_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(movement)
+    }
+
+    pub async fn mark_movement_reversed<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        movement_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            UPDATE financial_movements
+            SET reversed_at = NOW(), reversal_reason = $3
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            movement_id,
+            reason
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_title_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            FROM financial_movements
+            WHERE tenant_id = $1 AND title_id = $2
+            ORDER BY movement_date ASC, created_at ASC
+            "#,
+            tenant_id,
+            title_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    /// Vincula uma movimentação a uma sessão de caixa.
+    pub async fn attach_movement_to_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        movement_id: Uuid,
+        cash_session_id: Uuid,
+        cash_kind: CashMovementKind,
+    ) -> Result<FinancialMovement, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movement = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            UPDATE financial_movements
+            SET cash_session_id = $3, cash_kind = $4
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            "#,
+            tenant_id,
+            movement_id,
+            cash_session_id,
+            cash_kind as CashMovementKind
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(movement)
+    }
+
+    pub async fn list_cash_session_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            FROM financial_movements
+            WHERE tenant_id = $1 AND cash_session_id = $2
+            ORDER BY created_at ASC
+            "#,
+            tenant_id,
+            cash_session_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    /// Movimentações da conta no período (extrato), em ordem cronológica.
+    pub async fn list_account_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            FROM financial_movements
+            WHERE tenant_id = $1 AND account_id = $2
+              AND movement_date BETWEEN $3 AND $4
+            ORDER BY movement_date ASC, created_at ASC
+            "#,
+            tenant_id,
+            account_id,
+            start_date,
+            end_date
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    /// Saldo da conta antes de `start_date` e saldo total pelo histórico de movimentações.
+    /// Retorna (saldo_anterior, saldo_histórico).
+    pub async fn get_account_ledger_balances<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        start_date: NaiveDate,
+    ) -> Result<(Decimal, Decimal), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(amount) FILTER (WHERE movement_date < $3), 0) as "opening!",
+                COALESCE(SUM(amount), 0) as "ledger!"
+            FROM financial_movements
+            WHERE tenant_id = $1 AND account_id = $2
+            "#,
+            tenant_id,
+            account_id,
+            start_date
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok((row.opening, row.ledger))
+    }
+
+    /// As pernas de uma transferência (e seus estornos).
+    pub async fn list_transfer_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                id, tenant_id, account_id, title_id, amount, movement_date,
+                interest_amount, fee_amount, description,
+                cash_session_id, cash_kind as "cash_kind: CashMovementKind", transfer_id,
+                created_by, reversal_of, reversed_at, reversal_reason, created_at
+            FROM financial_movements
+            WHERE tenant_id = $1 AND transfer_id = $2
+            ORDER BY created_at ASC, amount ASC
+            "#,
+            tenant_id,
+            transfer_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    // =========================================================================
+    //  CAIXA (Sessões de Abertura / Fechamento)
+    // =========================================================================
+
+    pub async fn create_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        location_id: Uuid,
+        opened_by: Uuid,
+        opening_float: Decimal,
+        notes: Option<&str>,
+    ) -> Result<CashSession, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query_as!(
+            CashSession,
+            r#"
+            INSERT INTO cash_sessions (tenant_id, account_id, location_id, opened_by, opening_float, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            "#,
+            tenant_id,
+            account_id,
+            location_id,
+            opened_by,
+            opening_float,
+            notes
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::CashSessionAlreadyOpen(account_id.to_string());
+                    }
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::ResourceNotFound(format!("Local {}", location_id));
+                    }
+                }
+                e.into()
+            })
+    }
+
+    pub async fn get_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<Option<CashSession>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let session = sqlx::query_as!(
+            CashSession,
+            r#"
+            SELECT
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            FROM cash_sessions
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            cash_session_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(session)
+    }
+
+    pub async fn get_cash_session_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<Option<CashSession>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let session = sqlx::query_as!(
+            CashSession,
+            r#"
+            SELECT
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            FROM cash_sessions
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            cash_session_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(session)
+    }
+
+    /// Sessão aberta (se houver) sobre a conta. Usada para vincular baixas ao caixa.
+    pub async fn get_open_cash_session_for_account<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<CashSession>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let session = sqlx::query_as!(
+            CashSession,
+            r#"
+            SELECT
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            FROM cash_sessions
+            WHERE tenant_id = $1 AND account_id = $2 AND status = 'OPEN'
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(session)
+    }
+
+    pub async fn list_cash_sessions<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<CashSessionStatus>,
+    ) -> Result<Vec<CashSession>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let sessions = sqlx::query_as!(
+            CashSession,
+            r#"
+            SELECT
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            FROM cash_sessions
+            WHERE tenant_id = $1
+              AND ($2::cash_session_status IS NULL OR status = $2)
+            ORDER BY opened_at DESC
+            "#,
+            tenant_id,
+            status as Option<CashSessionStatus>
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(sessions)
+    }
+
+    pub async fn close_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+        closed_by: Uuid,
+        expected_amount: Decimal,
+        counted_amount: Decimal,
+        notes: Option<&str>,
+    ) -> Result<CashSession, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let session = sqlx::query_as!(
+            CashSession,
+            r#"
+            UPDATE cash_sessions
+            SET status = 'CLOSED',
+                closed_by = $3,
+                closed_at = NOW(),
+                expected_amount = $4,
+                counted_amount = $5,
+                difference = $5::NUMERIC - $4::NUMERIC,
+                notes = COALESCE($6, notes)
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, account_id, location_id,
+                status as "status: CashSessionStatus",
+                opened_by, opening_float, opened_at,
+                closed_by, closed_at, expected_amount, counted_amount, difference,
+                notes
+            "#,
+            tenant_id,
+            cash_session_id,
+            closed_by,
+            expected_amount,
+            counted_amount,
+            notes
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(session)
+    }
+
+    /// Nomes da conta e do local da sessão (para o relatório de fechamento).
+    pub async fn get_cash_session_labels<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<(String, String), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let row = sqlx::query!(
+            r#"
+            SELECT a.name as account_name, l.name as location_name
+            FROM cash_sessions cs
+            JOIN financial_accounts a ON cs.account_id = a.id
+            JOIN locations l ON cs.location_id = l.id
+            WHERE cs.tenant_id = $1 AND cs.id = $2
+            "#,
+            tenant_id,
+            cash_session_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok((row.account_name, row.location_name))
+    }
+
+    // =========================================================================
+    //  RELATÓRIOS (DRE)
+    // =========================================================================
+
+    /// Soma dos títulos por mês de competência, tipo e categoria (cancelados ficam de fora).
+    pub async fn get_dre_category_totals<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DreCategoryTotal>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let totals = sqlx::query_as!(
+            DreCategoryTotal,
+            r#"
+            SELECT
+                date_trunc('month', t.competence_date)::DATE as "month!",
+                t.kind as "kind!: TitleKind",
+                t.category_id,
+                SUM(t.amount_original) as "total!"
+            FROM financial_titles t
+            WHERE t.tenant_id = $1
+              AND t.competence_date BETWEEN $2 AND $3
+              AND t.status <> 'CANCELLED'
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2
+            "#,
+            tenant_id,
+            start_date,
+            end_date
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(totals)
+    }
+
+    /// CMV por mês: custo dos itens dos pedidos, no mês de competência
+    /// do(s) título(s) a receber gerados pelo pedido.
+    pub async fn get_monthly_cogs<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<MonthlyAmount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let totals = sqlx::query_as!(
+            MonthlyAmount,
+            r#"
+            WITH order_competence AS (
+                SELECT order_id, MIN(competence_date) AS competence_date
+                FROM financial_titles
+                WHERE tenant_id = $1
+                  AND order_id IS NOT NULL
+                  AND kind = 'RECEIVABLE'
+                  AND status <> 'CANCELLED'
+                GROUP BY order_id
+            )
+            SELECT
+                date_trunc('month', oc.competence_date)::DATE as "month!",
+                SUM(oi.quantity * oi.unit_cost) as "total!"
+            FROM order_competence oc
+            JOIN order_items oi ON oi.order_id = oc.order_id
+            WHERE oi.tenant_id = $1
+              AND oc.competence_date BETWEEN $2 AND $3
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            tenant_id,
+            start_date,
+            end_date
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(totals)
+    }
+
+    // =========================================================================
+    //  ORÇAMENTOS (por categoria e mês)
+    // =========================================================================
+
+    /// Cria ou substitui o orçamento da categoria no mês.
+    pub async fn upsert_budget<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        category_id: Uuid,
+        month: NaiveDate,
+        amount: Decimal,
+        created_by: Uuid,
+    ) -> Result<CategoryBudget, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let budget = sqlx::query_as!(
+            CategoryBudget,
+            r#"
+            INSERT INTO category_budgets (tenant_id, category_id, month, amount, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (category_id, month)
+            DO UPDATE SET
+                amount = EXCLUDED.amount,
+                updated_at = NOW()
+            RETURNING id, tenant_id, category_id, month, amount, created_by, created_at, updated_at
+            "#,
+            tenant_id,
+            category_id,
+            month,
+            amount,
+            created_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(budget)
+    }
+
+    pub async fn list_budgets<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_month: NaiveDate,
+        end_month: NaiveDate,
+        category_id: Option<Uuid>,
+    ) -> Result<Vec<CategoryBudget>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let budgets = sqlx::query_as!(
+            CategoryBudget,
+            r#"
+            SELECT b.id, b.tenant_id, b.category_id, b.month, b.amount, b.created_by, b.created_at, b.updated_at
+            FROM category_budgets b
+            JOIN financial_categories c ON c.id = b.category_id
+            WHERE b.tenant_id = $1
+              AND b.month BETWEEN $2 AND $3
+              AND ($4::UUID IS NULL OR b.category_id = $4)
+            ORDER BY b.month ASC, c.code ASC NULLS LAST, c.name ASC
+            "#,
+            tenant_id,
+            start_month,
+            end_month,
+            category_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(budgets)
+    }
+
+    /// Retorna false se o orçamento não existe.
+    pub async fn delete_budget<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            "DELETE FROM category_budgets WHERE id = $1 AND tenant_id = $2",
+            budget_id,
+            tenant_id
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Orçado por categoria, somando os meses entre `start_month` e `end_month`.
+    pub async fn get_budget_totals<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_month: NaiveDate,
+        end_month: NaiveDate,
+    ) -> Result<Vec<CategoryAmount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let totals = sqlx::query_as!(
+            CategoryAmount,
+            r#"
+            SELECT category_id, SUM(amount) as "total!"
+            FROM category_budgets
+            WHERE tenant_id = $1
+              AND month BETWEEN $2 AND $3
+            GROUP BY category_id
+            "#,
+            tenant_id,
+            start_month,
+            end_month
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(totals)
+    }
+
+    /// Baixas do período por categoria do título: só o principal (sem juros/multa),
+    /// ignorando movimentações estornadas e os próprios estornos.
+    pub async fn get_settled_category_totals<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<CategoryAmount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let totals = sqlx::query_as!(
+            CategoryAmount,
+            r#"
+            SELECT
+                t.category_id as "category_id!",
+                SUM(ABS(m.amount) - m.interest_amount - m.fee_amount) as "total!"
+            FROM financial_movements m
+            JOIN financial_titles t ON t.id = m.title_id
+            WHERE m.tenant_id = $1
+              AND t.category_id IS NOT NULL
+              AND m.movement_date BETWEEN $2 AND $3
+              AND m.reversed_at IS NULL
+              AND m.reversal_of IS NULL
+            GROUP BY t.category_id
+            "#,
+            tenant_id,
+            start_date,
+            end_date
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(totals)
+    }
+}
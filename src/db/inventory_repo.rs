@@ -9,7 +9,13 @@ use crate::{
     common::error::AppError,
     models::inventory::{
         Category, Item, InventoryLevel, StockMovement, StockMovementReason,
-        UnitOfMeasure, InventoryBatch, ItemKind, CompositionEntry, CompositionType
+        UnitOfMeasure, UnitConversion, InventoryBatch, ItemKind, CompositionEntry, CompositionType,
+        StockTransfer, StockTransferItem, StockTransferStatus,
+        InventoryCount, InventoryCountLine, InventoryCountStatus,
+        ProductionOrder, ProductionOrderComponent, ProductionOrderStatus,
+        WhereUsedEntry, FlattenedBomLine,
+        StockReservation, StockReservationStatus,
+        PoolAvailability, LocationAvailability
     },
 };
 
@@ -42,6 +48,7 @@ impl InventoryRepository {
             SELECT
                 id, tenant_id, sku, name, description,
                 base_unit_id as unit_id,
+                purchase_unit_id, sale_unit_id,
                 category_id,
                 kind as "kind: ItemKind",
                 settings,
@@ -74,6 +81,7 @@ impl InventoryRepository {
             SELECT
                 id, tenant_id, sku, name, description,
                 base_unit_id as unit_id,
+                purchase_unit_id, sale_unit_id,
                 category_id,
                 kind as "kind: ItemKind",
                 settings,
@@ -206,6 +214,182 @@ impl InventoryRepository {
             })
     }
 
+    // =========================================================================
+    //  CONVERSÕES DE UNIDADE
+    // =========================================================================
+
+    pub async fn upsert_unit_conversion<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        from_unit_id: Uuid,
+        to_unit_id: Uuid,
+        factor: Decimal,
+    ) -> Result<UnitConversion, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let conversion = sqlx::query_as!(
+            UnitConversion,
+            r#"
+            INSERT INTO unit_conversions (tenant_id, item_id, from_unit_id, to_unit_id, factor)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tenant_id, COALESCE(item_id, '00000000-0000-0000-0000-000000000000'::uuid), from_unit_id, to_unit_id)
+            DO UPDATE SET factor = EXCLUDED.factor
+            RETURNING id, tenant_id, item_id, from_unit_id, to_unit_id, factor, created_at, updated_at
+            "#,
+            tenant_id,
+            item_id,
+            from_unit_id,
+            to_unit_id,
+            factor
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::ResourceNotFound("Unidade ou item".into());
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(conversion)
+    }
+
+    // Sem `item_id`: só as conversões gerais. Com: as gerais e as do item.
+    pub async fn list_unit_conversions<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+    ) -> Result<Vec<UnitConversion>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let conversions = sqlx::query_as!(
+            UnitConversion,
+            r#"
+            SELECT id, tenant_id, item_id, from_unit_id, to_unit_id, factor, created_at, updated_at
+            FROM unit_conversions
+            WHERE tenant_id = $1
+              AND (item_id IS NULL OR item_id = $2)
+            ORDER BY item_id NULLS FIRST, created_at
+            "#,
+            tenant_id,
+            item_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(conversions)
+    }
+
+    pub async fn delete_unit_conversion<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        conversion_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            "DELETE FROM unit_conversions WHERE tenant_id = $1 AND id = $2",
+            tenant_id,
+            conversion_id
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Quantas unidades de `to_unit_id` cabem em 1 `from_unit_id` para o item.
+    // A conversão do item vence a geral; o cadastro inverso vale como 1 / fator.
+    pub async fn get_unit_factor<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        from_unit_id: Uuid,
+        to_unit_id: Uuid,
+    ) -> Result<Option<Decimal>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let factor = sqlx::query_scalar!(
+            r#"
+            SELECT c.factor as "factor!"
+            FROM (
+                SELECT factor, item_id IS NOT NULL AS item_specific, 0 AS inverse
+                FROM unit_conversions
+                WHERE tenant_id = $1 AND (item_id IS NULL OR item_id = $2)
+                  AND from_unit_id = $3 AND to_unit_id = $4
+
+                UNION ALL
+
+                SELECT 1 / factor, item_id IS NOT NULL, 1
+                FROM unit_conversions
+                WHERE tenant_id = $1 AND (item_id IS NULL OR item_id = $2)
+                  AND from_unit_id = $4 AND to_unit_id = $3
+            ) c
+            ORDER BY c.item_specific DESC, c.inverse
+            LIMIT 1
+            "#,
+            tenant_id,
+            item_id,
+            from_unit_id,
+            to_unit_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(factor)
+    }
+
+    pub async fn update_item_units<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        purchase_unit_id: Option<Uuid>,
+        sale_unit_id: Option<Uuid>,
+    ) -> Result<Item, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let item = sqlx::query_as!(
+            Item,
+            r#"
+            UPDATE items
+            SET purchase_unit_id = $3, sale_unit_id = $4, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, sku, name, description,
+                base_unit_id as unit_id,
+                purchase_unit_id, sale_unit_id,
+                category_id,
+                kind as "kind: ItemKind",
+                settings,
+                cost_price, sale_price,
+                current_stock, min_stock,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            item_id,
+            purchase_unit_id,
+            sale_unit_id
+        )
+            .fetch_optional(executor)
+            .await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        Ok(item)
+    }
+
     // =========================================================================
     //  CATÁLOGO AVANÇADO (Items + Composição)
     // =========================================================================
@@ -218,6 +402,8 @@ impl InventoryRepository {
         name: &str,
         description: Option<&str>,
         unit_id: Uuid,
+        purchase_unit_id: Option<Uuid>,
+        sale_unit_id: Option<Uuid>,
         category_id: Option<Uuid>,
 
         kind: ItemKind,
@@ -238,14 +424,16 @@ impl InventoryRepository {
             INSERT INTO items (
                 tenant_id, sku, name, description, base_unit_id, category_id,
                 kind, settings,
-                cost_price, sale_price, min_stock, current_stock
+                cost_price, sale_price, min_stock, current_stock,
+                purchase_unit_id, sale_unit_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 0)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 0, $12, $13)
             RETURNING
                 id, tenant_id, sku, name, description,
                 kind as "kind: ItemKind",
                 settings,
                 base_unit_id as unit_id,
+                purchase_unit_id, sale_unit_id,
                 category_id, cost_price, sale_price,
                 current_stock, min_stock, created_at, updated_at
             "#,
@@ -259,7 +447,9 @@ impl InventoryRepository {
             final_settings,
             cost_price,
             sale_price,
-            min_stock
+            min_stock,
+            purchase_unit_id,
+            sale_unit_id
         )
             .fetch_one(executor)
             .await
@@ -368,6 +558,171 @@ impl InventoryRepository {
         Ok(composition)
     }
 
+    // Serializa as alterações de ficha técnica da loja até o fim da transação
+    // (duas inclusões simultâneas A->B e B->A passariam pela checagem de ciclo)
+    pub async fn lock_compositions<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtext('item_compositions:' || $1::UUID::TEXT))",
+            tenant_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    // O pai já aparece abaixo do filho? (então parent -> child fecharia um ciclo)
+    pub async fn composition_creates_cycle<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        parent_id: Uuid,
+        child_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let creates_cycle = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE descendants (item_id) AS (
+                SELECT $3::UUID
+                UNION
+                SELECT ic.child_item_id
+                FROM item_compositions ic
+                JOIN descendants d ON ic.parent_item_id = d.item_id
+                WHERE ic.tenant_id = $1
+            )
+            SELECT EXISTS (SELECT 1 FROM descendants WHERE item_id = $2) as "exists!"
+            "#,
+            tenant_id,
+            parent_id,
+            child_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(creates_cycle)
+    }
+
+    // Busca reversa (idx_composition_child): fichas técnicas que usam o item
+    pub async fn get_where_used<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        child_id: Uuid,
+    ) -> Result<Vec<WhereUsedEntry>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let entries = sqlx::query_as!(
+            WhereUsedEntry,
+            r#"
+            SELECT
+                ic.id,
+                ic.parent_item_id,
+                i.sku as parent_sku,
+                i.name as parent_name,
+                i.kind as "parent_kind: ItemKind",
+                ic.quantity,
+                ic.comp_type as "comp_type: CompositionType"
+            FROM item_compositions ic
+            JOIN items i ON ic.parent_item_id = i.id
+            WHERE ic.tenant_id = $1
+              AND ic.child_item_id = $2
+            ORDER BY i.name ASC
+            "#,
+            tenant_id,
+            child_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(entries)
+    }
+
+    // Ficha técnica em todos os níveis (só COMPONENT), em profundidade.
+    // Custo: médio do local (se informado e com custo) ou o custo de cadastro.
+    // O caminho impede voltar a um item já visitado, mesmo em dados antigos com ciclo.
+    pub async fn get_flattened_composition<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        parent_id: Uuid,
+        quantity: Decimal,
+        location_id: Option<Uuid>,
+        max_depth: i32,
+    ) -> Result<Vec<FlattenedBomLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let lines = sqlx::query_as!(
+            FlattenedBomLine,
+            r#"
+            WITH RECURSIVE bom AS (
+                SELECT
+                    ic.parent_item_id, ic.child_item_id, ic.quantity,
+                    ic.quantity * $3 AS cumulative_quantity,
+                    1 AS level,
+                    ARRAY[ic.parent_item_id, ic.child_item_id] AS path
+                FROM item_compositions ic
+                WHERE ic.tenant_id = $1 AND ic.parent_item_id = $2 AND ic.comp_type = 'COMPONENT'
+
+                UNION ALL
+
+                SELECT
+                    ic.parent_item_id, ic.child_item_id, ic.quantity,
+                    b.cumulative_quantity * ic.quantity,
+                    b.level + 1,
+                    b.path || ic.child_item_id
+                FROM item_compositions ic
+                JOIN bom b ON ic.parent_item_id = b.child_item_id
+                WHERE ic.tenant_id = $1 AND ic.comp_type = 'COMPONENT'
+                  AND NOT ic.child_item_id = ANY(b.path)
+                  AND b.level < $5
+            )
+            SELECT
+                b.level as "level!",
+                b.parent_item_id as "parent_item_id!",
+                b.child_item_id as "item_id!",
+                i.sku as item_sku,
+                i.name as item_name,
+                u.symbol as item_unit,
+                b.quantity as "quantity!",
+                b.cumulative_quantity as "cumulative_quantity!",
+                COALESCE(NULLIF(lv.average_cost, 0), i.cost_price, 0) as "unit_cost!",
+                0::NUMERIC as "total_cost!",
+                NOT EXISTS (
+                    SELECT 1 FROM item_compositions sub
+                    WHERE sub.tenant_id = $1 AND sub.parent_item_id = b.child_item_id
+                      AND sub.comp_type = 'COMPONENT'
+                ) as "is_leaf!",
+                b.path as "path!"
+            FROM bom b
+            JOIN items i ON i.id = b.child_item_id
+            JOIN units_of_measure u ON i.base_unit_id = u.id
+            LEFT JOIN inventory_levels lv
+                   ON lv.tenant_id = $1 AND lv.item_id = b.child_item_id AND lv.location_id = $4
+            ORDER BY b.path
+            "#,
+            tenant_id,
+            parent_id,
+            quantity,
+            location_id,
+            max_depth
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(lines)
+    }
+
     // =========================================================================
     //  ESTOQUE & MOVIMENTAÇÃO (Mantido igual)
     // =========================================================================
@@ -397,7 +752,7 @@ impl InventoryRepository {
                 quantity, reserved_quantity,
                 average_cost, sale_price, low_stock_threshold
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            VALUES ($1, $2, $3, $4, $5, COALESCE($6::NUMERIC, 0), $7, COALESCE($8::NUMERIC, 0))
             ON CONFLICT (tenant_id, item_id, location_id)
             DO UPDATE SET
                 quantity = inventory_levels.quantity + $4,
@@ -413,9 +768,9 @@ impl InventoryRepository {
             location_id,
             quantity_delta,
             reserved_change,
-            new_average_cost.unwrap_or(Decimal::ZERO),
+            new_average_cost,
             new_sale_price,
-            low_stock_threshold.unwrap_or(Decimal::ZERO)
+            low_stock_threshold
         )
             .fetch_one(executor)
             .await?;
@@ -436,6 +791,30 @@ impl InventoryRepository {
         notes: Option<&str>,
         position: Option<&str>,
     ) -> Result<StockMovement, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.record_transfer_movement(
+            executor, tenant_id, item_id, location_id, quantity_changed, reason,
+            unit_cost, unit_price, notes, position, None
+        ).await
+    }
+
+    // Igual ao record_stock_movement, mas vinculando a movimentação a uma transferência
+    pub async fn record_transfer_movement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity_changed: Decimal,
+        reason: StockMovementReason,
+        unit_cost: Option<Decimal>,
+        unit_price: Option<Decimal>,
+        notes: Option<&str>,
+        position: Option<&str>,
+        transfer_id: Option<Uuid>,
+    ) -> Result<StockMovement, AppError>
     where
         E: Executor<'e, Database = Postgres>,
     {
@@ -445,15 +824,15 @@ impl InventoryRepository {
             INSERT INTO stock_movements (
                 tenant_id, item_id, location_id,
                 quantity_changed, reason,
-                unit_cost, unit_price, notes, position
+                unit_cost, unit_price, notes, position, transfer_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING
                 id, tenant_id, item_id, location_id,
                 quantity_changed,
                 reason as "reason: StockMovementReason",
                 unit_cost, unit_price, notes, created_at,
-                position
+                position, transfer_id
             "#,
             tenant_id,
             item_id,
@@ -463,7 +842,8 @@ impl InventoryRepository {
             unit_cost,
             unit_price,
             notes,
-            position
+            position,
+            transfer_id
         )
             .fetch_one(executor)
             .await?;
@@ -600,4 +980,1460 @@ impl InventoryRepository {
 
         Ok(batches)
     }
-}
\ No newline at end of file
+
+    // =========================================================================
+    //  TRANSFERÊNCIAS ENTRE LOCAIS
+    // =========================================================================
+
+    pub async fn get_location_exists<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM locations WHERE tenant_id = $1 AND id = $2) as "exists!""#,
+            tenant_id,
+            location_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(exists)
+    }
+
+    // Lote específico travado para baixa (FOR UPDATE)
+    pub async fn get_batch_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        batch_number: &str,
+        position: &str,
+    ) -> Result<Option<InventoryBatch>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let batch = sqlx::query_as!(
+            InventoryBatch,
+            r#"
+            SELECT * FROM inventory_batches
+            WHERE tenant_id = $1 AND item_id = $2 AND location_id = $3
+              AND batch_number = $4 AND position = $5
+            FOR UPDATE
+            "#,
+            tenant_id,
+            item_id,
+            location_id,
+            batch_number,
+            position
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(batch)
+    }
+
+    pub async fn create_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        from_location_id: Uuid,
+        to_location_id: Uuid,
+        notes: Option<&str>,
+        created_by: Option<Uuid>,
+    ) -> Result<StockTransfer, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let transfer = sqlx::query_as!(
+            StockTransfer,
+            r#"
+            INSERT INTO stock_transfers (
+                tenant_id, from_location_id, to_location_id, status, notes, created_by
+            )
+            VALUES ($1, $2, $3, 'IN_TRANSIT', $4, $5)
+            RETURNING
+                id, tenant_id, from_location_id, to_location_id,
+                status as "status: StockTransferStatus",
+                notes, created_by, shipped_at, received_at, received_by,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            from_location_id,
+            to_location_id,
+            notes,
+            created_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(transfer)
+    }
+
+    pub async fn add_transfer_item<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        item_id: Uuid,
+        batch_number: &str,
+        expiration_date: Option<chrono::NaiveDate>,
+        from_position: &str,
+        to_position: &str,
+        quantity: Decimal,
+        unit_cost: Decimal,
+    ) -> Result<StockTransferItem, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let item = sqlx::query_as!(
+            StockTransferItem,
+            r#"
+            INSERT INTO stock_transfer_items (
+                tenant_id, transfer_id, item_id, batch_number, expiration_date,
+                from_position, to_position, quantity, unit_cost
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+            tenant_id,
+            transfer_id,
+            item_id,
+            batch_number,
+            expiration_date,
+            from_position,
+            to_position,
+            quantity,
+            unit_cost
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(item)
+    }
+
+    pub async fn get_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+    ) -> Result<Option<StockTransfer>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let transfer = sqlx::query_as!(
+            StockTransfer,
+            r#"
+            SELECT
+                id, tenant_id, from_location_id, to_location_id,
+                status as "status: StockTransferStatus",
+                notes, created_by, shipped_at, received_at, received_by,
+                created_at, updated_at
+            FROM stock_transfers
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            transfer_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(transfer)
+    }
+
+    pub async fn get_transfer_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+    ) -> Result<Option<StockTransfer>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let transfer = sqlx::query_as!(
+            StockTransfer,
+            r#"
+            SELECT
+                id, tenant_id, from_location_id, to_location_id,
+                status as "status: StockTransferStatus",
+                notes, created_by, shipped_at, received_at, received_by,
+                created_at, updated_at
+            FROM stock_transfers
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            transfer_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(transfer)
+    }
+
+    pub async fn list_transfers<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<StockTransferStatus>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<StockTransfer>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        // location_id filtra pela origem OU pelo destino
+        let transfers = sqlx::query_as!(
+            StockTransfer,
+            r#"
+            SELECT
+                id, tenant_id, from_location_id, to_location_id,
+                status as "status: StockTransferStatus",
+                notes, created_by, shipped_at, received_at, received_by,
+                created_at, updated_at
+            FROM stock_transfers
+            WHERE tenant_id = $1
+              AND ($2::stock_transfer_status IS NULL OR status = $2)
+              AND ($3::UUID IS NULL OR from_location_id = $3 OR to_location_id = $3)
+            ORDER BY shipped_at DESC
+            "#,
+            tenant_id,
+            status as Option<StockTransferStatus>,
+            location_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(transfers)
+    }
+
+    pub async fn get_transfer_items<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+    ) -> Result<Vec<StockTransferItem>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let items = sqlx::query_as!(
+            StockTransferItem,
+            r#"
+            SELECT * FROM stock_transfer_items
+            WHERE tenant_id = $1 AND transfer_id = $2
+            ORDER BY item_id, batch_number, from_position
+            "#,
+            tenant_id,
+            transfer_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(items)
+    }
+
+    pub async fn close_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        status: StockTransferStatus,
+        received_by: Option<Uuid>,
+    ) -> Result<StockTransfer, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let transfer = sqlx::query_as!(
+            StockTransfer,
+            r#"
+            UPDATE stock_transfers
+            SET status = $3, received_at = NOW(), received_by = $4, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, from_location_id, to_location_id,
+                status as "status: StockTransferStatus",
+                notes, created_by, shipped_at, received_at, received_by,
+                created_at, updated_at
+            "#,
+            tenant_id,
+            transfer_id,
+            status as StockTransferStatus,
+            received_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(transfer)
+    }
+
+    // =========================================================================
+    //  INVENTÁRIO FÍSICO (CONTAGEM)
+    // =========================================================================
+
+    pub async fn create_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        location_id: Uuid,
+        notes: Option<&str>,
+        created_by: Option<Uuid>,
+    ) -> Result<InventoryCount, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_as!(
+            InventoryCount,
+            r#"
+            INSERT INTO inventory_counts (tenant_id, location_id, notes, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id, tenant_id, location_id,
+                status as "status: InventoryCountStatus",
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            location_id,
+            notes,
+            created_by
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::InventoryCountAlreadyOpen(location_id.to_string());
+                    }
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::ResourceNotFound(format!("Local {}", location_id));
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(count)
+    }
+
+    // Congela o saldo esperado de cada lote/posição do local.
+    // Saldo sem nenhum lote (dados antigos) entra como o lote padrão.
+    pub async fn snapshot_count_lines<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        location_id: Uuid,
+        item_ids: Option<&[Uuid]>,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO inventory_count_lines (
+                tenant_id, count_id, item_id, batch_number, position,
+                expiration_date, expected_quantity, unit_cost
+            )
+            SELECT b.tenant_id, $2::UUID, b.item_id, b.batch_number, b.position,
+                   b.expiration_date, b.quantity, COALESCE(lv.average_cost, b.unit_cost)
+            FROM inventory_batches b
+            LEFT JOIN inventory_levels lv
+                   ON lv.tenant_id = b.tenant_id AND lv.item_id = b.item_id AND lv.location_id = b.location_id
+            WHERE b.tenant_id = $1 AND b.location_id = $3 AND b.quantity <> 0
+              AND ($4::UUID[] IS NULL OR b.item_id = ANY($4))
+
+            UNION ALL
+
+            SELECT lv.tenant_id, $2::UUID, lv.item_id, 'DEFAULT', 'Geral',
+                   NULL, lv.quantity, lv.average_cost
+            FROM inventory_levels lv
+            WHERE lv.tenant_id = $1 AND lv.location_id = $3 AND lv.quantity <> 0
+              AND ($4::UUID[] IS NULL OR lv.item_id = ANY($4))
+              AND NOT EXISTS (
+                  SELECT 1 FROM inventory_batches b
+                  WHERE b.tenant_id = lv.tenant_id AND b.item_id = lv.item_id
+                    AND b.location_id = lv.location_id AND b.quantity <> 0
+              )
+            "#,
+            tenant_id,
+            count_id,
+            location_id,
+            item_ids as Option<&[Uuid]>
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<Option<InventoryCount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_as!(
+            InventoryCount,
+            r#"
+            SELECT
+                id, tenant_id, location_id,
+                status as "status: InventoryCountStatus",
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM inventory_counts
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            count_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_count_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<Option<InventoryCount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_as!(
+            InventoryCount,
+            r#"
+            SELECT
+                id, tenant_id, location_id,
+                status as "status: InventoryCountStatus",
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM inventory_counts
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            count_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(count)
+    }
+
+    pub async fn list_counts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<InventoryCountStatus>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<InventoryCount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let counts = sqlx::query_as!(
+            InventoryCount,
+            r#"
+            SELECT
+                id, tenant_id, location_id,
+                status as "status: InventoryCountStatus",
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM inventory_counts
+            WHERE tenant_id = $1
+              AND ($2::inventory_count_status IS NULL OR status = $2)
+              AND ($3::UUID IS NULL OR location_id = $3)
+            ORDER BY created_at DESC
+            "#,
+            tenant_id,
+            status as Option<InventoryCountStatus>,
+            location_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(counts)
+    }
+
+    pub async fn get_count_lines<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<Vec<InventoryCountLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let lines = sqlx::query_as!(
+            InventoryCountLine,
+            r#"
+            SELECT
+                l.id, l.count_id, l.item_id,
+                i.sku as item_sku, i.name as item_name,
+                l.batch_number, l.position, l.expiration_date,
+                l.expected_quantity, l.counted_quantity, l.unit_cost,
+                (l.counted_quantity - l.expected_quantity) as "variance?",
+                ROUND((l.counted_quantity - l.expected_quantity) * l.unit_cost, 2) as "variance_value?",
+                l.counted_by, l.counted_at
+            FROM inventory_count_lines l
+            JOIN items i ON i.id = l.item_id
+            WHERE l.tenant_id = $1 AND l.count_id = $2
+            ORDER BY l.position, i.name, l.batch_number
+            "#,
+            tenant_id,
+            count_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(lines)
+    }
+
+    // Lança a contagem de um lote/posição.
+    // `accumulate` soma ao que já foi contado (vários aparelhos no mesmo lote);
+    // senão, substitui. Lote/posição fora do congelamento entra com esperado zero.
+    pub async fn upsert_count_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        location_id: Uuid,
+        item_id: Uuid,
+        batch_number: &str,
+        position: &str,
+        expiration_date: Option<chrono::NaiveDate>,
+        quantity: Decimal,
+        accumulate: bool,
+        counted_by: Option<Uuid>,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            INSERT INTO inventory_count_lines (
+                tenant_id, count_id, item_id, batch_number, position, expiration_date,
+                expected_quantity, counted_quantity, unit_cost, counted_by, counted_at
+            )
+            VALUES (
+                $1, $2, $4, $5, $6, $7,
+                0, $8,
+                COALESCE((
+                    SELECT average_cost FROM inventory_levels
+                    WHERE tenant_id = $1 AND item_id = $4 AND location_id = $3
+                ), 0),
+                $10, NOW()
+            )
+            ON CONFLICT (count_id, item_id, batch_number, position)
+            DO UPDATE SET
+                counted_quantity = CASE
+                    WHEN $9 THEN COALESCE(inventory_count_lines.counted_quantity, 0) + EXCLUDED.counted_quantity
+                    ELSE EXCLUDED.counted_quantity
+                END,
+                counted_by = EXCLUDED.counted_by,
+                counted_at = NOW()
+            "#,
+            tenant_id,
+            count_id,
+            location_id,
+            item_id,
+            batch_number,
+            position,
+            expiration_date,
+            quantity,
+            accumulate,
+            counted_by
+        )
+            .execute(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::ResourceNotFound(format!("Item {}", item_id));
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(())
+    }
+
+    // Linhas não contadas passam a valer zero (o lote sumiu)
+    pub async fn zero_uncounted_lines<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        counted_by: Option<Uuid>,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            r#"
+            UPDATE inventory_count_lines
+            SET counted_quantity = 0, counted_by = $3, counted_at = NOW()
+            WHERE tenant_id = $1 AND count_id = $2 AND counted_quantity IS NULL
+            "#,
+            tenant_id,
+            count_id,
+            counted_by
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn close_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        status: InventoryCountStatus,
+        closed_by: Option<Uuid>,
+    ) -> Result<InventoryCount, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_as!(
+            InventoryCount,
+            r#"
+            UPDATE inventory_counts
+            SET status = $3, closed_by = $4, closed_at = NOW(), updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, location_id,
+                status as "status: InventoryCountStatus",
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            count_id,
+            status as InventoryCountStatus,
+            closed_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(count)
+    }
+
+    // =========================================================================
+    //  ORDENS DE PRODUÇÃO
+    // =========================================================================
+
+    pub async fn create_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        planned_quantity: Decimal,
+        notes: Option<&str>,
+        created_by: Option<Uuid>,
+    ) -> Result<ProductionOrder, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            INSERT INTO production_orders (tenant_id, item_id, location_id, planned_quantity, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            item_id,
+            location_id,
+            planned_quantity,
+            notes,
+            created_by
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::ResourceNotFound(format!("Local {}", location_id));
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(order)
+    }
+
+    pub async fn add_production_component<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+        item_id: Uuid,
+        planned_quantity: Decimal,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            INSERT INTO production_order_components (tenant_id, production_order_id, item_id, planned_quantity)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (production_order_id, item_id)
+            DO UPDATE SET planned_quantity = production_order_components.planned_quantity + EXCLUDED.planned_quantity
+            "#,
+            tenant_id,
+            production_order_id,
+            item_id,
+            planned_quantity
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+    ) -> Result<Option<ProductionOrder>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            SELECT
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM production_orders
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            production_order_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(order)
+    }
+
+    pub async fn get_production_order_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+    ) -> Result<Option<ProductionOrder>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            SELECT
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM production_orders
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            production_order_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(order)
+    }
+
+    pub async fn list_production_orders<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<ProductionOrderStatus>,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<ProductionOrder>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let orders = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            SELECT
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            FROM production_orders
+            WHERE tenant_id = $1
+              AND ($2::production_order_status IS NULL OR status = $2)
+              AND ($3::UUID IS NULL OR item_id = $3)
+              AND ($4::UUID IS NULL OR location_id = $4)
+            ORDER BY created_at DESC
+            "#,
+            tenant_id,
+            status as Option<ProductionOrderStatus>,
+            item_id,
+            location_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(orders)
+    }
+
+    pub async fn get_production_components<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+    ) -> Result<Vec<ProductionOrderComponent>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let components = sqlx::query_as!(
+            ProductionOrderComponent,
+            r#"
+            SELECT
+                c.id, c.production_order_id, c.item_id,
+                i.sku as item_sku, i.name as item_name,
+                c.planned_quantity, c.consumed_quantity, c.total_cost
+            FROM production_order_components c
+            JOIN items i ON i.id = c.item_id
+            WHERE c.tenant_id = $1 AND c.production_order_id = $2
+            ORDER BY i.name
+            "#,
+            tenant_id,
+            production_order_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(components)
+    }
+
+    pub async fn set_production_consumption<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        component_id: Uuid,
+        consumed_quantity: Decimal,
+        total_cost: Decimal,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            UPDATE production_order_components
+            SET consumed_quantity = $3, total_cost = $4
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            component_id,
+            consumed_quantity,
+            total_cost
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+        produced_quantity: Decimal,
+        yield_percent: Decimal,
+        loss_percent: Decimal,
+        batch_number: &str,
+        position: &str,
+        expiration_date: Option<chrono::NaiveDate>,
+        total_cost: Decimal,
+        unit_cost: Decimal,
+        closed_by: Option<Uuid>,
+    ) -> Result<ProductionOrder, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            UPDATE production_orders
+            SET status = 'COMPLETED',
+                produced_quantity = $3, yield_percent = $4, loss_percent = $5,
+                batch_number = $6, position = $7, expiration_date = $8,
+                total_cost = $9, unit_cost = $10,
+                closed_by = $11, closed_at = NOW(), updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            production_order_id,
+            produced_quantity,
+            yield_percent,
+            loss_percent,
+            batch_number,
+            position,
+            expiration_date,
+            total_cost,
+            unit_cost,
+            closed_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(order)
+    }
+
+    pub async fn cancel_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+        closed_by: Option<Uuid>,
+    ) -> Result<ProductionOrder, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            ProductionOrder,
+            r#"
+            UPDATE production_orders
+            SET status = 'CANCELLED', closed_by = $3, closed_at = NOW(), updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, item_id, location_id,
+                status as "status: ProductionOrderStatus",
+                planned_quantity, produced_quantity, yield_percent, loss_percent,
+                batch_number, position, expiration_date, total_cost, unit_cost,
+                notes, created_by, closed_by, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            production_order_id,
+            closed_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(order)
+    }
+
+    // Saldo livre (físico - reservado) do item em cada local do mesmo pool do local
+    // preferido. O preferido vem primeiro; os demais, do maior saldo para o menor.
+    pub async fn get_pool_availability<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        preferred_location_id: Uuid,
+    ) -> Result<Vec<(Uuid, Decimal)>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l.id as location_id,
+                COALESCE(il.quantity - il.reserved_quantity, 0) as "available!"
+            FROM locations pref
+            JOIN locations l ON l.tenant_id = pref.tenant_id AND l.stock_pool_id = pref.stock_pool_id
+            LEFT JOIN inventory_levels il
+                ON il.tenant_id = l.tenant_id AND il.location_id = l.id AND il.item_id = $2
+            WHERE pref.tenant_id = $1 AND pref.id = $3
+            ORDER BY (l.id = $3) DESC, 2 DESC, l.name
+            "#,
+            tenant_id,
+            item_id,
+            preferred_location_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| (row.location_id, row.available)).collect())
+    }
+
+    // Saldo por item e pool, com a quebra por local (só locais com saldo registrado)
+    pub async fn get_stock_pool_availability<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        stock_pool_id: Option<Uuid>,
+    ) -> Result<Vec<PoolAvailability>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                p.id as stock_pool_id, p.name as stock_pool_name,
+                i.id as item_id, i.sku as item_sku, i.name as item_name,
+                l.id as location_id, l.name as location_name,
+                il.quantity, il.reserved_quantity
+            FROM inventory_levels il
+            JOIN locations l ON l.id = il.location_id AND l.tenant_id = il.tenant_id
+            JOIN stock_pools p ON p.id = l.stock_pool_id
+            JOIN items i ON i.id = il.item_id
+            WHERE il.tenant_id = $1
+              AND ($2::UUID IS NULL OR il.item_id = $2)
+              AND ($3::UUID IS NULL OR p.id = $3)
+            ORDER BY i.name, i.id, p.name, p.id, l.name
+            "#,
+            tenant_id,
+            item_id,
+            stock_pool_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        // Linhas já vêm agrupadas por (item, pool)
+        let mut pools: Vec<PoolAvailability> = Vec::new();
+        for row in rows {
+            let location = LocationAvailability {
+                location_id: row.location_id,
+                location_name: row.location_name,
+                quantity: row.quantity,
+                reserved_quantity: row.reserved_quantity,
+                available: row.quantity - row.reserved_quantity,
+            };
+
+            match pools.last_mut() {
+                Some(pool) if pool.item_id == row.item_id && pool.stock_pool_id == row.stock_pool_id => {
+                    pool.quantity += location.quantity;
+                    pool.reserved_quantity += location.reserved_quantity;
+                    pool.available += location.available;
+                    pool.locations.push(location);
+                }
+                _ => pools.push(PoolAvailability {
+                    stock_pool_id: row.stock_pool_id,
+                    stock_pool_name: row.stock_pool_name,
+                    item_id: row.item_id,
+                    item_sku: row.item_sku,
+                    item_name: row.item_name,
+                    quantity: location.quantity,
+                    reserved_quantity: location.reserved_quantity,
+                    available: location.available,
+                    locations: vec![location],
+                }),
+            }
+        }
+
+        Ok(pools)
+    }
+
+    // =========================================================================
+    //  RESERVAS DE ESTOQUE
+    // =========================================================================
+
+    pub async fn create_reservation<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+        order_item_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<StockReservation, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservation = sqlx::query_as!(
+            StockReservation,
+            r#"
+            INSERT INTO stock_reservations (
+                tenant_id, order_id, order_item_id, item_id, location_id, quantity, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            order_id,
+            order_item_id,
+            item_id,
+            location_id,
+            quantity,
+            expires_at
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(reservation)
+    }
+
+    // Reservas ativas da linha (uma por local, se a linha foi dividida)
+    pub async fn list_active_line_reservations_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_item_id: Uuid,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservations = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            FROM stock_reservations
+            WHERE tenant_id = $1 AND order_item_id = $2 AND status = 'ACTIVE'
+            ORDER BY created_at
+            FOR UPDATE
+            "#,
+            tenant_id,
+            order_item_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(reservations)
+    }
+
+    pub async fn get_reservation_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        reservation_id: Uuid,
+    ) -> Result<Option<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservation = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            FROM stock_reservations
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            reservation_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(reservation)
+    }
+
+    pub async fn list_order_reservations_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservations = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            FROM stock_reservations
+            WHERE tenant_id = $1 AND order_id = $2 AND status = 'ACTIVE'
+            ORDER BY created_at
+            FOR UPDATE
+            "#,
+            tenant_id,
+            order_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(reservations)
+    }
+
+    // Ativas com prazo vencido (SKIP LOCKED: não espera por um pedido em transição)
+    pub async fn list_expired_reservations_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservations = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            FROM stock_reservations
+            WHERE tenant_id = $1 AND status = 'ACTIVE' AND expires_at <= NOW()
+            ORDER BY expires_at
+            FOR UPDATE SKIP LOCKED
+            "#,
+            tenant_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(reservations)
+    }
+
+    pub async fn list_reservations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<StockReservationStatus>,
+        order_id: Option<Uuid>,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservations = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            FROM stock_reservations
+            WHERE tenant_id = $1
+              AND ($2::stock_reservation_status IS NULL OR status = $2)
+              AND ($3::UUID IS NULL OR order_id = $3)
+              AND ($4::UUID IS NULL OR item_id = $4)
+              AND ($5::UUID IS NULL OR location_id = $5)
+            ORDER BY created_at DESC
+            "#,
+            tenant_id,
+            status as Option<StockReservationStatus>,
+            order_id,
+            item_id,
+            location_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(reservations)
+    }
+
+    pub async fn update_reservation_quantity<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        reservation_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            "UPDATE stock_reservations SET quantity = $3, updated_at = NOW() WHERE tenant_id = $1 AND id = $2",
+            tenant_id,
+            reservation_id,
+            quantity
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn close_reservation<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        reservation_id: Uuid,
+        status: StockReservationStatus,
+    ) -> Result<StockReservation, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let reservation = sqlx::query_as!(
+            StockReservation,
+            r#"
+            UPDATE stock_reservations
+            SET status = $3, closed_at = NOW(), updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, order_id, order_item_id, item_id, location_id,
+                quantity, status as "status: StockReservationStatus",
+                expires_at, closed_at, created_at, updated_at
+            "#,
+            tenant_id,
+            reservation_id,
+            status as StockReservationStatus
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(reservation)
+    }
+
+    // =========================================================================
+    //  HISTÓRICO DE MOVIMENTAÇÕES / KARDEX
+    // =========================================================================
+
+    pub async fn list_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        reason: Option<StockMovementReason>,
+        position: Option<&str>,
+        start_date: Option<chrono::NaiveDate>,
+        end_date: Option<chrono::NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<StockMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            StockMovement,
+            r#"
+            SELECT
+                id, tenant_id, item_id, location_id,
+                quantity_changed,
+                reason as "reason: StockMovementReason",
+                unit_cost, unit_price, notes, created_at,
+                position, transfer_id
+            FROM stock_movements
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR item_id = $2)
+              AND ($3::UUID IS NULL OR location_id = $3)
+              AND ($4::stock_movement_reason IS NULL OR reason = $4)
+              AND ($5::TEXT IS NULL OR position = $5)
+              AND ($6::DATE IS NULL OR created_at >= $6::DATE)
+              AND ($7::DATE IS NULL OR created_at < $7::DATE + 1)
+            ORDER BY sequence_number DESC
+            LIMIT $8 OFFSET $9
+            "#,
+            tenant_id,
+            item_id,
+            location_id,
+            reason as Option<StockMovementReason>,
+            position,
+            start_date,
+            end_date,
+            limit,
+            offset
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    pub async fn count_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        reason: Option<StockMovementReason>,
+        position: Option<&str>,
+        start_date: Option<chrono::NaiveDate>,
+        end_date: Option<chrono::NaiveDate>,
+    ) -> Result<i64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "total!"
+            FROM stock_movements
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR item_id = $2)
+              AND ($3::UUID IS NULL OR location_id = $3)
+              AND ($4::stock_movement_reason IS NULL OR reason = $4)
+              AND ($5::TEXT IS NULL OR position = $5)
+              AND ($6::DATE IS NULL OR created_at >= $6::DATE)
+              AND ($7::DATE IS NULL OR created_at < $7::DATE + 1)
+            "#,
+            tenant_id,
+            item_id,
+            location_id,
+            reason as Option<StockMovementReason>,
+            position,
+            start_date,
+            end_date
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(total)
+    }
+
+    // Histórico completo do item até o fim do período, em ordem cronológica
+    // (o custo médio do kardex depende de tudo o que veio antes)
+    pub async fn get_item_movements_until<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Option<Uuid>,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<StockMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            StockMovement,
+            r#"
+            SELECT
+                id, tenant_id, item_id, location_id,
+                quantity_changed,
+                reason as "reason: StockMovementReason",
+                unit_cost, unit_price, notes, created_at,
+                position, transfer_id
+            FROM stock_movements
+            WHERE tenant_id = $1
+              AND item_id = $2
+              AND ($3::UUID IS NULL OR location_id = $3)
+              AND created_at < $4::DATE + 1
+            ORDER BY sequence_number ASC
+            "#,
+            tenant_id,
+            item_id,
+            location_id,
+            end_date
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+}
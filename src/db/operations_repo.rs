@@ -6,6 +6,7 @@ use rust_decimal::Decimal;
 use crate::{
     common::error::AppError,
     models::operations::{Pipeline, PipelineStage, Order, OrderItem, PipelineCategory, OrderDetail},
+    models::inventory::CompositionChoice,
 };
 
 #[derive(Clone)]
@@ -58,6 +59,9 @@ impl OperationsRepository {
         category: PipelineCategory,
         position: i32,
         stock_action: Option<&str>,
+        generates_receivable: bool,
+        payment_term_id: Option<Uuid>,
+        reservation_hours: Option<i32>,
     ) -> Result<PipelineStage, AppError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -68,20 +72,25 @@ impl OperationsRepository {
             PipelineStage,
             r#"
             INSERT INTO pipeline_stages (
-                tenant_id, pipeline_id, name, category, position, stock_action
+                tenant_id, pipeline_id, name, category, position, stock_action,
+                generates_receivable, payment_term_id, reservation_hours
             )
-            VALUES ($1, $2, $3, $4, $5, $6)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING
                 id, tenant_id, pipeline_id, name,
                 category as "category: PipelineCategory",
-                position, color, stock_action, generates_receivable, is_locked
+                position, color, stock_action, generates_receivable, is_locked,
+                payment_term_id, reservation_hours
             "#,
             tenant_id,
             pipeline_id,
             name,
             category as PipelineCategory,
             position,
-            final_stock_action
+            final_stock_action,
+            generates_receivable,
+            payment_term_id,
+            reservation_hours
         )
             .fetch_one(executor)
             .await?;
@@ -107,7 +116,8 @@ impl OperationsRepository {
             SELECT
                 id, tenant_id, pipeline_id, name,
                 category as "category: PipelineCategory",
-                position, color, stock_action, generates_receivable, is_locked
+                position, color, stock_action, generates_receivable, is_locked,
+                payment_term_id, reservation_hours
             FROM pipeline_stages
             WHERE tenant_id = $1 AND id = $2
             "#,
@@ -133,6 +143,9 @@ impl OperationsRepository {
         customer_id: Option<Uuid>,
         pipeline_id: Uuid,
         notes: Option<&str>,
+        payment_term_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        fulfilment_strategy: Option<&str>,
     ) -> Result<Order, AppError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -142,7 +155,8 @@ impl OperationsRepository {
             Order,
             r#"
             INSERT INTO orders (
-                tenant_id, customer_id, pipeline_id, stage_id, notes
+                tenant_id, customer_id, pipeline_id, stage_id, notes, payment_term_id,
+                location_id, fulfilment_strategy
             )
             VALUES (
                 $1, $2, $3,
@@ -151,17 +165,21 @@ impl OperationsRepository {
                     WHERE pipeline_id = $3 AND tenant_id = $1
                     ORDER BY position ASC LIMIT 1
                 ),
-                $4
+                $4, $5, $6, $7
             )
             RETURNING
                 id, tenant_id, customer_id, pipeline_id, stage_id,
                 display_id, total_amount, total_discount, tags, notes,
+                payment_term_id, location_id, fulfilment_strategy,
                 opened_at, closed_at, created_at, updated_at
             "#,
             tenant_id,
             customer_id,
             pipeline_id,
-            notes
+            notes,
+            payment_term_id,
+            location_id,
+            fulfilment_strategy.unwrap_or("FIXED")
         )
             .fetch_one(executor)
             .await?;
@@ -178,6 +196,9 @@ impl OperationsRepository {
         quantity: Decimal,
         unit_price: Decimal,
         unit_cost: Decimal,
+        unit_id: Option<Uuid>,
+        unit_quantity: Option<Decimal>,
+        location_id: Option<Uuid>,
     ) -> Result<OrderItem, AppError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -186,9 +207,10 @@ impl OperationsRepository {
             OrderItem,
             r#"
             INSERT INTO order_items (
-                tenant_id, order_id, item_id, quantity, unit_price, unit_cost
+                tenant_id, order_id, item_id, quantity, unit_price, unit_cost,
+                unit_id, unit_quantity, location_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *
             "#,
             tenant_id,
@@ -196,7 +218,10 @@ impl OperationsRepository {
             item_id,
             quantity,
             unit_price,
-            unit_cost
+            unit_cost,
+            unit_id,
+            unit_quantity,
+            location_id
         )
             .fetch_one(executor)
             .await?;
@@ -221,7 +246,7 @@ impl OperationsRepository {
             r#"
             UPDATE orders
             SET total_amount = (
-                SELECT COALESCE(SUM(quantity * unit_price - discount), 0)
+                SELECT COALESCE(SUM(COALESCE(unit_quantity, quantity) * unit_price - discount), 0)
                 FROM order_items
                 WHERE order_items.order_id = orders.id
             )
@@ -261,6 +286,117 @@ impl OperationsRepository {
         Ok(items)
     }
 
+    pub async fn add_order_item_choice<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_item_id: Uuid,
+        child_item_id: Uuid,
+        replaces_item_id: Option<Uuid>,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            INSERT INTO order_item_choices (tenant_id, order_item_id, child_item_id, replaces_item_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (order_item_id, child_item_id)
+            DO UPDATE SET replaces_item_id = EXCLUDED.replaces_item_id
+            "#,
+            tenant_id,
+            order_item_id,
+            child_item_id,
+            replaces_item_id
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_order_item_choices<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_item_id: Uuid,
+    ) -> Result<Vec<CompositionChoice>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let choices = sqlx::query_as!(
+            CompositionChoice,
+            r#"
+            SELECT child_item_id, replaces_item_id
+            FROM order_item_choices
+            WHERE tenant_id = $1 AND order_item_id = $2
+            "#,
+            tenant_id,
+            order_item_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(choices)
+    }
+
+    // Custo efetivamente consumido na baixa (ficha técnica explodida)
+    pub async fn update_order_item_cost<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_item_id: Uuid,
+        unit_cost: Decimal,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            r#"
+            UPDATE order_items SET unit_cost = $3
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+            tenant_id,
+            order_item_id,
+            unit_cost
+        )
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_order_fulfilment<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+        location_id: Option<Uuid>,
+        fulfilment_strategy: &str,
+    ) -> Result<Order, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let order = sqlx::query_as!(
+            Order,
+            r#"
+            UPDATE orders
+            SET location_id = $3, fulfilment_strategy = $4, updated_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING *
+            "#,
+            tenant_id,
+            order_id,
+            location_id,
+            fulfilment_strategy
+        )
+            .fetch_optional(executor)
+            .await?
+            .ok_or(AppError::ResourceNotFound(format!("Pedido {}", order_id)))?;
+
+        Ok(order)
+    }
+
     pub async fn update_order_stage<'e, E>(
         &self,
         executor: E,
@@ -340,4 +476,66 @@ impl OperationsRepository {
             items: vec![], // Preencheremos no Service
         })
     }
+
+    // =========================================================================
+    //  LOCAIS DE ATENDIMENTO
+    // =========================================================================
+
+    // O local precisa ser da loja (a FK sozinha não passa pelo RLS)
+    pub async fn location_exists<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM locations WHERE tenant_id = $1 AND id = $2) as "exists!""#,
+            tenant_id,
+            location_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn count_locations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<i64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM locations WHERE tenant_id = $1"#,
+            tenant_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(count)
+    }
+
+    // Pedido sem local definido: o local da loja (usado quando ela tem um único)
+    pub async fn get_default_location<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Option<Uuid>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let location_id = sqlx::query_scalar!(
+            "SELECT id FROM locations WHERE tenant_id = $1 ORDER BY created_at, id LIMIT 1",
+            tenant_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(location_id)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,447 @@
+// src/db/reconciliation_repo.rs
+
+use sqlx::{PgPool, Postgres, Executor};
+use uuid::Uuid;
+use rust_decimal::Decimal;
+use chrono::NaiveDate;
+use crate::{
+    common::error::AppError,
+    models::finance::{
+        BankLineStatus, BankStatementFormat, BankStatementImport, BankStatementLine,
+        CashMovementKind, FinancialMovement, FinancialTitle, TitleKind, TitleStatus
+    },
+};
+
+#[derive(Clone)]
+pub struct ReconciliationRepository {
+    pool: PgPool,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // =========================================================================
+    //  IMPORTAÇÕES
+    // =========================================================================
+
+    pub async fn create_import<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        format: BankStatementFormat,
+        file_name: Option<&str>,
+        imported_by: Option<Uuid>,
+    ) -> Result<BankStatementImport, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let import = sqlx::query_as!(
+            BankStatementImport,
+            r#"
+            INSERT INTO bank_statement_imports (tenant_id, account_id, format, file_name, imported_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id, tenant_id, account_id,
+                format as "format: BankStatementFormat",
+                file_name, total_lines, duplicated_lines, matched_lines,
+                imported_by, imported_at
+            "#,
+            tenant_id,
+            account_id,
+            format as BankStatementFormat,
+            file_name,
+            imported_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(import)
+    }
+
+    pub async fn update_import_counts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        import_id: Uuid,
+        total_lines: i32,
+        duplicated_lines: i32,
+        matched_lines: i32,
+    ) -> Result<BankStatementImport, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let import = sqlx::query_as!(
+            BankStatementImport,
+            r#"
+            UPDATE bank_statement_imports
+            SET total_lines = $3, duplicated_lines = $4, matched_lines = $5
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, account_id,
+                format as "format: BankStatementFormat",
+                file_name, total_lines, duplicated_lines, matched_lines,
+                imported_by, imported_at
+            "#,
+            tenant_id,
+            import_id,
+            total_lines,
+            duplicated_lines,
+            matched_lines
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(import)
+    }
+
+    pub async fn list_imports<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+    ) -> Result<Vec<BankStatementImport>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let imports = sqlx::query_as!(
+            BankStatementImport,
+            r#"
+            SELECT
+                id, tenant_id, account_id,
+                format as "format: BankStatementFormat",
+                file_name, total_lines, duplicated_lines, matched_lines,
+                imported_by, imported_at
+            FROM bank_statement_imports
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR account_id = $2)
+            ORDER BY imported_at DESC
+            "#,
+            tenant_id,
+            account_id
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(imports)
+    }
+
+    // =========================================================================
+    //  LINHAS DO EXTRATO
+    // =========================================================================
+
+    /// Insere a linha. Se o mesmo `external_id` já foi importado para a conta,
+    /// não faz nada e retorna None (reimportação do mesmo arquivo).
+    pub async fn insert_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        import_id: Uuid,
+        account_id: Uuid,
+        external_id: &str,
+        posted_date: NaiveDate,
+        amount: Decimal,
+        description: Option<&str>,
+    ) -> Result<Option<BankStatementLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let line = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            INSERT INTO bank_statement_lines (
+                tenant_id, import_id, account_id, external_id, posted_date, amount, description
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (account_id, external_id) DO NOTHING
+            RETURNING
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            "#,
+            tenant_id,
+            import_id,
+            account_id,
+            external_id,
+            posted_date,
+            amount,
+            description
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(line)
+    }
+
+    pub async fn list_lines<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        import_id: Option<Uuid>,
+        status: Option<BankLineStatus>,
+    ) -> Result<Vec<BankStatementLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let lines = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            SELECT
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            FROM bank_statement_lines
+            WHERE tenant_id = $1
+              AND ($2::UUID IS NULL OR account_id = $2)
+              AND ($3::UUID IS NULL OR import_id = $3)
+              AND ($4::bank_line_status IS NULL OR status = $4)
+            ORDER BY posted_date ASC, created_at ASC
+            "#,
+            tenant_id,
+            account_id,
+            import_id,
+            status as Option<BankLineStatus>
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(lines)
+    }
+
+    pub async fn get_line_for_update<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+    ) -> Result<Option<BankStatementLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let line = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            SELECT
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            FROM bank_statement_lines
+            WHERE tenant_id = $1 AND id = $2
+            FOR UPDATE
+            "#,
+            tenant_id,
+            line_id
+        )
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(line)
+    }
+
+    /// Concilia a linha com uma movimentação. `reviewed_by` vazio = conciliação automática.
+    pub async fn mark_line_matched<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        movement_id: Uuid,
+        reviewed_by: Option<Uuid>,
+    ) -> Result<BankStatementLine, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let line = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            UPDATE bank_statement_lines
+            SET status = 'MATCHED', matched_movement_id = $3,
+                reviewed_by = $4, reviewed_at = CASE WHEN $4::UUID IS NULL THEN NULL ELSE NOW() END
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            "#,
+            tenant_id,
+            line_id,
+            movement_id,
+            reviewed_by
+        )
+            .fetch_one(executor)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::MovementAlreadyReconciled(movement_id.to_string());
+                    }
+                }
+                e.into()
+            })?;
+
+        Ok(line)
+    }
+
+    pub async fn mark_line_ignored<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        reviewed_by: Uuid,
+    ) -> Result<BankStatementLine, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let line = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            UPDATE bank_statement_lines
+            SET status = 'IGNORED', reviewed_by = $3, reviewed_at = NOW()
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            "#,
+            tenant_id,
+            line_id,
+            reviewed_by
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(line)
+    }
+
+    pub async fn set_line_suggestion<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<BankStatementLine, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let line = sqlx::query_as!(
+            BankStatementLine,
+            r#"
+            UPDATE bank_statement_lines
+            SET suggested_title_id = $3
+            WHERE tenant_id = $1 AND id = $2
+            RETURNING
+                id, tenant_id, import_id, account_id, external_id, posted_date, amount, description,
+                status as "status: BankLineStatus",
+                matched_movement_id, suggested_title_id,
+                reviewed_by, reviewed_at, created_at
+            "#,
+            tenant_id,
+            line_id,
+            title_id
+        )
+            .fetch_one(executor)
+            .await?;
+
+        Ok(line)
+    }
+
+    // =========================================================================
+    //  CANDIDATOS À CONCILIAÇÃO
+    // =========================================================================
+
+    /// Movimentações da conta com o mesmo valor, dentro da janela de datas,
+    /// que ainda não foram conciliadas nem estornadas. Mais próximas da data primeiro.
+    pub async fn find_movement_candidates<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        amount: Decimal,
+        posted_date: NaiveDate,
+        window_days: i32,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let movements = sqlx::query_as!(
+            FinancialMovement,
+            r#"
+            SELECT
+                m.id, m.tenant_id, m.account_id, m.title_id, m.amount, m.movement_date,
+                m.interest_amount, m.fee_amount, m.description,
+                m.cash_session_id, m.cash_kind as "cash_kind: CashMovementKind", m.transfer_id,
+                m.created_by, m.reversal_of, m.reversed_at, m.reversal_reason, m.created_at
+            FROM financial_movements m
+            WHERE m.tenant_id = $1 AND m.account_id = $2
+              AND m.amount = $3
+              AND m.movement_date BETWEEN $4::DATE - $5::INT AND $4::DATE + $5::INT
+              AND m.reversed_at IS NULL
+              AND m.reversal_of IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM bank_statement_lines l WHERE l.matched_movement_id = m.id
+              )
+            ORDER BY ABS(m.movement_date - $4::DATE) ASC, m.created_at ASC
+            "#,
+            tenant_id,
+            account_id,
+            amount,
+            posted_date,
+            window_days
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(movements)
+    }
+
+    /// Títulos em aberto do tipo informado com saldo igual ao valor e vencimento
+    /// dentro da janela de datas. Vencimentos mais próximos primeiro.
+    pub async fn find_title_candidates<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        kind: TitleKind,
+        amount: Decimal,
+        posted_date: NaiveDate,
+        window_days: i32,
+    ) -> Result<Vec<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let titles = sqlx::query_as!(
+            FinancialTitle,
+            r#"
+            SELECT
+                id, tenant_id, description,
+                kind as "kind: TitleKind",
+                status as "status: TitleStatus",
+                amount_original, amount_balance,
+                due_date, competence_date,
+                category_id, customer_id, order_id,
+                installment_group_id, installment_number, installment_count, recurring_title_id,
+                created_at, updated_at
+            FROM financial_titles
+            WHERE tenant_id = $1 AND kind = $2
+              AND status IN ('PENDING', 'PARTIAL', 'OVERDUE')
+              AND amount_balance = $3
+              AND due_date BETWEEN $4::DATE - $5::INT AND $4::DATE + $5::INT
+            ORDER BY ABS(due_date - $4::DATE) ASC, created_at ASC
+            "#,
+            tenant_id,
+            kind as TitleKind,
+            amount,
+            posted_date,
+            window_days
+        )
+            .fetch_all(executor)
+            .await?;
+
+        Ok(titles)
+    }
+}
@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use sqlx::{Executor, PgPool, Postgres};
 use uuid::Uuid;
 use crate::{
@@ -40,8 +41,11 @@ impl SettingsRepository {
             None => Ok(TenantSettings {
                 tenant_id,
                 logo_url: None, primary_color: None, company_name: None,
-                document_number: None, address: None, phone: None,
+                document_number: None, address: None, city: None, phone: None,
                 email: None, pix_key: None, pix_key_type: None,
+                late_fee_percent: Decimal::ZERO, daily_interest_percent: Decimal::ZERO,
+                budget_alert_percent: Decimal::ONE_HUNDRED,
+                shared_pool_selling: false,
                 updated_at: None,
             })
         }
@@ -60,14 +64,27 @@ impl SettingsRepository {
         let settings = sqlx::query_as!(
             TenantSettings,
             r#"
-            INSERT INTO tenant_settings (tenant_id, company_name, document_number, pix_key, address)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO tenant_settings (
+                tenant_id, company_name, document_number, pix_key, pix_key_type, address, city,
+                late_fee_percent, daily_interest_percent, budget_alert_percent, shared_pool_selling
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7,
+                COALESCE($8::NUMERIC, 0), COALESCE($9::NUMERIC, 0), COALESCE($10::NUMERIC, 100),
+                COALESCE($11::BOOLEAN, FALSE)
+            )
             ON CONFLICT (tenant_id)
             DO UPDATE SET
                 company_name = EXCLUDED.company_name,
                 document_number = EXCLUDED.document_number,
                 pix_key = EXCLUDED.pix_key,
+                pix_key_type = EXCLUDED.pix_key_type,
                 address = EXCLUDED.address,
+                city = EXCLUDED.city,
+                late_fee_percent = COALESCE($8, tenant_settings.late_fee_percent),
+                daily_interest_percent = COALESCE($9, tenant_settings.daily_interest_percent),
+                budget_alert_percent = COALESCE($10, tenant_settings.budget_alert_percent),
+                shared_pool_selling = COALESCE($11, tenant_settings.shared_pool_selling),
                 updated_at = NOW()
             RETURNING *
             "#,
@@ -75,7 +92,13 @@ impl SettingsRepository {
             input.company_name,
             input.document_number,
             input.pix_key,
-            input.address
+            input.pix_key_type,
+            input.address,
+            input.city,
+            input.late_fee_percent,
+            input.daily_interest_percent,
+            input.budget_alert_percent,
+            input.shared_pool_selling
         )
             .fetch_one(executor)
             .await?;
@@ -37,6 +37,15 @@ impl TenantRepository {
         Ok(result.exists.unwrap_or(false))
     }
 
+    /// Retorna o ID de todas as lojas (usado pelas rotinas em segundo plano).
+    pub async fn list_all_tenant_ids(&self) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar!("SELECT id FROM tenants ORDER BY created_at ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ids)
+    }
+
     /// Retorna todos os tenants ativos aos quais o utilizador tem acesso.
     /// Atualizado para usar tenant_members e verificar is_active.
     pub async fn get_tenants_for_user(&self, user_id: Uuid) -> Result<Vec<Tenant>, AppError> {
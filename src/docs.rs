@@ -25,12 +25,40 @@ use crate::models;
         handlers::inventory::get_all_items,
         handlers::inventory::add_composition_item,
         handlers::inventory::get_item_composition,
+        handlers::inventory::get_where_used,
+        handlers::inventory::get_flattened_composition,
         handlers::inventory::create_unit_of_measure,
         handlers::inventory::get_all_units,
+        handlers::inventory::create_unit_conversion,
+        handlers::inventory::list_unit_conversions,
+        handlers::inventory::delete_unit_conversion,
+        handlers::inventory::set_item_units,
         handlers::inventory::create_category,
         handlers::inventory::get_all_categories,
         handlers::inventory::add_stock,
         handlers::inventory::sell_item,
+        handlers::inventory::create_transfer,
+        handlers::inventory::list_transfers,
+        handlers::inventory::get_transfer,
+        handlers::inventory::receive_transfer,
+        handlers::inventory::cancel_transfer,
+        handlers::inventory::open_count,
+        handlers::inventory::list_counts,
+        handlers::inventory::get_count,
+        handlers::inventory::record_counts,
+        handlers::inventory::approve_count,
+        handlers::inventory::cancel_count,
+        handlers::inventory::get_count_report,
+        handlers::inventory::create_production_order,
+        handlers::inventory::list_production_orders,
+        handlers::inventory::get_production_order,
+        handlers::inventory::complete_production_order,
+        handlers::inventory::cancel_production_order,
+        handlers::inventory::list_reservations,
+        handlers::inventory::release_reservation,
+        handlers::inventory::get_pool_availability,
+        handlers::inventory::list_movements,
+        handlers::inventory::get_item_kardex,
 
         // --- RBAC ---
         handlers::rbac::create_role,
@@ -46,6 +74,7 @@ use crate::models;
         // --- Dashboard ---
         handlers::dashboard::get_summary,
         handlers::dashboard::get_sales_chart,
+        handlers::dashboard::get_cash_flow,
         handlers::dashboard::get_top_products,
 
         // --- OPERATIONS ---
@@ -53,7 +82,59 @@ use crate::models;
         handlers::operations::add_stage,
         handlers::operations::create_order,
         handlers::operations::add_order_item,
+        handlers::operations::set_order_fulfilment,
         handlers::operations::transition_order,
+
+        // --- FINANCE ---
+        handlers::finance::create_account,
+        handlers::finance::list_accounts,
+        handlers::finance::get_account_statement,
+        handlers::finance::create_category,
+        handlers::finance::list_categories,
+        handlers::finance::update_category,
+        handlers::finance::get_category_tree,
+        handlers::finance::create_title,
+        handlers::finance::list_titles,
+        handlers::finance::get_title,
+        handlers::finance::get_title_pix,
+        handlers::finance::settle_title,
+        handlers::finance::list_title_movements,
+        handlers::finance::reverse_movement,
+        handlers::finance::create_payment_term,
+        handlers::finance::list_payment_terms,
+        handlers::finance::create_recurring_title,
+        handlers::finance::list_recurring_titles,
+        handlers::finance::update_recurring_title,
+        handlers::finance::open_cash_session,
+        handlers::finance::list_cash_sessions,
+        handlers::finance::post_cash_movement,
+        handlers::finance::close_cash_session,
+        handlers::finance::get_cash_session_report,
+        handlers::finance::create_transfer,
+        handlers::finance::get_dre_report,
+        handlers::finance::set_budget,
+        handlers::finance::list_budgets,
+        handlers::finance::delete_budget,
+        handlers::finance::get_budget_report,
+
+        // --- RECONCILIATION ---
+        handlers::reconciliation::import_bank_statement,
+        handlers::reconciliation::list_bank_imports,
+        handlers::reconciliation::list_bank_lines,
+        handlers::reconciliation::match_bank_line,
+        handlers::reconciliation::settle_bank_line,
+        handlers::reconciliation::create_title_from_bank_line,
+        handlers::reconciliation::ignore_bank_line,
+
+        // --- BILLING (BOLETOS / CNAB) ---
+        handlers::billing::get_billing_config,
+        handlers::billing::upsert_billing_config,
+        handlers::billing::create_boleto,
+        handlers::billing::list_boletos,
+        handlers::billing::get_boleto,
+        handlers::billing::generate_remittance,
+        handlers::billing::import_cnab_return,
+        handlers::billing::list_cnab_files,
     ),
     components(
         schemas(
@@ -61,6 +142,9 @@ use crate::models;
             // --- DASHBOARD ---
             models::dashboard::DashboardSummary,
             models::dashboard::SalesChartEntry,
+            models::dashboard::CashFlowPoint,
+            models::dashboard::CashFlowProjection,
+            models::dashboard::BudgetAlert,
             models::dashboard::TopProductEntry,
 
             // --- Settings ---
@@ -88,20 +172,56 @@ use crate::models;
             models::inventory::CompositionType,
             models::inventory::Item,
             models::inventory::CompositionEntry,
+            models::inventory::CompositionChoice,
+            models::inventory::WhereUsedEntry,
+            models::inventory::FlattenedBomLine,
+            models::inventory::FlattenedBom,
             models::inventory::Category,
             models::inventory::InventoryLevel,
             models::inventory::StockMovementReason,
             models::inventory::StockMovement,
             models::inventory::UnitOfMeasure,
+            models::inventory::UnitConversion,
             models::inventory::InventoryBatch,
+            models::inventory::StockTransferStatus,
+            models::inventory::StockTransfer,
+            models::inventory::StockTransferItem,
+            models::inventory::StockTransferLine,
+            models::inventory::StockTransferDetail,
+            models::inventory::InventoryCountStatus,
+            models::inventory::InventoryCount,
+            models::inventory::InventoryCountLine,
+            models::inventory::InventoryCountDetail,
+            models::inventory::InventoryCountEntry,
+            models::inventory::InventoryCountReport,
+            models::inventory::ProductionOrderStatus,
+            models::inventory::ProductionOrder,
+            models::inventory::ProductionOrderComponent,
+            models::inventory::ProductionOrderDetail,
+            models::inventory::ProductionConsumption,
+            models::inventory::StockReservationStatus,
+            models::inventory::StockReservation,
+            models::inventory::LocationAvailability,
+            models::inventory::PoolAvailability,
+            models::inventory::StockMovementPage,
+            models::inventory::KardexLine,
+            models::inventory::ItemKardex,
 
             // --- Payloads ---
             handlers::inventory::CreateItemPayload,
             handlers::inventory::AddCompositionPayload,
             handlers::inventory::CreateUnitPayload,
+            handlers::inventory::CreateUnitConversionPayload,
+            handlers::inventory::SetItemUnitsPayload,
             handlers::inventory::CreateCategoryPayload,
             handlers::inventory::AddStockPayload,
             handlers::inventory::SellItemPayload,
+            handlers::inventory::CreateStockTransferPayload,
+            handlers::inventory::OpenInventoryCountPayload,
+            handlers::inventory::RecordCountsPayload,
+            handlers::inventory::ApproveCountPayload,
+            handlers::inventory::CreateProductionOrderPayload,
+            handlers::inventory::CompleteProductionOrderPayload,
 
             // --- RBAC ---
             models::rbac::Role,
@@ -129,15 +249,73 @@ use crate::models;
             models::finance::TitleStatus,
             models::finance::FinancialAccount,
             models::finance::FinancialCategory,
+            models::finance::CategoryTreeNode,
+            models::finance::CategoryTree,
             models::finance::FinancialTitle,
             models::finance::FinancialMovement,
+            models::finance::TitleSettlement,
+            models::finance::PixCharge,
+            models::finance::PaymentTerm,
+            models::finance::RecurrenceFrequency,
+            models::finance::RecurringTitle,
+            models::finance::CashSessionStatus,
+            models::finance::CashMovementKind,
+            models::finance::CashSession,
+            models::finance::CashSessionReport,
+            models::finance::AccountTransfer,
+            models::finance::StatementLine,
+            models::finance::AccountStatement,
+            models::finance::DreLine,
+            models::finance::DreReport,
+            models::finance::CategoryBudget,
+            models::finance::BudgetLine,
+            models::finance::BudgetReport,
+            models::finance::BankStatementFormat,
+            models::finance::BankLineStatus,
+            models::finance::BankStatementImport,
+            models::finance::BankStatementLine,
+            models::finance::BankImportResult,
+            models::finance::BankLineResolution,
+            models::finance::CnabLayout,
+            models::finance::CnabFileKind,
+            models::finance::BoletoStatus,
+            models::finance::BillingConfig,
+            models::finance::Boleto,
+            models::finance::CnabFile,
+            models::finance::CnabReturnEntry,
+            models::finance::CnabReturnResult,
 
             // --- OPERATIONS PAYLOADS ---
             handlers::operations::CreatePipelinePayload,
             handlers::operations::AddStagePayload,
             handlers::operations::CreateOrderPayload,
             handlers::operations::AddOrderItemPayload,
+            handlers::operations::SetOrderFulfilmentPayload,
             handlers::operations::TransitionOrderPayload,
+
+            // --- FINANCE PAYLOADS ---
+            handlers::finance::CreateAccountPayload,
+            handlers::finance::CreateFinancialCategoryPayload,
+            handlers::finance::UpdateFinancialCategoryPayload,
+            handlers::finance::CreateTitlePayload,
+            handlers::finance::SettleTitlePayload,
+            handlers::finance::ReverseMovementPayload,
+            handlers::finance::CreatePaymentTermPayload,
+            handlers::finance::CreateRecurringTitlePayload,
+            handlers::finance::UpdateRecurringTitlePayload,
+            handlers::finance::OpenCashSessionPayload,
+            handlers::finance::CashMovementPayload,
+            handlers::finance::CloseCashSessionPayload,
+            handlers::finance::TransferPayload,
+            handlers::finance::SetBudgetPayload,
+            handlers::reconciliation::ImportBankStatementPayload,
+            handlers::reconciliation::MatchBankLinePayload,
+            handlers::reconciliation::SettleBankLinePayload,
+            handlers::reconciliation::CreateTitleFromBankLinePayload,
+            handlers::billing::BillingConfigPayload,
+            handlers::billing::CreateBoletoPayload,
+            handlers::billing::GenerateRemittancePayload,
+            handlers::billing::ImportCnabReturnPayload,
         )
     ),
     tags(
@@ -149,7 +327,10 @@ use crate::models;
         (name = "RBAC", description = "Controle de Acesso (Cargos e Permissões)"),
         (name = "Tenancy", description = "Gestão de Lojas e Acesso"),
         (name = "Tenancy Setup", description = "Configuração Física da Loja (Estoques e Locais)"),
-        (name = "Dashboard", description = "Indicadores e Gráficos Gerenciais")
+        (name = "Dashboard", description = "Indicadores e Gráficos Gerenciais"),
+        (name = "Finance", description = "Financeiro (Contas, Categorias e Títulos a Pagar/Receber)"),
+        (name = "Reconciliation", description = "Conciliação Bancária (Importação de Extratos OFX/CSV)"),
+        (name = "Billing", description = "Cobrança Bancária (Boletos e Arquivos CNAB 240/400)")
     ),
     modifiers(&SecurityAddon)
 )]
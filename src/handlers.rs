@@ -6,4 +6,7 @@ pub(crate) mod rbac;
 pub(crate) mod operations;
 pub(crate) mod dashboard;
 pub(crate) mod documents;
-pub(crate) mod settings;
\ No newline at end of file
+pub(crate) mod settings;
+pub(crate) mod finance;
+pub(crate) mod reconciliation;
+pub(crate) mod billing;
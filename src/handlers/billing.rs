@@ -0,0 +1,453 @@
+// src/handlers/billing.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    common::{
+        error::{ApiError, AppError},
+        db_utils::get_rls_connection,
+    },
+    config::AppState,
+    middleware::{
+        auth::AuthenticatedUser,
+        i18n::Locale,
+        tenancy::TenantContext,
+        rbac::{RequirePermission, PermFinanceRead, PermFinanceWrite},
+    },
+    models::finance::{
+        BillingConfig, Boleto, BoletoStatus, CnabFile, CnabFileKind, CnabLayout, CnabReturnResult
+    },
+};
+
+// =============================================================================
+//  1. CONVÊNIO DE COBRANÇA (por conta bancária)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingConfigPayload {
+    /// 001 (Banco do Brasil), 237 (Bradesco) ou 341 (Itaú)
+    #[validate(length(equal = 3))]
+    #[schema(example = "237")]
+    pub bank_code: String,
+
+    #[validate(length(min = 1, max = 5))]
+    #[schema(example = "1234")]
+    pub agency: String,
+
+    #[validate(length(max = 1))]
+    #[schema(example = "5")]
+    pub agency_digit: Option<String>,
+
+    #[validate(length(min = 1, max = 12))]
+    #[schema(example = "0012345")]
+    pub account_number: String,
+
+    #[validate(length(max = 1))]
+    #[schema(example = "6")]
+    pub account_digit: Option<String>,
+
+    /// Carteira de cobrança
+    #[validate(length(min = 1, max = 3))]
+    #[schema(example = "09")]
+    pub wallet: String,
+
+    /// Convênio / Código do beneficiário no banco
+    #[validate(length(min = 1, max = 20))]
+    #[schema(example = "4567890")]
+    pub agreement_code: String,
+
+    #[schema(example = "Cnab240")]
+    pub layout: Option<CnabLayout>,
+
+    /// Para continuar a sequência de outro sistema. Vazio = mantém a atual.
+    #[validate(range(min = 1))]
+    #[schema(example = 1)]
+    pub next_our_number: Option<i64>,
+}
+
+// PUT /api/finance/accounts/{id}/billing-config
+#[utoipa::path(
+    put,
+    path = "/api/finance/accounts/{account_id}/billing-config",
+    tag = "Billing",
+    request_body = BillingConfigPayload,
+    responses(
+        (status = 200, description = "Convênio salvo", body = BillingConfig),
+        (status = 400, description = "Dados incompatíveis com o banco"),
+        (status = 404, description = "Conta não encontrada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("account_id" = Uuid, Path, description = "ID da Conta"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn upsert_billing_config(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<BillingConfigPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let config = app_state.billing_service
+        .upsert_config(
+            &mut *rls_conn,
+            tenant.0,
+            account_id,
+            &payload.bank_code,
+            &payload.agency,
+            payload.agency_digit.as_deref(),
+            &payload.account_number,
+            payload.account_digit.as_deref(),
+            &payload.wallet,
+            &payload.agreement_code,
+            payload.layout.unwrap_or(CnabLayout::Cnab240),
+            payload.next_our_number,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(config)))
+}
+
+// GET /api/finance/accounts/{id}/billing-config
+#[utoipa::path(
+    get,
+    path = "/api/finance/accounts/{account_id}/billing-config",
+    tag = "Billing",
+    responses(
+        (status = 200, description = "Convênio de cobrança da conta", body = BillingConfig),
+        (status = 404, description = "Conta sem convênio")
+    ),
+    params(
+        ("account_id" = Uuid, Path, description = "ID da Conta"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_billing_config(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(account_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let config = app_state.billing_service
+        .get_config(&mut *rls_conn, tenant.0, account_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(config)))
+}
+
+// =============================================================================
+//  2. BOLETOS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBoletoPayload {
+    /// Conta bancária (com convênio) que vai receber
+    pub account_id: Uuid,
+}
+
+// POST /api/finance/titles/{id}/boleto
+#[utoipa::path(
+    post,
+    path = "/api/finance/titles/{title_id}/boleto",
+    tag = "Billing",
+    request_body = CreateBoletoPayload,
+    responses(
+        (status = 201, description = "Boleto emitido (aguardando remessa)", body = Boleto),
+        (status = 400, description = "Título a pagar, vencido ou sem cliente com CPF/CNPJ"),
+        (status = 404, description = "Título ou convênio não encontrado"),
+        (status = 409, description = "Título pago/cancelado ou já com boleto em aberto"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("title_id" = Uuid, Path, description = "ID do Título"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_boleto(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(title_id): Path<Uuid>,
+    Json(payload): Json<CreateBoletoPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let boleto = app_state.billing_service
+        .create_boleto(&mut *rls_conn, tenant.0, title_id, payload.account_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(boleto)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListBoletosQuery {
+    pub account_id: Option<Uuid>,
+    pub title_id: Option<Uuid>,
+    pub status: Option<BoletoStatus>,
+}
+
+// GET /api/finance/boletos
+#[utoipa::path(
+    get,
+    path = "/api/finance/boletos",
+    tag = "Billing",
+    responses(
+        (status = 200, description = "Boletos (por vencimento)", body = Vec<Boleto>)
+    ),
+    params(
+        ListBoletosQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_boletos(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListBoletosQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let boletos = app_state.billing_service
+        .list_boletos(&mut *rls_conn, tenant.0, filters.account_id, filters.title_id, filters.status)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(boletos)))
+}
+
+// GET /api/finance/boletos/{id}
+#[utoipa::path(
+    get,
+    path = "/api/finance/boletos/{boleto_id}",
+    tag = "Billing",
+    responses(
+        (status = 200, description = "Detalhe do boleto", body = Boleto),
+        (status = 404, description = "Boleto não encontrado")
+    ),
+    params(
+        ("boleto_id" = Uuid, Path, description = "ID do Boleto"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_boleto(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(boleto_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let boleto = app_state.billing_service
+        .get_boleto(&mut *rls_conn, tenant.0, boleto_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(boleto)))
+}
+
+// =============================================================================
+//  3. ARQUIVOS CNAB (REMESSA / RETORNO)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateRemittancePayload {
+    pub account_id: Uuid,
+}
+
+// POST /api/finance/cnab/remittances
+#[utoipa::path(
+    post,
+    path = "/api/finance/cnab/remittances",
+    tag = "Billing",
+    request_body = GenerateRemittancePayload,
+    responses(
+        (status = 201, description = "Remessa gerada com os boletos pendentes da conta", body = CnabFile),
+        (status = 400, description = "Nenhum boleto pendente ou loja sem CNPJ"),
+        (status = 404, description = "Conta sem convênio"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn generate_remittance(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<GenerateRemittancePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let file = app_state.billing_service
+        .generate_remittance(&mut *rls_conn, tenant.0, payload.account_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(file)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCnabReturnPayload {
+    pub account_id: Uuid,
+
+    #[validate(length(max = 255))]
+    #[schema(example = "CB160101.RET")]
+    pub file_name: Option<String>,
+
+    /// Conteúdo do arquivo de retorno (texto)
+    #[validate(length(min = 1, message = "required"))]
+    pub content: String,
+}
+
+// POST /api/finance/cnab/returns
+#[utoipa::path(
+    post,
+    path = "/api/finance/cnab/returns",
+    tag = "Billing",
+    request_body = ImportCnabReturnPayload,
+    responses(
+        (status = 201, description = "Retorno processado (liquidações baixadas)", body = CnabReturnResult),
+        (status = 400, description = "Arquivo inválido ou de outro banco"),
+        (status = 404, description = "Conta sem convênio"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn import_cnab_return(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<ImportCnabReturnPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let result = app_state.billing_service
+        .import_return(
+            &mut *rls_conn,
+            tenant.0,
+            payload.account_id,
+            payload.file_name.as_deref(),
+            &payload.content,
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListCnabFilesQuery {
+    pub account_id: Option<Uuid>,
+    pub kind: Option<CnabFileKind>,
+}
+
+// GET /api/finance/cnab/files
+#[utoipa::path(
+    get,
+    path = "/api/finance/cnab/files",
+    tag = "Billing",
+    responses(
+        (status = 200, description = "Remessas e retornos (mais recentes primeiro)", body = Vec<CnabFile>)
+    ),
+    params(
+        ListCnabFilesQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_cnab_files(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListCnabFilesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let files = app_state.billing_service
+        .list_cnab_files(&mut *rls_conn, tenant.0, filters.account_id, filters.kind)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(files)))
+}
@@ -1,11 +1,13 @@
 // src/handlers/dashboard.rs
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
+use serde::Deserialize;
+use utoipa::IntoParams;
 use uuid::Uuid; // Importante para o Swagger params
 
 use crate::{
@@ -20,7 +22,7 @@ use crate::{
         tenancy::TenantContext,
     },
     // Importamos os models para referenciar no Swagger
-    models::dashboard::{DashboardSummary, SalesChartEntry, TopProductEntry},
+    models::dashboard::{CashFlowProjection, DashboardSummary, SalesChartEntry, TopProductEntry},
 };
 
 // GET /api/dashboard/summary
@@ -93,6 +95,58 @@ pub async fn get_sales_chart(
     Ok((StatusCode::OK, Json(chart)))
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct CashFlowQuery {
+    /// Dias projetados à frente (padrão: 30, máximo: 365)
+    pub days: Option<i64>,
+    /// Dias realizados para trás (padrão: 30, máximo: 365)
+    pub history_days: Option<i64>,
+}
+
+// GET /api/dashboard/cash-flow
+#[utoipa::path(
+    get,
+    path = "/api/dashboard/cash-flow",
+    tag = "Dashboard",
+    responses(
+        (status = 200, description = "Fluxo de caixa diário e semanal: realizado x projetado", body = CashFlowProjection),
+        (status = 400, description = "Horizonte inválido")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja"),
+        CashFlowQuery
+    ),
+    security(
+        ("api_jwt" = [])
+    )
+)]
+pub async fn get_cash_flow(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    Query(query): Query<CashFlowQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let cash_flow = app_state.dashboard_service
+        .get_cash_flow(
+            &mut *rls_conn,
+            tenant.0,
+            query.days.unwrap_or(30),
+            query.history_days.unwrap_or(30),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(cash_flow)))
+}
+
 // GET /api/dashboard/top-products
 #[utoipa::path(
     get,
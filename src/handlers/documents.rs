@@ -1,7 +1,7 @@
 // src/handlers/documents.rs
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
 };
@@ -10,7 +10,13 @@ use uuid::Uuid;
 use crate::{
     common::{error::{ApiError, AppError}, db_utils::get_rls_connection},
     config::AppState,
-    middleware::{auth::AuthenticatedUser, i18n::Locale, tenancy::TenantContext},
+    handlers::finance::StatementQuery,
+    middleware::{
+        auth::AuthenticatedUser,
+        i18n::Locale,
+        tenancy::TenantContext,
+        rbac::{RequirePermission, PermCashOperate, PermFinanceRead},
+    },
 };
 
 pub async fn generate_order_pdf(
@@ -37,4 +43,141 @@ pub async fn generate_order_pdf(
     ];
 
     Ok((headers, pdf_bytes).into_response())
-}
\ No newline at end of file
+}
+
+pub async fn generate_cash_session_pdf(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let pdf_bytes = app_state.document_service
+        .generate_cash_session_pdf(&mut *rls_conn, tenant.0, session_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf"),
+        (header::CONTENT_DISPOSITION, &format!("attachment; filename=\"caixa_{}.pdf\"", session_id)),
+    ];
+
+    Ok((headers, pdf_bytes).into_response())
+}
+
+pub async fn generate_account_statement_pdf(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(account_id): Path<Uuid>,
+    Query(period): Query<StatementQuery>,
+) -> Result<Response, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let pdf_bytes = app_state.document_service
+        .generate_account_statement_pdf(&mut *rls_conn, tenant.0, account_id, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf"),
+        (header::CONTENT_DISPOSITION, &format!("attachment; filename=\"extrato_{}.pdf\"", account_id)),
+    ];
+
+    Ok((headers, pdf_bytes).into_response())
+}
+
+pub async fn generate_account_statement_csv(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(account_id): Path<Uuid>,
+    Query(period): Query<StatementQuery>,
+) -> Result<Response, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let csv_bytes = app_state.document_service
+        .generate_account_statement_csv(&mut *rls_conn, tenant.0, account_id, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+        (header::CONTENT_DISPOSITION, &format!("attachment; filename=\"extrato_{}.csv\"", account_id)),
+    ];
+
+    Ok((headers, csv_bytes).into_response())
+}
+
+pub async fn generate_boleto_pdf(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(boleto_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let pdf_bytes = app_state.document_service
+        .generate_boleto_pdf(&mut *rls_conn, tenant.0, boleto_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf"),
+        (header::CONTENT_DISPOSITION, &format!("attachment; filename=\"boleto_{}.pdf\"", boleto_id)),
+    ];
+
+    Ok((headers, pdf_bytes).into_response())
+}
+
+/// Download do arquivo CNAB (remessa para enviar ao banco ou retorno importado)
+pub async fn download_cnab_file(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(file_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let file = app_state.billing_service
+        .get_cnab_file(&mut *rls_conn, tenant.0, file_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    // O nome do retorno vem do usuário: sem aspas nem quebras no header
+    let file_name = file.file_name
+        .map(|name| name.replace(['"', '\r', '\n'], ""))
+        .unwrap_or_else(|| format!("cnab_{}.txt", file_id));
+    let headers = [
+        (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+        (header::CONTENT_DISPOSITION, &format!("attachment; filename=\"{}\"", file_name)),
+    ];
+
+    Ok((headers, file.content).into_response())
+}
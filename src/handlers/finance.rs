@@ -0,0 +1,1719 @@
+// src/handlers/finance.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    common::{
+        error::{ApiError, AppError},
+        db_utils::get_rls_connection,
+    },
+    config::AppState,
+    middleware::{
+        auth::AuthenticatedUser,
+        i18n::Locale,
+        tenancy::TenantContext,
+        rbac::{RequirePermission, PermCashOperate, PermFinanceRead, PermFinanceWrite},
+    },
+    models::finance::{
+        AccountStatement, AccountTransfer, BudgetReport, CashMovementKind, CategoryBudget, CategoryTree, DreReport, CashSession, CashSessionReport, CashSessionStatus,
+        FinancialAccount, FinancialCategory, FinancialMovement, FinancialTitle, PaymentTerm, PixCharge,
+        RecurrenceFrequency, RecurringTitle, TitleKind, TitleSettlement, TitleStatus
+    },
+};
+
+// --- Validações Auxiliares ---
+fn validate_positive(val: &Decimal) -> Result<(), ValidationError> {
+    if *val <= Decimal::ZERO {
+        let mut err = ValidationError::new("range");
+        err.message = Some("O valor deve ser maior que zero.".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_down_payment(val: &Decimal) -> Result<(), ValidationError> {
+    if *val < Decimal::ZERO || *val >= Decimal::ONE_HUNDRED {
+        let mut err = ValidationError::new("range");
+        err.message = Some("A entrada deve estar entre 0 e 99.99%.".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+// =============================================================================
+//  1. CONTAS (Onde o dinheiro mora)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountPayload {
+    #[validate(length(min = 1, max = 100, message = "required"))]
+    #[schema(example = "Caixa Físico")]
+    pub name: String,
+
+    #[validate(length(max = 100))]
+    #[schema(example = "Itaú")]
+    pub bank_name: Option<String>,
+}
+
+// POST /api/finance/accounts
+#[utoipa::path(
+    post,
+    path = "/api/finance/accounts",
+    tag = "Finance",
+    request_body = CreateAccountPayload,
+    responses(
+        (status = 201, description = "Conta criada", body = FinancialAccount),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_account(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<CreateAccountPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let account = app_state.finance_service
+        .create_account(&mut *rls_conn, tenant.0, &payload.name, payload.bank_name.as_deref())
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+// GET /api/finance/accounts
+#[utoipa::path(
+    get,
+    path = "/api/finance/accounts",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Lista de contas", body = Vec<FinancialAccount>)
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_accounts(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let accounts = app_state.finance_service
+        .list_accounts(&mut *rls_conn, tenant.0)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(accounts)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct StatementQuery {
+    /// Início do período (padrão: dia 1 do mês de `endDate`)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Fim do período (padrão: hoje)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-31")]
+    pub end_date: Option<NaiveDate>,
+}
+
+// GET /api/finance/accounts/{id}/statement
+#[utoipa::path(
+    get,
+    path = "/api/finance/accounts/{account_id}/statement",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Extrato da conta com saldo acumulado", body = AccountStatement),
+        (status = 400, description = "Período inválido"),
+        (status = 404, description = "Conta não encontrada")
+    ),
+    params(
+        ("account_id" = Uuid, Path, description = "ID da Conta"),
+        StatementQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_account_statement(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(account_id): Path<Uuid>,
+    Query(period): Query<StatementQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let statement = app_state.finance_service
+        .get_account_statement(&mut *rls_conn, tenant.0, account_id, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(statement)))
+}
+
+// =============================================================================
+//  2. CATEGORIAS (Plano de Contas)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFinancialCategoryPayload {
+    /// Código no plano de contas (único na loja)
+    #[validate(length(min = 1, max = 20))]
+    #[schema(example = "2.3.01")]
+    pub code: Option<String>,
+
+    #[validate(length(min = 1, max = 100, message = "required"))]
+    #[schema(example = "Despesa com Aluguel")]
+    pub name: String,
+
+    #[schema(example = "Payable")]
+    pub kind: TitleKind,
+
+    /// Categoria pai (mesmo tipo). Vazio = raiz
+    pub parent_id: Option<Uuid>,
+}
+
+// POST /api/finance/categories
+#[utoipa::path(
+    post,
+    path = "/api/finance/categories",
+    tag = "Finance",
+    request_body = CreateFinancialCategoryPayload,
+    responses(
+        (status = 201, description = "Categoria criada", body = FinancialCategory),
+        (status = 400, description = "Categoria pai de outro tipo"),
+        (status = 409, description = "Código já existe"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_category(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<CreateFinancialCategoryPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let category = app_state.finance_service
+        .create_category(
+            &mut *rls_conn,
+            tenant.0,
+            payload.code.as_deref(),
+            &payload.name,
+            payload.kind,
+            payload.parent_id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+// GET /api/finance/categories
+#[utoipa::path(
+    get,
+    path = "/api/finance/categories",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Lista de categorias financeiras", body = Vec<FinancialCategory>)
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_categories(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let categories = app_state.finance_service
+        .list_categories(&mut *rls_conn, tenant.0)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(categories)))
+}
+
+/// Substitui código, nome e pai da categoria. O tipo não muda.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFinancialCategoryPayload {
+    #[validate(length(min = 1, max = 20))]
+    #[schema(example = "2.3.01")]
+    pub code: Option<String>,
+
+    #[validate(length(min = 1, max = 100, message = "required"))]
+    #[schema(example = "Aluguel")]
+    pub name: String,
+
+    /// Categoria pai (mesmo tipo). Vazio = raiz
+    pub parent_id: Option<Uuid>,
+
+    /// Vazio = mantém
+    pub is_active: Option<bool>,
+}
+
+// PUT /api/finance/categories/{id}
+#[utoipa::path(
+    put,
+    path = "/api/finance/categories/{category_id}",
+    tag = "Finance",
+    request_body = UpdateFinancialCategoryPayload,
+    responses(
+        (status = 200, description = "Categoria atualizada", body = FinancialCategory),
+        (status = 400, description = "Pai de outro tipo ou dentro da própria categoria"),
+        (status = 404, description = "Categoria não encontrada"),
+        (status = 409, description = "Código já existe"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("category_id" = Uuid, Path, description = "ID da Categoria"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn update_category(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(category_id): Path<Uuid>,
+    Json(payload): Json<UpdateFinancialCategoryPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let category = app_state.finance_service
+        .update_category(
+            &mut *rls_conn,
+            tenant.0,
+            category_id,
+            payload.code.as_deref(),
+            &payload.name,
+            payload.parent_id,
+            payload.is_active,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(category)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct CategoryTreeQuery {
+    /// Início do período de competência (padrão: 1º de janeiro do ano de `endDate`)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Fim do período de competência (padrão: hoje)
+    #[param(value_type = Option<String>, format = Date, example = "2026-03-31")]
+    pub end_date: Option<NaiveDate>,
+}
+
+// GET /api/finance/categories/tree
+#[utoipa::path(
+    get,
+    path = "/api/finance/categories/tree",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Plano de contas em árvore, com os totais do período por nó", body = CategoryTree),
+        (status = 400, description = "Período inválido")
+    ),
+    params(
+        CategoryTreeQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_category_tree(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(query): Query<CategoryTreeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let tree = app_state.finance_service
+        .get_category_tree(&mut *rls_conn, tenant.0, query.start_date, query.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(tree)))
+}
+
+// =============================================================================
+//  3. TÍTULOS (Contas a Pagar / Receber)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTitlePayload {
+    #[validate(length(min = 1, max = 255, message = "required"))]
+    #[schema(example = "Aluguel Janeiro/2026")]
+    pub description: String,
+
+    #[schema(example = "Payable")]
+    pub kind: TitleKind,
+
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "3500.00")]
+    pub amount: Decimal,
+
+    #[schema(value_type = String, format = Date, example = "2026-01-10")]
+    pub due_date: NaiveDate,
+
+    // Se não informado, assume a data de hoje (DRE)
+    #[schema(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub competence_date: Option<NaiveDate>,
+
+    pub category_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+}
+
+// POST /api/finance/titles
+#[utoipa::path(
+    post,
+    path = "/api/finance/titles",
+    tag = "Finance",
+    request_body = CreateTitlePayload,
+    responses(
+        (status = 201, description = "Título lançado", body = FinancialTitle),
+        (status = 400, description = "Dados inválidos (ex: categoria de outro tipo)"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_title(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<CreateTitlePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let title = app_state.finance_service
+        .create_manual_title(
+            &mut *rls_conn,
+            tenant.0,
+            &payload.description,
+            payload.kind,
+            payload.amount,
+            payload.due_date,
+            payload.competence_date,
+            payload.category_id,
+            payload.customer_id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(title)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListTitlesQuery {
+    /// Receivable ou Payable
+    pub kind: Option<TitleKind>,
+    /// Pending, Partial, Paid, Cancelled, Overdue
+    pub status: Option<TitleStatus>,
+    /// Vencimento a partir de (AAAA-MM-DD)
+    #[param(value_type = Option<String>, format = Date)]
+    pub due_from: Option<NaiveDate>,
+    /// Vencimento até (AAAA-MM-DD)
+    #[param(value_type = Option<String>, format = Date)]
+    pub due_to: Option<NaiveDate>,
+    /// Somente os títulos gerados por esta recorrência
+    pub recurring_title_id: Option<Uuid>,
+}
+
+// GET /api/finance/titles
+#[utoipa::path(
+    get,
+    path = "/api/finance/titles",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Lista de títulos", body = Vec<FinancialTitle>)
+    ),
+    params(
+        ListTitlesQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_titles(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListTitlesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let titles = app_state.finance_service
+        .list_titles(
+            &mut *rls_conn,
+            tenant.0,
+            filters.kind,
+            filters.status,
+            filters.due_from,
+            filters.due_to,
+            filters.recurring_title_id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(titles)))
+}
+
+// GET /api/finance/titles/{id}
+#[utoipa::path(
+    get,
+    path = "/api/finance/titles/{title_id}",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Detalhe do título", body = FinancialTitle),
+        (status = 404, description = "Título não encontrado")
+    ),
+    params(
+        ("title_id" = Uuid, Path, description = "ID do Título"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_title(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(title_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let title = app_state.finance_service
+        .get_title(&mut *rls_conn, tenant.0, title_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(title)))
+}
+
+// GET /api/finance/titles/{id}/pix
+#[utoipa::path(
+    get,
+    path = "/api/finance/titles/{title_id}/pix",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Pix Copia e Cola + QR Code (PNG em base64)", body = PixCharge),
+        (status = 400, description = "Título a pagar ou Pix não configurado"),
+        (status = 404, description = "Título não encontrado"),
+        (status = 409, description = "Título pago ou cancelado")
+    ),
+    params(
+        ("title_id" = Uuid, Path, description = "ID do Título"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_title_pix(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(title_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let pix = app_state.finance_service
+        .get_title_pix(&mut *rls_conn, tenant.0, title_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(pix)))
+}
+
+// =============================================================================
+//  4. BAIXAS & ESTORNOS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleTitlePayload {
+    /// Conta onde o dinheiro entra/sai
+    pub account_id: Uuid,
+
+    // Se não informado, quita o saldo em aberto
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "1500.00")]
+    pub amount: Option<Decimal>,
+
+    // Se não informado, assume a data de hoje
+    #[schema(value_type = Option<String>, format = Date, example = "2026-01-10")]
+    pub movement_date: Option<NaiveDate>,
+
+    #[validate(length(max = 255))]
+    #[schema(example = "Pagamento via Pix")]
+    pub description: Option<String>,
+}
+
+// POST /api/finance/titles/{id}/settle
+#[utoipa::path(
+    post,
+    path = "/api/finance/titles/{title_id}/settle",
+    tag = "Finance",
+    request_body = SettleTitlePayload,
+    responses(
+        (status = 201, description = "Baixa registrada", body = TitleSettlement),
+        (status = 400, description = "Valor maior que o saldo em aberto"),
+        (status = 409, description = "Título já quitado/cancelado ou conta inativa"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("title_id" = Uuid, Path, description = "ID do Título"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn settle_title(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(title_id): Path<Uuid>,
+    Json(payload): Json<SettleTitlePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let settlement = app_state.finance_service
+        .settle_title(
+            &mut *rls_conn,
+            tenant.0,
+            title_id,
+            payload.account_id,
+            payload.amount,
+            payload.movement_date,
+            payload.description.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(settlement)))
+}
+
+// GET /api/finance/titles/{id}/movements
+#[utoipa::path(
+    get,
+    path = "/api/finance/titles/{title_id}/movements",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Histórico de baixas e estornos do título", body = Vec<FinancialMovement>)
+    ),
+    params(
+        ("title_id" = Uuid, Path, description = "ID do Título"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_title_movements(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Path(title_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let movements = app_state.finance_service
+        .list_title_movements(&mut *rls_conn, tenant.0, title_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(movements)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverseMovementPayload {
+    #[validate(length(max = 500))]
+    #[schema(example = "Pagamento lançado na conta errada")]
+    pub reason: Option<String>,
+}
+
+// POST /api/finance/movements/{id}/reverse
+#[utoipa::path(
+    post,
+    path = "/api/finance/movements/{movement_id}/reverse",
+    tag = "Finance",
+    request_body = ReverseMovementPayload,
+    responses(
+        (status = 201, description = "Estorno registrado", body = TitleSettlement),
+        (status = 400, description = "Movimentação é um estorno"),
+        (status = 409, description = "Movimentação já estornada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("movement_id" = Uuid, Path, description = "ID da Movimentação"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn reverse_movement(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(movement_id): Path<Uuid>,
+    Json(payload): Json<ReverseMovementPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let reversal = app_state.finance_service
+        .reverse_movement(
+            &mut *rls_conn,
+            tenant.0,
+            movement_id,
+            payload.reason.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(reversal)))
+}
+
+// =============================================================================
+//  5. CONDIÇÕES DE PAGAMENTO (Parcelamento)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePaymentTermPayload {
+    #[validate(length(min = 1, max = 100, message = "required"))]
+    #[schema(example = "Entrada + 3x")]
+    pub name: String,
+
+    /// Quantidade de parcelas (sem contar a entrada)
+    #[validate(range(min = 1, max = 120))]
+    #[schema(example = 3)]
+    pub installments: i32,
+
+    /// Dias entre as parcelas (Padrão: 30)
+    #[validate(range(min = 1))]
+    #[schema(example = 30)]
+    pub interval_days: Option<i32>,
+
+    /// Dias até a 1ª parcela (Padrão: 30)
+    #[validate(range(min = 0))]
+    #[schema(example = 30)]
+    pub first_due_days: Option<i32>,
+
+    /// Entrada em % do total, vence na geração (Padrão: 0)
+    #[validate(custom(function = "validate_down_payment"))]
+    #[schema(example = "30.00")]
+    pub down_payment_percent: Option<Decimal>,
+}
+
+// POST /api/finance/payment-terms
+#[utoipa::path(
+    post,
+    path = "/api/finance/payment-terms",
+    tag = "Finance",
+    request_body = CreatePaymentTermPayload,
+    responses(
+        (status = 201, description = "Condição de pagamento criada", body = PaymentTerm),
+        (status = 409, description = "Nome já existe"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_payment_term(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<CreatePaymentTermPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let term = app_state.finance_service
+        .create_payment_term(
+            &mut *rls_conn,
+            tenant.0,
+            &payload.name,
+            payload.installments,
+            payload.interval_days.unwrap_or(30),
+            payload.first_due_days.unwrap_or(30),
+            payload.down_payment_percent.unwrap_or(Decimal::ZERO),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(term)))
+}
+
+// GET /api/finance/payment-terms
+#[utoipa::path(
+    get,
+    path = "/api/finance/payment-terms",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Lista de condições de pagamento", body = Vec<PaymentTerm>)
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_payment_terms(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let terms = app_state.finance_service
+        .list_payment_terms(&mut *rls_conn, tenant.0)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(terms)))
+}
+
+// =============================================================================
+//  6. CAIXA (Abertura / Sangria / Suprimento / Fechamento)
+// =============================================================================
+
+fn validate_non_negative(val: &Decimal) -> Result<(), ValidationError> {
+    if *val < Decimal::ZERO {
+        let mut err = ValidationError::new("range");
+        err.message = Some("O valor não pode ser negativo.".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenCashSessionPayload {
+    /// Conta que representa a gaveta
+    pub account_id: Uuid,
+    pub location_id: Uuid,
+
+    /// Troco inicial (contado na abertura)
+    #[validate(custom(function = "validate_non_negative"))]
+    #[schema(example = "200.00")]
+    pub opening_float: Decimal,
+
+    pub notes: Option<String>,
+}
+
+// POST /api/finance/cash-sessions
+#[utoipa::path(
+    post,
+    path = "/api/finance/cash-sessions",
+    tag = "Finance",
+    request_body = OpenCashSessionPayload,
+    responses(
+        (status = 201, description = "Caixa aberto", body = CashSession),
+        (status = 409, description = "Já existe caixa aberto para a gaveta ou operador"),
+        (status = 403, description = "Sem permissão (Requer 'cash:operate')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn open_cash_session(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Json(payload): Json<OpenCashSessionPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let session = app_state.finance_service
+        .open_cash_session(
+            &mut *rls_conn,
+            tenant.0,
+            payload.account_id,
+            payload.location_id,
+            user.0.id,
+            payload.opening_float,
+            payload.notes.as_deref(),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListCashSessionsQuery {
+    /// Open ou Closed
+    pub status: Option<CashSessionStatus>,
+}
+
+// GET /api/finance/cash-sessions
+#[utoipa::path(
+    get,
+    path = "/api/finance/cash-sessions",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Sessões de caixa (mais recentes primeiro)", body = Vec<CashSession>)
+    ),
+    params(
+        ListCashSessionsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_cash_sessions(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Query(filters): Query<ListCashSessionsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let sessions = app_state.finance_service
+        .list_cash_sessions(&mut *rls_conn, tenant.0, filters.status)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(sessions)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashMovementPayload {
+    /// Sale (venda de balcão), Deposit (suprimento) ou Withdrawal (sangria)
+    #[schema(example = "Withdrawal")]
+    pub kind: CashMovementKind,
+
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "150.00")]
+    pub amount: Decimal,
+
+    #[validate(length(max = 255))]
+    #[schema(example = "Sangria para o cofre")]
+    pub description: Option<String>,
+}
+
+// POST /api/finance/cash-sessions/{id}/movements
+#[utoipa::path(
+    post,
+    path = "/api/finance/cash-sessions/{session_id}/movements",
+    tag = "Finance",
+    request_body = CashMovementPayload,
+    responses(
+        (status = 201, description = "Movimentação lançada no caixa", body = FinancialMovement),
+        (status = 400, description = "Natureza inválida para lançamento manual"),
+        (status = 409, description = "Caixa fechado")
+    ),
+    params(
+        ("session_id" = Uuid, Path, description = "ID da Sessão de Caixa"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn post_cash_movement(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Path(session_id): Path<Uuid>,
+    Json(payload): Json<CashMovementPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let movement = app_state.finance_service
+        .post_cash_movement(
+            &mut *rls_conn,
+            tenant.0,
+            session_id,
+            payload.kind,
+            payload.amount,
+            payload.description.as_deref(),
+            user.0.id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(movement)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseCashSessionPayload {
+    /// Valor contado na gaveta
+    #[validate(custom(function = "validate_non_negative"))]
+    #[schema(example = "1345.00")]
+    pub counted_amount: Decimal,
+
+    pub notes: Option<String>,
+}
+
+// POST /api/finance/cash-sessions/{id}/close
+#[utoipa::path(
+    post,
+    path = "/api/finance/cash-sessions/{session_id}/close",
+    tag = "Finance",
+    request_body = CloseCashSessionPayload,
+    responses(
+        (status = 200, description = "Caixa fechado (Contado x Esperado)", body = CashSessionReport),
+        (status = 409, description = "Caixa já fechado")
+    ),
+    params(
+        ("session_id" = Uuid, Path, description = "ID da Sessão de Caixa"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn close_cash_session(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Path(session_id): Path<Uuid>,
+    Json(payload): Json<CloseCashSessionPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.finance_service
+        .close_cash_session(
+            &mut *rls_conn,
+            tenant.0,
+            session_id,
+            user.0.id,
+            payload.counted_amount,
+            payload.notes.as_deref(),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// GET /api/finance/cash-sessions/{id}/report
+#[utoipa::path(
+    get,
+    path = "/api/finance/cash-sessions/{session_id}/report",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Relatório do caixa (parcial, se ainda aberto)", body = CashSessionReport)
+    ),
+    params(
+        ("session_id" = Uuid, Path, description = "ID da Sessão de Caixa"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_cash_session_report(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermCashOperate>,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.finance_service
+        .get_cash_session_report(&mut *rls_conn, tenant.0, session_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// =============================================================================
+//  7. TRANSFERÊNCIAS ENTRE CONTAS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferPayload {
+    /// Conta de onde o dinheiro sai
+    pub from_account_id: Uuid,
+    /// Conta onde o dinheiro entra
+    pub to_account_id: Uuid,
+
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "500.00")]
+    pub amount: Decimal,
+
+    // Se não informado, assume a data de hoje
+    #[schema(value_type = Option<String>, format = Date, example = "2026-01-14")]
+    pub movement_date: Option<NaiveDate>,
+
+    #[validate(length(max = 255))]
+    #[schema(example = "Depósito do caixa no banco")]
+    pub description: Option<String>,
+}
+
+// POST /api/finance/transfers
+#[utoipa::path(
+    post,
+    path = "/api/finance/transfers",
+    tag = "Finance",
+    request_body = TransferPayload,
+    responses(
+        (status = 201, description = "Transferência registrada", body = AccountTransfer),
+        (status = 400, description = "Origem e destino iguais ou valor inválido"),
+        (status = 404, description = "Conta não encontrada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_transfer(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<TransferPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfer = app_state.finance_service
+        .transfer_between_accounts(
+            &mut *rls_conn,
+            tenant.0,
+            payload.from_account_id,
+            payload.to_account_id,
+            payload.amount,
+            payload.movement_date,
+            payload.description.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+// =============================================================================
+//  8. RELATÓRIOS (DRE)
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct DreQuery {
+    /// Início do período de competência (padrão: 1º de janeiro do ano de `endDate`)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Fim do período de competência (padrão: hoje)
+    #[param(value_type = Option<String>, format = Date, example = "2026-03-31")]
+    pub end_date: Option<NaiveDate>,
+}
+
+// GET /api/finance/reports/dre
+#[utoipa::path(
+    get,
+    path = "/api/finance/reports/dre",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "DRE por competência, mês a mês", body = DreReport),
+        (status = 400, description = "Período inválido")
+    ),
+    params(
+        DreQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_dre_report(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(period): Query<DreQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.finance_service
+        .get_dre_report(&mut *rls_conn, tenant.0, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// =============================================================================
+//  9. TÍTULOS RECORRENTES (Aluguel, Salários, Mensalidades)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRecurringTitlePayload {
+    #[validate(length(min = 1, max = 255, message = "required"))]
+    #[schema(example = "Aluguel da loja")]
+    pub description: String,
+
+    #[schema(example = "Payable")]
+    pub kind: TitleKind,
+
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "3500.00")]
+    pub amount: Decimal,
+
+    pub category_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+
+    #[schema(example = "Monthly")]
+    pub frequency: RecurrenceFrequency,
+
+    /// A cada N semanas/meses/anos (Padrão: 1)
+    #[validate(range(min = 1, max = 52))]
+    #[schema(example = 1)]
+    pub interval_count: Option<i32>,
+
+    /// Primeiro vencimento
+    #[schema(value_type = String, format = Date, example = "2026-02-05")]
+    pub start_date: NaiveDate,
+
+    /// Último vencimento possível (vazio = sem fim)
+    #[schema(value_type = Option<String>, format = Date, example = "2026-12-05")]
+    pub end_date: Option<NaiveDate>,
+
+    /// Dias de antecedência para gerar o título (Padrão: 30)
+    #[validate(range(min = 0, max = 366))]
+    #[schema(example = 30)]
+    pub lead_days: Option<i32>,
+}
+
+// POST /api/finance/recurring-titles
+#[utoipa::path(
+    post,
+    path = "/api/finance/recurring-titles",
+    tag = "Finance",
+    request_body = CreateRecurringTitlePayload,
+    responses(
+        (status = 201, description = "Recorrência criada (ocorrências dentro da antecedência já geradas)", body = RecurringTitle),
+        (status = 400, description = "Dados inválidos (ex: categoria de outro tipo, data final antes do início)"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_recurring_title(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<CreateRecurringTitlePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let recurring = app_state.finance_service
+        .create_recurring_title(
+            &mut *rls_conn,
+            tenant.0,
+            &payload.description,
+            payload.kind,
+            payload.amount,
+            payload.category_id,
+            payload.customer_id,
+            payload.frequency,
+            payload.interval_count.unwrap_or(1),
+            payload.start_date,
+            payload.end_date,
+            payload.lead_days.unwrap_or(30),
+            user.0.id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(recurring)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListRecurringTitlesQuery {
+    /// Receivable ou Payable
+    pub kind: Option<TitleKind>,
+}
+
+// GET /api/finance/recurring-titles
+#[utoipa::path(
+    get,
+    path = "/api/finance/recurring-titles",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Lista de recorrências", body = Vec<RecurringTitle>)
+    ),
+    params(
+        ListRecurringTitlesQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_recurring_titles(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListRecurringTitlesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let recurring = app_state.finance_service
+        .list_recurring_titles(&mut *rls_conn, tenant.0, filters.kind)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(recurring)))
+}
+
+/// Substitui os dados do modelo. Vale para as ocorrências ainda não geradas.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRecurringTitlePayload {
+    #[validate(length(min = 1, max = 255, message = "required"))]
+    #[schema(example = "Aluguel da loja (reajustado)")]
+    pub description: String,
+
+    #[validate(custom(function = "validate_positive"))]
+    #[schema(example = "3800.00")]
+    pub amount: Decimal,
+
+    pub category_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+
+    /// Último vencimento possível (vazio = sem fim)
+    #[schema(value_type = Option<String>, format = Date, example = "2026-12-05")]
+    pub end_date: Option<NaiveDate>,
+
+    /// Dias de antecedência (vazio = mantém)
+    #[validate(range(min = 0, max = 366))]
+    #[schema(example = 30)]
+    pub lead_days: Option<i32>,
+
+    /// false = pausa a geração (vazio = mantém)
+    pub is_active: Option<bool>,
+}
+
+// PUT /api/finance/recurring-titles/{id}
+#[utoipa::path(
+    put,
+    path = "/api/finance/recurring-titles/{recurring_id}",
+    tag = "Finance",
+    request_body = UpdateRecurringTitlePayload,
+    responses(
+        (status = 200, description = "Recorrência atualizada", body = RecurringTitle),
+        (status = 400, description = "Dados inválidos"),
+        (status = 404, description = "Recorrência não encontrada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("recurring_id" = Uuid, Path, description = "ID da Recorrência"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn update_recurring_title(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(recurring_id): Path<Uuid>,
+    Json(payload): Json<UpdateRecurringTitlePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let recurring = app_state.finance_service
+        .update_recurring_title(
+            &mut *rls_conn,
+            tenant.0,
+            recurring_id,
+            &payload.description,
+            payload.amount,
+            payload.category_id,
+            payload.customer_id,
+            payload.end_date,
+            payload.lead_days,
+            payload.is_active,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(recurring)))
+}
+
+// =============================================================================
+//  10. ORÇAMENTOS (Orçado x Realizado por categoria)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBudgetPayload {
+    pub category_id: Uuid,
+
+    /// Qualquer dia do mês (gravado como o dia 1º)
+    #[schema(value_type = String, format = Date, example = "2026-03-01")]
+    pub month: NaiveDate,
+
+    /// Vale para a categoria e as subcategorias
+    #[validate(custom(function = "validate_non_negative"))]
+    #[schema(example = "5000.00")]
+    pub amount: Decimal,
+}
+
+// PUT /api/finance/budgets
+#[utoipa::path(
+    put,
+    path = "/api/finance/budgets",
+    tag = "Finance",
+    request_body = SetBudgetPayload,
+    responses(
+        (status = 200, description = "Orçamento do mês definido (substitui o anterior)", body = CategoryBudget),
+        (status = 400, description = "Valor inválido"),
+        (status = 404, description = "Categoria não encontrada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn set_budget(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<SetBudgetPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let budget = app_state.finance_service
+        .set_budget(&mut *rls_conn, tenant.0, payload.category_id, payload.month, payload.amount, user.0.id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(budget)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct BudgetQuery {
+    /// Início do período (padrão: 1º dia do mês corrente)
+    #[param(value_type = Option<String>, format = Date, example = "2026-03-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Fim do período (padrão: último dia do mês de `startDate`)
+    #[param(value_type = Option<String>, format = Date, example = "2026-03-31")]
+    pub end_date: Option<NaiveDate>,
+    pub category_id: Option<Uuid>,
+}
+
+// GET /api/finance/budgets
+#[utoipa::path(
+    get,
+    path = "/api/finance/budgets",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Orçamentos dos meses do período", body = Vec<CategoryBudget>),
+        (status = 400, description = "Período inválido")
+    ),
+    params(
+        BudgetQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_budgets(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<BudgetQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let budgets = app_state.finance_service
+        .list_budgets(&mut *rls_conn, tenant.0, filters.start_date, filters.end_date, filters.category_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(budgets)))
+}
+
+// DELETE /api/finance/budgets/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/finance/budgets/{budget_id}",
+    tag = "Finance",
+    responses(
+        (status = 204, description = "Orçamento removido"),
+        (status = 404, description = "Orçamento não encontrado"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("budget_id" = Uuid, Path, description = "ID do Orçamento"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn delete_budget(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    app_state.finance_service
+        .delete_budget(&mut *rls_conn, tenant.0, budget_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/finance/reports/budget
+#[utoipa::path(
+    get,
+    path = "/api/finance/reports/budget",
+    tag = "Finance",
+    responses(
+        (status = 200, description = "Orçado x Realizado (competência e baixas) por categoria", body = BudgetReport),
+        (status = 400, description = "Período inválido")
+    ),
+    params(
+        BudgetQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_budget_report(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(period): Query<BudgetQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.finance_service
+        .get_budget_report(&mut *rls_conn, tenant.0, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
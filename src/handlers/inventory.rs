@@ -1,7 +1,7 @@
 // src/handlers/inventory.rs
 
 use axum::{
-    extract::{State, Path},
+    extract::{State, Path, Query},
     http::StatusCode,
     response::IntoResponse,
     Json
@@ -11,7 +11,7 @@ use serde::Deserialize;
 use serde_json::Value;
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     common::{
@@ -23,11 +23,17 @@ use crate::{
         auth::AuthenticatedUser,
         i18n::Locale,
         tenancy::TenantContext,
-        rbac::{RequirePermission, PermInventoryWrite},
+        rbac::{RequirePermission, PermInventoryRead, PermInventoryWrite},
     },
     models::inventory::{
         StockMovementReason, ItemKind, CompositionType,
-        Item, CompositionEntry, UnitOfMeasure, Category, InventoryLevel
+        Item, CompositionEntry, UnitOfMeasure, UnitConversion, Category, InventoryLevel,
+        StockTransfer, StockTransferDetail, StockTransferLine, StockTransferStatus,
+        InventoryCount, InventoryCountDetail, InventoryCountEntry, InventoryCountReport, InventoryCountStatus,
+        ItemKardex, StockMovementPage, CompositionChoice,
+        ProductionOrder, ProductionOrderDetail, ProductionOrderStatus, ProductionConsumption,
+        WhereUsedEntry, FlattenedBom, StockReservation, StockReservationStatus,
+        PoolAvailability
     },
 };
 use chrono::NaiveDate;
@@ -68,6 +74,11 @@ pub struct CreateItemPayload {
     #[schema(example = "550e8400-e29b-41d4-a716-446655440002")]
     pub base_unit_id: Option<Uuid>,
 
+    /// Unidade padrão das entradas (precisa converter para a base)
+    pub purchase_unit_id: Option<Uuid>,
+    /// Unidade padrão das vendas (precisa converter para a base)
+    pub sale_unit_id: Option<Uuid>,
+
     #[schema(example = "Product")]
     pub kind: ItemKind,
 
@@ -152,6 +163,8 @@ pub async fn create_item(
             payload.location_id,
             payload.category_id,
             payload.base_unit_id.unwrap(),
+            payload.purchase_unit_id,
+            payload.sale_unit_id,
             &payload.sku,
             &payload.name,
             payload.description.as_deref(),
@@ -198,7 +211,8 @@ pub struct AddCompositionPayload {
     request_body = AddCompositionPayload,
     responses(
         (status = 201, description = "Item adicionado à composição"),
-        (status = 404, description = "Item não encontrado")
+        (status = 404, description = "Item não encontrado"),
+        (status = 409, description = "O filho já contém o pai na sua ficha técnica (ciclo)")
     ),
     security(("api_jwt" = []))
 )]
@@ -267,6 +281,89 @@ pub async fn get_item_composition(
     Ok((StatusCode::OK, Json(composition)))
 }
 
+// GET /api/inventory/items/{id}/where-used
+#[utoipa::path(
+    get,
+    path = "/api/inventory/items/{item_id}/where-used",
+    tag = "Inventory",
+    params(
+        ("item_id" = Uuid, Path, description = "ID do Item Filho"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    responses(
+        (status = 200, description = "Itens cuja ficha técnica usa este item", body = Vec<WhereUsedEntry>)
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_where_used(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let entries = app_state.inventory_service
+        .get_where_used(&mut *rls_conn, tenant.0, item_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(entries)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct FlattenedCompositionQuery {
+    /// Quantidade do item pai (padrão: 1)
+    pub quantity: Option<Decimal>,
+    /// Custo médio deste local (padrão: custo de cadastro)
+    pub location_id: Option<Uuid>,
+}
+
+// GET /api/inventory/items/{id}/composition/flattened
+#[utoipa::path(
+    get,
+    path = "/api/inventory/items/{parent_id}/composition/flattened",
+    tag = "Inventory",
+    params(
+        ("parent_id" = Uuid, Path, description = "ID do Item Pai"),
+        FlattenedCompositionQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    responses(
+        (status = 200, description = "Ficha técnica em todos os níveis com quantidades acumuladas e custo", body = FlattenedBom),
+        (status = 404, description = "Item não encontrado")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_flattened_composition(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(parent_id): Path<Uuid>,
+    Query(query): Query<FlattenedCompositionQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let bom = app_state.inventory_service
+        .get_flattened_composition(&mut *rls_conn, tenant.0, parent_id, query.quantity, query.location_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(bom)))
+}
+
 // =============================================================================
 //  GET ITEMS
 // =============================================================================
@@ -368,6 +465,189 @@ pub async fn get_all_units(
     Ok((StatusCode::OK, Json(units)))
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUnitConversionPayload {
+    /// Conversão só deste item (padrão: vale para todos)
+    pub item_id: Option<Uuid>,
+    pub from_unit_id: Uuid,
+    pub to_unit_id: Uuid,
+    /// 1 unidade de origem = `factor` unidades de destino
+    #[schema(example = "12.0")]
+    pub factor: Decimal,
+}
+
+// POST /api/inventory/units/conversions
+#[utoipa::path(
+    post,
+    path = "/api/inventory/units/conversions",
+    tag = "Inventory",
+    request_body = CreateUnitConversionPayload,
+    responses(
+        (status = 201, description = "Conversão cadastrada (ou fator atualizado)", body = UnitConversion),
+        (status = 400, description = "Fator inválido ou unidades iguais"),
+        (status = 404, description = "Unidade ou item não encontrado")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_unit_conversion(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Json(payload): Json<CreateUnitConversionPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let conversion = app_state.inventory_service
+        .create_unit_conversion(
+            &mut *rls_conn,
+            tenant.0,
+            payload.item_id,
+            payload.from_unit_id,
+            payload.to_unit_id,
+            payload.factor,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(conversion)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListUnitConversionsQuery {
+    /// Inclui as conversões específicas deste item
+    pub item_id: Option<Uuid>,
+}
+
+// GET /api/inventory/units/conversions
+#[utoipa::path(
+    get,
+    path = "/api/inventory/units/conversions",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Conversões gerais (e as do item, se informado)", body = Vec<UnitConversion>)
+    ),
+    params(
+        ListUnitConversionsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_unit_conversions(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListUnitConversionsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let conversions = app_state.inventory_service
+        .list_unit_conversions(&mut *rls_conn, tenant.0, filters.item_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(conversions)))
+}
+
+// DELETE /api/inventory/units/conversions/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/inventory/units/conversions/{conversion_id}",
+    tag = "Inventory",
+    responses(
+        (status = 204, description = "Conversão removida"),
+        (status = 404, description = "Conversão não encontrada")
+    ),
+    params(
+        ("conversion_id" = Uuid, Path, description = "ID da Conversão"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn delete_unit_conversion(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(conversion_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    app_state.inventory_service
+        .delete_unit_conversion(&mut *rls_conn, tenant.0, conversion_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetItemUnitsPayload {
+    /// Vazio = entradas na unidade base
+    pub purchase_unit_id: Option<Uuid>,
+    /// Vazio = vendas na unidade base
+    pub sale_unit_id: Option<Uuid>,
+}
+
+// PUT /api/inventory/items/{id}/units
+#[utoipa::path(
+    put,
+    path = "/api/inventory/items/{item_id}/units",
+    tag = "Inventory",
+    request_body = SetItemUnitsPayload,
+    responses(
+        (status = 200, description = "Unidades de compra e venda atualizadas", body = Item),
+        (status = 400, description = "Unidade sem conversão para a unidade base do item"),
+        (status = 404, description = "Item não encontrado")
+    ),
+    params(
+        ("item_id" = Uuid, Path, description = "ID do Item"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn set_item_units(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<SetItemUnitsPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let item = app_state.inventory_service
+        .set_item_units(&mut *rls_conn, tenant.0, item_id, payload.purchase_unit_id, payload.sale_unit_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(item)))
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateCategoryPayload {
@@ -444,6 +724,8 @@ pub struct AddStockPayload {
     #[validate(custom(function = "validate_not_negative"))]
     #[schema(example = "25.50")]
     pub unit_cost: Decimal,
+    /// Unidade de `quantity` e `unitCost` (padrão: unidade de compra do item)
+    pub unit_id: Option<Uuid>,
     #[schema(example = "Purchase")]
     pub reason: StockMovementReason,
     pub notes: Option<String>,
@@ -477,7 +759,7 @@ pub async fn add_stock(
     let mut rls_conn = get_rls_connection(&app_state, &tenant, &user).await.map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
     let updated_level = app_state.inventory_service.add_stock(
         &mut *rls_conn, tenant.0, payload.item_id, payload.location_id,
-        payload.quantity, payload.unit_cost, payload.reason, payload.notes.as_deref(),
+        payload.quantity, payload.unit_cost, payload.unit_id, payload.reason, payload.notes.as_deref(),
         payload.batch_number, payload.expiration_date, payload.position,
     ).await.map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
     Ok((StatusCode::OK, Json(updated_level)))
@@ -494,8 +776,13 @@ pub struct SellItemPayload {
     #[validate(custom(function = "validate_not_negative"))]
     #[schema(example = "50.00")]
     pub unit_price: Decimal,
+    /// Unidade de `quantity` e `unitPrice` (padrão: unidade de venda do item)
+    pub unit_id: Option<Uuid>,
     pub batch_number: Option<String>,
     pub position: Option<String>,
+    /// Acessórios incluídos e substituições de componentes (itens com ficha técnica)
+    #[serde(default)]
+    pub choices: Vec<CompositionChoice>,
 }
 
 // POST /api/inventory/sell
@@ -505,7 +792,7 @@ pub struct SellItemPayload {
     tag = "Inventory",
     request_body = SellItemPayload,
     responses(
-        (status = 200, description = "Item vendido (estoque baixado)")
+        (status = 200, description = "Item vendido (estoque baixado; com a venda compartilhada, completa pelo pool)")
     ),
     params(
         ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
@@ -521,10 +808,1085 @@ pub async fn sell_item(
 ) -> Result<impl IntoResponse, ApiError> {
     payload.validate().map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
     let mut rls_conn = get_rls_connection(&app_state, &tenant, &user).await.map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
-    app_state.inventory_service.sell_item(
+    app_state.inventory_service.sell_item_from_pool(
         &mut *rls_conn, tenant.0, payload.item_id, payload.location_id,
-        payload.quantity, payload.unit_price, false, Some("Venda via API"),
-        payload.batch_number, payload.position,
+        payload.quantity, payload.unit_price, payload.unit_id, Some("Venda via API"),
+        payload.batch_number, payload.position, &payload.choices,
     ).await.map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
     Ok(StatusCode::OK)
-}
\ No newline at end of file
+}
+
+// =============================================================================
+//  TRANSFERÊNCIAS ENTRE LOCAIS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStockTransferPayload {
+    /// Local de onde a mercadoria sai
+    pub from_location_id: Uuid,
+    /// Local onde a mercadoria entra
+    pub to_location_id: Uuid,
+
+    pub items: Vec<StockTransferLine>,
+
+    /// Se verdadeiro, a entrada no destino fica pendente até o recebimento
+    #[serde(default)]
+    #[schema(example = false)]
+    pub in_transit: bool,
+
+    #[validate(length(max = 500))]
+    #[schema(example = "Reposição da loja do centro")]
+    pub notes: Option<String>,
+}
+
+// POST /api/inventory/transfers
+#[utoipa::path(
+    post,
+    path = "/api/inventory/transfers",
+    tag = "Inventory",
+    request_body = CreateStockTransferPayload,
+    responses(
+        (status = 201, description = "Transferência registrada", body = StockTransferDetail),
+        (status = 400, description = "Origem e destino iguais ou quantidade inválida"),
+        (status = 404, description = "Local ou lote não encontrado"),
+        (status = 409, description = "Estoque insuficiente na origem"),
+        (status = 403, description = "Sem permissão (Requer 'inventory:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_transfer(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Json(payload): Json<CreateStockTransferPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfer = app_state.inventory_service
+        .create_transfer(
+            &mut *rls_conn,
+            tenant.0,
+            payload.from_location_id,
+            payload.to_location_id,
+            &payload.items,
+            payload.in_transit,
+            payload.notes.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListStockTransfersQuery {
+    /// Filtra pela situação (ex: IN_TRANSIT)
+    pub status: Option<StockTransferStatus>,
+    /// Transferências que saem ou chegam neste local
+    pub location_id: Option<Uuid>,
+}
+
+// GET /api/inventory/transfers
+#[utoipa::path(
+    get,
+    path = "/api/inventory/transfers",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Lista de transferências", body = Vec<StockTransfer>)
+    ),
+    params(
+        ListStockTransfersQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_transfers(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListStockTransfersQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfers = app_state.inventory_service
+        .list_transfers(&mut *rls_conn, tenant.0, filters.status, filters.location_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(transfers)))
+}
+
+// GET /api/inventory/transfers/{id}
+#[utoipa::path(
+    get,
+    path = "/api/inventory/transfers/{transfer_id}",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Transferência com os lotes movimentados", body = StockTransferDetail),
+        (status = 404, description = "Transferência não encontrada")
+    ),
+    params(
+        ("transfer_id" = Uuid, Path, description = "ID da Transferência"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_transfer(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfer = app_state.inventory_service
+        .get_transfer(&mut *rls_conn, tenant.0, transfer_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(transfer)))
+}
+
+// POST /api/inventory/transfers/{id}/receive
+#[utoipa::path(
+    post,
+    path = "/api/inventory/transfers/{transfer_id}/receive",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Mercadoria recebida no destino", body = StockTransferDetail),
+        (status = 404, description = "Transferência não encontrada"),
+        (status = 409, description = "A transferência não está em trânsito")
+    ),
+    params(
+        ("transfer_id" = Uuid, Path, description = "ID da Transferência"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn receive_transfer(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfer = app_state.inventory_service
+        .receive_transfer(&mut *rls_conn, tenant.0, transfer_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(transfer)))
+}
+
+// POST /api/inventory/transfers/{id}/cancel
+#[utoipa::path(
+    post,
+    path = "/api/inventory/transfers/{transfer_id}/cancel",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Transferência cancelada (mercadoria devolvida à origem)", body = StockTransferDetail),
+        (status = 404, description = "Transferência não encontrada"),
+        (status = 409, description = "A transferência não está em trânsito")
+    ),
+    params(
+        ("transfer_id" = Uuid, Path, description = "ID da Transferência"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn cancel_transfer(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let transfer = app_state.inventory_service
+        .cancel_transfer(&mut *rls_conn, tenant.0, transfer_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(transfer)))
+}
+
+// =============================================================================
+//  INVENTÁRIO FÍSICO (CONTAGEM)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInventoryCountPayload {
+    /// Local que será contado
+    pub location_id: Uuid,
+
+    /// Contagem cíclica: apenas estes itens (padrão: todo o local)
+    pub item_ids: Option<Vec<Uuid>>,
+
+    #[validate(length(max = 500))]
+    #[schema(example = "Contagem mensal - corredor A")]
+    pub notes: Option<String>,
+}
+
+// POST /api/inventory/counts
+#[utoipa::path(
+    post,
+    path = "/api/inventory/counts",
+    tag = "Inventory",
+    request_body = OpenInventoryCountPayload,
+    responses(
+        (status = 201, description = "Contagem aberta com os saldos esperados congelados", body = InventoryCountDetail),
+        (status = 404, description = "Local não encontrado"),
+        (status = 409, description = "Já existe uma contagem aberta no local"),
+        (status = 403, description = "Sem permissão (Requer 'inventory:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn open_count(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Json(payload): Json<OpenInventoryCountPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let count = app_state.inventory_service
+        .open_count(
+            &mut *rls_conn,
+            tenant.0,
+            payload.location_id,
+            payload.item_ids.as_deref(),
+            payload.notes.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(count)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListInventoryCountsQuery {
+    /// Filtra pela situação (ex: OPEN)
+    pub status: Option<InventoryCountStatus>,
+    pub location_id: Option<Uuid>,
+}
+
+// GET /api/inventory/counts
+#[utoipa::path(
+    get,
+    path = "/api/inventory/counts",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Lista de contagens", body = Vec<InventoryCount>)
+    ),
+    params(
+        ListInventoryCountsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_counts(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListInventoryCountsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let counts = app_state.inventory_service
+        .list_counts(&mut *rls_conn, tenant.0, filters.status, filters.location_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(counts)))
+}
+
+// GET /api/inventory/counts/{id}
+#[utoipa::path(
+    get,
+    path = "/api/inventory/counts/{count_id}",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Contagem com esperado, contado e diferença por lote/posição", body = InventoryCountDetail),
+        (status = 404, description = "Contagem não encontrada")
+    ),
+    params(
+        ("count_id" = Uuid, Path, description = "ID da Contagem"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_count(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(count_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let count = app_state.inventory_service
+        .get_count(&mut *rls_conn, tenant.0, count_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(count)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordCountsPayload {
+    pub entries: Vec<InventoryCountEntry>,
+
+    /// Soma ao que já foi contado (vários aparelhos). Padrão: substitui.
+    #[serde(default)]
+    #[schema(example = true)]
+    pub accumulate: bool,
+}
+
+// POST /api/inventory/counts/{id}/entries
+#[utoipa::path(
+    post,
+    path = "/api/inventory/counts/{count_id}/entries",
+    tag = "Inventory",
+    request_body = RecordCountsPayload,
+    responses(
+        (status = 200, description = "Quantidades lançadas", body = InventoryCountDetail),
+        (status = 400, description = "Quantidade inválida"),
+        (status = 404, description = "Contagem ou item não encontrado"),
+        (status = 409, description = "A contagem não está aberta")
+    ),
+    params(
+        ("count_id" = Uuid, Path, description = "ID da Contagem"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn record_counts(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(count_id): Path<Uuid>,
+    Json(payload): Json<RecordCountsPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let count = app_state.inventory_service
+        .record_counts(&mut *rls_conn, tenant.0, count_id, &payload.entries, payload.accumulate, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(count)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveCountPayload {
+    /// Linhas não contadas viram zero (padrão: são ignoradas)
+    #[serde(default)]
+    #[schema(example = false)]
+    pub zero_uncounted: bool,
+}
+
+// POST /api/inventory/counts/{id}/approve
+#[utoipa::path(
+    post,
+    path = "/api/inventory/counts/{count_id}/approve",
+    tag = "Inventory",
+    request_body = ApproveCountPayload,
+    responses(
+        (status = 200, description = "Diferenças lançadas como CORRECTION", body = InventoryCountReport),
+        (status = 404, description = "Contagem não encontrada"),
+        (status = 409, description = "A contagem não está aberta ou a correção deixaria o estoque negativo")
+    ),
+    params(
+        ("count_id" = Uuid, Path, description = "ID da Contagem"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn approve_count(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(count_id): Path<Uuid>,
+    Json(payload): Json<ApproveCountPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.inventory_service
+        .approve_count(&mut *rls_conn, tenant.0, count_id, payload.zero_uncounted, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// POST /api/inventory/counts/{id}/cancel
+#[utoipa::path(
+    post,
+    path = "/api/inventory/counts/{count_id}/cancel",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Contagem descartada (estoque inalterado)", body = InventoryCount),
+        (status = 404, description = "Contagem não encontrada"),
+        (status = 409, description = "A contagem não está aberta")
+    ),
+    params(
+        ("count_id" = Uuid, Path, description = "ID da Contagem"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn cancel_count(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(count_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let count = app_state.inventory_service
+        .cancel_count(&mut *rls_conn, tenant.0, count_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(count)))
+}
+
+// GET /api/inventory/counts/{id}/report
+#[utoipa::path(
+    get,
+    path = "/api/inventory/counts/{count_id}/report",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Diferenças valorizadas ao custo médio", body = InventoryCountReport),
+        (status = 404, description = "Contagem não encontrada")
+    ),
+    params(
+        ("count_id" = Uuid, Path, description = "ID da Contagem"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_count_report(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(count_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let report = app_state.inventory_service
+        .get_count_report(&mut *rls_conn, tenant.0, count_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// =============================================================================
+//  ORDENS DE PRODUÇÃO
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProductionOrderPayload {
+    /// Item produzido (PRODUCT com componentes na ficha técnica)
+    pub item_id: Uuid,
+    /// Local onde os componentes são consumidos e o lote é gerado
+    pub location_id: Uuid,
+
+    #[schema(example = "10.0")]
+    pub planned_quantity: Decimal,
+
+    #[validate(length(max = 500))]
+    #[schema(example = "Molho de tomate - produção da manhã")]
+    pub notes: Option<String>,
+}
+
+// POST /api/inventory/production-orders
+#[utoipa::path(
+    post,
+    path = "/api/inventory/production-orders",
+    tag = "Inventory",
+    request_body = CreateProductionOrderPayload,
+    responses(
+        (status = 201, description = "Ordem planejada com os componentes da ficha técnica", body = ProductionOrderDetail),
+        (status = 400, description = "Quantidade inválida ou item sem ficha técnica"),
+        (status = 404, description = "Item ou local não encontrado"),
+        (status = 403, description = "Sem permissão (Requer 'inventory:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_production_order(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Json(payload): Json<CreateProductionOrderPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let order = app_state.inventory_service
+        .create_production_order(
+            &mut *rls_conn,
+            tenant.0,
+            payload.item_id,
+            payload.location_id,
+            payload.planned_quantity,
+            payload.notes.as_deref(),
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(order)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListProductionOrdersQuery {
+    /// Filtra pela situação (ex: PLANNED)
+    pub status: Option<ProductionOrderStatus>,
+    pub item_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+}
+
+// GET /api/inventory/production-orders
+#[utoipa::path(
+    get,
+    path = "/api/inventory/production-orders",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Lista de ordens de produção", body = Vec<ProductionOrder>)
+    ),
+    params(
+        ListProductionOrdersQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_production_orders(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListProductionOrdersQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let orders = app_state.inventory_service
+        .list_production_orders(&mut *rls_conn, tenant.0, filters.status, filters.item_id, filters.location_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(orders)))
+}
+
+// GET /api/inventory/production-orders/{id}
+#[utoipa::path(
+    get,
+    path = "/api/inventory/production-orders/{order_id}",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Ordem com componentes planejados e consumidos", body = ProductionOrderDetail),
+        (status = 404, description = "Ordem de produção não encontrada")
+    ),
+    params(
+        ("order_id" = Uuid, Path, description = "ID da Ordem de Produção"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_production_order(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(order_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let order = app_state.inventory_service
+        .get_production_order(&mut *rls_conn, tenant.0, order_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(order)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteProductionOrderPayload {
+    /// Quantidade efetivamente produzida (define rendimento e perda)
+    #[schema(example = "9.2")]
+    pub produced_quantity: Decimal,
+
+    /// Consumo real dos componentes (padrão: o planejado)
+    #[serde(default)]
+    pub consumptions: Vec<ProductionConsumption>,
+
+    /// Lote gerado (padrão: "OP-" + início do ID da ordem)
+    #[validate(length(min = 1, max = 255))]
+    #[schema(example = "OP-2026-02-03")]
+    pub batch_number: Option<String>,
+
+    /// Padrão: "Geral"
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Câmara fria")]
+    pub position: Option<String>,
+
+    pub expiration_date: Option<NaiveDate>,
+}
+
+// POST /api/inventory/production-orders/{id}/complete
+#[utoipa::path(
+    post,
+    path = "/api/inventory/production-orders/{order_id}/complete",
+    tag = "Inventory",
+    request_body = CompleteProductionOrderPayload,
+    responses(
+        (status = 200, description = "Componentes consumidos e lote do item gerado", body = ProductionOrderDetail),
+        (status = 400, description = "Quantidade ou consumo inválido"),
+        (status = 404, description = "Ordem de produção não encontrada"),
+        (status = 409, description = "A ordem não está planejada ou falta estoque de componente")
+    ),
+    params(
+        ("order_id" = Uuid, Path, description = "ID da Ordem de Produção"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn complete_production_order(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(order_id): Path<Uuid>,
+    Json(payload): Json<CompleteProductionOrderPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let order = app_state.inventory_service
+        .complete_production_order(
+            &mut *rls_conn,
+            tenant.0,
+            order_id,
+            payload.produced_quantity,
+            &payload.consumptions,
+            payload.batch_number,
+            payload.position,
+            payload.expiration_date,
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(order)))
+}
+
+// POST /api/inventory/production-orders/{id}/cancel
+#[utoipa::path(
+    post,
+    path = "/api/inventory/production-orders/{order_id}/cancel",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Ordem cancelada (estoque inalterado)", body = ProductionOrder),
+        (status = 404, description = "Ordem de produção não encontrada"),
+        (status = 409, description = "A ordem não está planejada")
+    ),
+    params(
+        ("order_id" = Uuid, Path, description = "ID da Ordem de Produção"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn cancel_production_order(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(order_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let order = app_state.inventory_service
+        .cancel_production_order(&mut *rls_conn, tenant.0, order_id, Some(user.0.id))
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(order)))
+}
+
+// =============================================================================
+//  RESERVAS DE ESTOQUE
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListReservationsQuery {
+    /// Filtra pela situação (ex: ACTIVE)
+    pub status: Option<StockReservationStatus>,
+    pub order_id: Option<Uuid>,
+    pub item_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+}
+
+// GET /api/inventory/reservations
+#[utoipa::path(
+    get,
+    path = "/api/inventory/reservations",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Lista de reservas de estoque", body = Vec<StockReservation>)
+    ),
+    params(
+        ListReservationsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_reservations(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListReservationsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let reservations = app_state.inventory_service
+        .list_reservations(
+            &mut *rls_conn, tenant.0,
+            filters.status, filters.order_id, filters.item_id, filters.location_id
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(reservations)))
+}
+
+// POST /api/inventory/reservations/{id}/release
+#[utoipa::path(
+    post,
+    path = "/api/inventory/reservations/{reservation_id}/release",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Reserva liberada (quantidade volta ao disponível)", body = StockReservation),
+        (status = 404, description = "Reserva não encontrada"),
+        (status = 409, description = "A reserva não está ativa")
+    ),
+    params(
+        ("reservation_id" = Uuid, Path, description = "ID da Reserva"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn release_reservation(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryWrite>,
+    Path(reservation_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let reservation = app_state.inventory_service
+        .release_reservation(&mut *rls_conn, tenant.0, reservation_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(reservation)))
+}
+
+// =============================================================================
+//  DISPONIBILIDADE POR POOL
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct PoolAvailabilityQuery {
+    pub item_id: Option<Uuid>,
+    pub stock_pool_id: Option<Uuid>,
+}
+
+// GET /api/inventory/availability
+#[utoipa::path(
+    get,
+    path = "/api/inventory/availability",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Disponível (físico - reservado) por item e pool, com a quebra por local", body = Vec<PoolAvailability>)
+    ),
+    params(
+        PoolAvailabilityQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_pool_availability(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<PoolAvailabilityQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let availability = app_state.inventory_service
+        .get_stock_pool_availability(&mut *rls_conn, tenant.0, filters.item_id, filters.stock_pool_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(availability)))
+}
+
+// =============================================================================
+//  HISTÓRICO DE MOVIMENTAÇÕES / KARDEX
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListMovementsQuery {
+    pub item_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+    /// Ex: SALE, TRANSFER_IN, CORRECTION
+    pub reason: Option<StockMovementReason>,
+    /// Posição exata (ex: "A1")
+    pub position: Option<String>,
+    /// A partir de (AAAA-MM-DD)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Até (AAAA-MM-DD, inclusive)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-31")]
+    pub end_date: Option<NaiveDate>,
+    /// Página (padrão: 1)
+    pub page: Option<i64>,
+    /// Itens por página (padrão: 50, máximo: 200)
+    pub page_size: Option<i64>,
+}
+
+// GET /api/inventory/movements
+#[utoipa::path(
+    get,
+    path = "/api/inventory/movements",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Histórico de movimentações (mais recentes primeiro)", body = StockMovementPage),
+        (status = 400, description = "Período inválido")
+    ),
+    params(
+        ListMovementsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_movements(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Query(filters): Query<ListMovementsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let page = app_state.inventory_service
+        .list_movements(
+            &mut *rls_conn,
+            tenant.0,
+            filters.item_id,
+            filters.location_id,
+            filters.reason,
+            filters.position.as_deref(),
+            filters.start_date,
+            filters.end_date,
+            filters.page,
+            filters.page_size,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(page)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct KardexQuery {
+    /// Local (padrão: todos os locais somados)
+    pub location_id: Option<Uuid>,
+    /// Início do período (padrão: dia 1 do mês de `endDate`)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-01")]
+    pub start_date: Option<NaiveDate>,
+    /// Fim do período (padrão: hoje)
+    #[param(value_type = Option<String>, format = Date, example = "2026-01-31")]
+    pub end_date: Option<NaiveDate>,
+}
+
+// GET /api/inventory/items/{id}/kardex
+#[utoipa::path(
+    get,
+    path = "/api/inventory/items/{item_id}/kardex",
+    tag = "Inventory",
+    responses(
+        (status = 200, description = "Kardex com saldo e custo médio após cada movimentação", body = ItemKardex),
+        (status = 400, description = "Período inválido"),
+        (status = 404, description = "Item não encontrado")
+    ),
+    params(
+        ("item_id" = Uuid, Path, description = "ID do Item"),
+        KardexQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn get_item_kardex(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermInventoryRead>,
+    Path(item_id): Path<Uuid>,
+    Query(period): Query<KardexQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let kardex = app_state.inventory_service
+        .get_item_kardex(&mut *rls_conn, tenant.0, item_id, period.location_id, period.start_date, period.end_date)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(kardex)))
+}
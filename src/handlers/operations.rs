@@ -9,7 +9,7 @@ use axum::{
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 use utoipa::ToSchema; // <--- Importe ToSchema
 
 use crate::{
@@ -25,8 +25,17 @@ use crate::{
     },
     // Importe os models de resposta para o Swagger
     models::operations::{Pipeline, PipelineStage, PipelineCategory, Order, OrderItem},
+    models::inventory::CompositionChoice,
 };
 
+// --- Validações Auxiliares ---
+fn validate_fulfilment_strategy(val: &str) -> Result<(), ValidationError> {
+    if !matches!(val, "FIXED" | "POOL" | "POOL_SPLIT") {
+        return Err(ValidationError::new("InvalidFulfilmentStrategy"));
+    }
+    Ok(())
+}
+
 // =============================================================================
 //  1. CONFIGURAÇÃO (PIPELINES & STAGES)
 // =============================================================================
@@ -96,6 +105,18 @@ pub struct AddStagePayload {
     //TodO: Trocar segundo example
     #[schema(example = "RESERVE", example = "Ação de estoque: NONE, RESERVE, DEDUCT")]
     pub stock_action: Option<String>,
+
+    // Gera Contas a Receber ao entrar nesta etapa?
+    #[schema(example = false)]
+    pub generates_receivable: Option<bool>,
+
+    // Parcelamento padrão dos pedidos que passarem por aqui
+    pub payment_term_id: Option<Uuid>,
+
+    // Validade das reservas (etapa RESERVE), em horas. Vazio = sem prazo
+    #[validate(range(min = 1))]
+    #[schema(example = 48)]
+    pub reservation_hours: Option<i32>,
 }
 
 // POST /api/operations/pipelines/{id}/stages
@@ -137,7 +158,10 @@ pub async fn add_stage(
             &payload.name,
             payload.category,
             payload.position,
-            payload.stock_action.as_deref()
+            payload.stock_action.as_deref(),
+            payload.generates_receivable.unwrap_or(false),
+            payload.payment_term_id,
+            payload.reservation_hours
         )
         .await
         .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
@@ -159,6 +183,17 @@ pub struct CreateOrderPayload {
 
     #[schema(example = "Pedido urgente do cliente VIP")]
     pub notes: Option<String>,
+
+    // Parcelamento (se não informado, usa o da etapa que gerar o Contas a Receber)
+    pub payment_term_id: Option<Uuid>,
+
+    /// Local de atendimento (padrão: o primeiro local da loja)
+    pub location_id: Option<Uuid>,
+
+    /// FIXED (padrão), POOL ou POOL_SPLIT
+    #[validate(custom(function = "validate_fulfilment_strategy"))]
+    #[schema(example = "POOL")]
+    pub fulfilment_strategy: Option<String>,
 }
 
 // POST /api/operations/orders
@@ -168,7 +203,9 @@ pub struct CreateOrderPayload {
     tag = "Operations",
     request_body = CreateOrderPayload,
     responses(
-        (status = 201, description = "Pedido criado (iniciado na 1ª etapa)", body = Order)
+        (status = 201, description = "Pedido criado (iniciado na 1ª etapa)", body = Order),
+        (status = 400, description = "Estratégia de atendimento inválida"),
+        (status = 404, description = "Local não encontrado")
     ),
     params(
         ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
@@ -183,6 +220,9 @@ pub async fn create_order(
     Json(payload): Json<CreateOrderPayload>,
 ) -> Result<impl IntoResponse, ApiError> {
 
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
     let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
         .await
         .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
@@ -194,7 +234,10 @@ pub async fn create_order(
             tenant.0,
             payload.customer_id,
             payload.pipeline_id,
-            payload.notes.as_deref()
+            payload.notes.as_deref(),
+            payload.payment_term_id,
+            payload.location_id,
+            payload.fulfilment_strategy.as_deref()
         )
         .await
         .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
@@ -212,6 +255,16 @@ pub struct AddOrderItemPayload {
 
     #[schema(example = "50.00")]
     pub unit_price: Decimal,
+
+    /// Unidade de `quantity` e `unitPrice` (padrão: unidade de venda do item)
+    pub unit_id: Option<Uuid>,
+
+    /// Local de atendimento só desta linha (padrão: o do pedido)
+    pub location_id: Option<Uuid>,
+
+    /// Acessórios incluídos e substituições de componentes (itens com ficha técnica)
+    #[serde(default)]
+    pub choices: Vec<CompositionChoice>,
 }
 
 // POST /api/operations/orders/{id}/items
@@ -222,7 +275,8 @@ pub struct AddOrderItemPayload {
     request_body = AddOrderItemPayload,
     responses(
         (status = 201, description = "Item adicionado ao pedido", body = OrderItem),
-        (status = 404, description = "Pedido ou Item não encontrado")
+        (status = 400, description = "Unidade sem conversão para a unidade base do item ou quantidade com mais de 2 casas na unidade base"),
+        (status = 404, description = "Pedido, Item ou Local não encontrado")
     ),
     params(
         ("order_id" = Uuid, Path, description = "ID do Pedido"),
@@ -261,7 +315,10 @@ pub async fn add_order_item(
             payload.item_id,
             payload.quantity,
             payload.unit_price,
-            cost
+            cost,
+            payload.unit_id,
+            payload.location_id,
+            &payload.choices
         )
         .await
         .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
@@ -269,6 +326,65 @@ pub async fn add_order_item(
     Ok((StatusCode::CREATED, Json(item)))
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetOrderFulfilmentPayload {
+    /// Local de atendimento (vazio = o primeiro local da loja)
+    pub location_id: Option<Uuid>,
+
+    /// FIXED, POOL ou POOL_SPLIT
+    #[validate(custom(function = "validate_fulfilment_strategy"))]
+    #[schema(example = "POOL_SPLIT")]
+    pub fulfilment_strategy: String,
+}
+
+// PUT /api/operations/orders/{id}/fulfilment
+#[utoipa::path(
+    put,
+    path = "/api/operations/orders/{order_id}/fulfilment",
+    tag = "Operations",
+    request_body = SetOrderFulfilmentPayload,
+    responses(
+        (status = 200, description = "Local e estratégia de atendimento atualizados", body = Order),
+        (status = 400, description = "Estratégia de atendimento inválida"),
+        (status = 404, description = "Pedido ou Local não encontrado")
+    ),
+    params(
+        ("order_id" = Uuid, Path, description = "ID do Pedido"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn set_order_fulfilment(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    Path(order_id): Path<Uuid>,
+    Json(payload): Json<SetOrderFulfilmentPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let order = app_state.operations_service
+        .set_order_fulfilment(
+            &mut *rls_conn,
+            tenant.0,
+            order_id,
+            payload.location_id,
+            &payload.fulfilment_strategy
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(order)))
+}
+
 // =============================================================================
 //  3. TRANSIÇÃO (A MÁGICA)
 // =============================================================================
@@ -287,7 +403,7 @@ pub struct TransitionOrderPayload {
     request_body = TransitionOrderPayload,
     responses(
         (status = 200, description = "Pedido movido para nova etapa (Estoque/Financeiro atualizados)"),
-        (status = 400, description = "Transição inválida")
+        (status = 400, description = "Transição inválida ou local de atendimento obrigatório (loja com mais de um local)")
     ),
     params(
         ("order_id" = Uuid, Path, description = "ID do Pedido"),
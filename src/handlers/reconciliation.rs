@@ -0,0 +1,383 @@
+// src/handlers/reconciliation.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    common::{
+        error::{ApiError, AppError},
+        db_utils::get_rls_connection,
+    },
+    config::AppState,
+    middleware::{
+        auth::AuthenticatedUser,
+        i18n::Locale,
+        tenancy::TenantContext,
+        rbac::{RequirePermission, PermFinanceRead, PermFinanceWrite},
+    },
+    models::finance::{
+        BankImportResult, BankLineResolution, BankLineStatus, BankStatementFormat,
+        BankStatementImport, BankStatementLine
+    },
+};
+
+// =============================================================================
+//  1. IMPORTAÇÃO DE EXTRATOS (OFX / CSV)
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBankStatementPayload {
+    /// Conta bancária dona do extrato
+    pub account_id: Uuid,
+
+    #[schema(example = "Ofx")]
+    pub format: BankStatementFormat,
+
+    #[validate(length(max = 255))]
+    #[schema(example = "extrato_janeiro.ofx")]
+    pub file_name: Option<String>,
+
+    /// Conteúdo do arquivo (texto)
+    #[validate(length(min = 1, message = "required"))]
+    #[schema(example = "<OFX><BANKTRANLIST><STMTTRN><DTPOSTED>20260115<TRNAMT>-350.00<FITID>001<MEMO>PIX ENVIADO</STMTTRN></BANKTRANLIST></OFX>")]
+    pub content: String,
+}
+
+// POST /api/finance/bank-imports
+#[utoipa::path(
+    post,
+    path = "/api/finance/bank-imports",
+    tag = "Reconciliation",
+    request_body = ImportBankStatementPayload,
+    responses(
+        (status = 201, description = "Extrato importado e conciliado automaticamente", body = BankImportResult),
+        (status = 400, description = "Arquivo inválido"),
+        (status = 404, description = "Conta não encontrada"),
+        (status = 403, description = "Sem permissão (Requer 'finance:write')")
+    ),
+    params(
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn import_bank_statement(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Json(payload): Json<ImportBankStatementPayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let result = app_state.reconciliation_service
+        .import_statement(
+            &mut *rls_conn,
+            tenant.0,
+            payload.account_id,
+            payload.format,
+            payload.file_name.as_deref(),
+            &payload.content,
+            Some(user.0.id),
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListBankImportsQuery {
+    pub account_id: Option<Uuid>,
+}
+
+// GET /api/finance/bank-imports
+#[utoipa::path(
+    get,
+    path = "/api/finance/bank-imports",
+    tag = "Reconciliation",
+    responses(
+        (status = 200, description = "Extratos importados (mais recentes primeiro)", body = Vec<BankStatementImport>)
+    ),
+    params(
+        ListBankImportsQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_bank_imports(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListBankImportsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let imports = app_state.reconciliation_service
+        .list_imports(&mut *rls_conn, tenant.0, filters.account_id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(imports)))
+}
+
+// =============================================================================
+//  2. FILA DE REVISÃO (Linhas do Extrato)
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListBankLinesQuery {
+    pub account_id: Option<Uuid>,
+    pub import_id: Option<Uuid>,
+    /// Pending (fila de revisão), Matched ou Ignored
+    pub status: Option<BankLineStatus>,
+}
+
+// GET /api/finance/bank-lines
+#[utoipa::path(
+    get,
+    path = "/api/finance/bank-lines",
+    tag = "Reconciliation",
+    responses(
+        (status = 200, description = "Linhas do extrato (por data)", body = Vec<BankStatementLine>)
+    ),
+    params(
+        ListBankLinesQuery,
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn list_bank_lines(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceRead>,
+    Query(filters): Query<ListBankLinesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let lines = app_state.reconciliation_service
+        .list_lines(&mut *rls_conn, tenant.0, filters.account_id, filters.import_id, filters.status)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(lines)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchBankLinePayload {
+    /// Movimentação já lançada (mesma conta e mesmo valor)
+    pub movement_id: Uuid,
+}
+
+// POST /api/finance/bank-lines/{id}/match
+#[utoipa::path(
+    post,
+    path = "/api/finance/bank-lines/{line_id}/match",
+    tag = "Reconciliation",
+    request_body = MatchBankLinePayload,
+    responses(
+        (status = 200, description = "Linha conciliada", body = BankStatementLine),
+        (status = 400, description = "Conta ou valor não conferem"),
+        (status = 409, description = "Linha já revisada ou movimentação já conciliada")
+    ),
+    params(
+        ("line_id" = Uuid, Path, description = "ID da Linha do Extrato"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn match_bank_line(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(line_id): Path<Uuid>,
+    Json(payload): Json<MatchBankLinePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let line = app_state.reconciliation_service
+        .match_line(&mut *rls_conn, tenant.0, line_id, payload.movement_id, user.0.id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(line)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleBankLinePayload {
+    /// Se não informado, usa o título sugerido na importação
+    pub title_id: Option<Uuid>,
+}
+
+// POST /api/finance/bank-lines/{id}/settle
+#[utoipa::path(
+    post,
+    path = "/api/finance/bank-lines/{line_id}/settle",
+    tag = "Reconciliation",
+    request_body = SettleBankLinePayload,
+    responses(
+        (status = 201, description = "Título baixado e linha conciliada", body = BankLineResolution),
+        (status = 400, description = "Sem título para baixar ou tipo incompatível"),
+        (status = 409, description = "Linha já revisada ou título não está em aberto")
+    ),
+    params(
+        ("line_id" = Uuid, Path, description = "ID da Linha do Extrato"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn settle_bank_line(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(line_id): Path<Uuid>,
+    Json(payload): Json<SettleBankLinePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let resolution = app_state.reconciliation_service
+        .settle_line(&mut *rls_conn, tenant.0, line_id, payload.title_id, user.0.id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(resolution)))
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTitleFromBankLinePayload {
+    /// Se não informado, usa a descrição do banco
+    #[validate(length(min = 1, max = 255))]
+    #[schema(example = "Tarifa bancária - Janeiro/2026")]
+    pub description: Option<String>,
+
+    pub category_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+}
+
+// POST /api/finance/bank-lines/{id}/create-title
+#[utoipa::path(
+    post,
+    path = "/api/finance/bank-lines/{line_id}/create-title",
+    tag = "Reconciliation",
+    request_body = CreateTitleFromBankLinePayload,
+    responses(
+        (status = 201, description = "Título criado já quitado e linha conciliada", body = BankLineResolution),
+        (status = 400, description = "Categoria incompatível"),
+        (status = 409, description = "Linha já revisada")
+    ),
+    params(
+        ("line_id" = Uuid, Path, description = "ID da Linha do Extrato"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn create_title_from_bank_line(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(line_id): Path<Uuid>,
+    Json(payload): Json<CreateTitleFromBankLinePayload>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let resolution = app_state.reconciliation_service
+        .create_title_from_line(
+            &mut *rls_conn,
+            tenant.0,
+            line_id,
+            payload.description.as_deref(),
+            payload.category_id,
+            payload.customer_id,
+            user.0.id,
+        )
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::CREATED, Json(resolution)))
+}
+
+// POST /api/finance/bank-lines/{id}/ignore
+#[utoipa::path(
+    post,
+    path = "/api/finance/bank-lines/{line_id}/ignore",
+    tag = "Reconciliation",
+    responses(
+        (status = 200, description = "Linha descartada", body = BankStatementLine),
+        (status = 409, description = "Linha já revisada")
+    ),
+    params(
+        ("line_id" = Uuid, Path, description = "ID da Linha do Extrato"),
+        ("x-tenant-id" = Uuid, Header, description = "ID da Loja")
+    ),
+    security(("api_jwt" = []))
+)]
+pub async fn ignore_bank_line(
+    State(app_state): State<AppState>,
+    locale: Locale,
+    user: AuthenticatedUser,
+    tenant: TenantContext,
+    _guard: RequirePermission<PermFinanceWrite>,
+    Path(line_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+
+    let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
+        .await
+        .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
+
+    let line = app_state.reconciliation_service
+        .ignore_line(&mut *rls_conn, tenant.0, line_id, user.0.id)
+        .await
+        .map_err(|app_err| app_err.to_api_error(&locale, &app_state.i18n_store))?;
+
+    Ok((StatusCode::OK, Json(line)))
+}
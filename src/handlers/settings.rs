@@ -6,6 +6,7 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use validator::Validate;
 
 use crate::{
     common::{
@@ -63,6 +64,7 @@ pub async fn get_settings(
     request_body = UpdateSettingsRequest, // Define o corpo da requisição
     responses(
         (status = 200, description = "Configurações atualizadas", body = TenantSettings),
+        (status = 400, description = "Taxas de juros/multa inválidas"),
         (status = 401, description = "Não autorizado"),
     ),
     security(
@@ -77,6 +79,9 @@ pub async fn update_settings(
     Json(payload): Json<UpdateSettingsRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
 
+    payload.validate()
+        .map_err(|e| AppError::ValidationError(e).to_api_error(&locale, &app_state.i18n_store))?;
+
     let mut rls_conn = get_rls_connection(&app_state, &tenant, &user)
         .await
         .map_err(|e| e.to_api_error(&locale, &app_state.i18n_store))?;
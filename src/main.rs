@@ -2,7 +2,7 @@
 
 use axum::{
     middleware as axum_middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use tokio::net::TcpListener;
@@ -39,6 +39,9 @@ async fn main() {
 
     tracing::info!("✅ Migrações do banco de dados executadas com sucesso!");
 
+    // Rotinas em segundo plano (ex: marcar títulos vencidos)
+    services::scheduler::spawn(app_state.clone());
+
     // --- ROTAS PÚBLICAS ---
     let auth_routes = Router::new()
         .route("/register", post(handlers::auth::register))
@@ -71,10 +74,34 @@ async fn main() {
     let inventory_routes = Router::new()
         .route("/items", post(handlers::inventory::create_item).get(handlers::inventory::get_all_items))
         .route("/items/{id}/composition", post(handlers::inventory::add_composition_item).get(handlers::inventory::get_item_composition))
+        .route("/items/{id}/composition/flattened", get(handlers::inventory::get_flattened_composition))
+        .route("/items/{id}/where-used", get(handlers::inventory::get_where_used))
+        .route("/items/{id}/units", put(handlers::inventory::set_item_units))
+        .route("/items/{id}/kardex", get(handlers::inventory::get_item_kardex))
         .route("/units", post(handlers::inventory::create_unit_of_measure).get(handlers::inventory::get_all_units))
+        .route("/units/conversions", post(handlers::inventory::create_unit_conversion).get(handlers::inventory::list_unit_conversions))
+        .route("/units/conversions/{id}", delete(handlers::inventory::delete_unit_conversion))
         .route("/categories", post(handlers::inventory::create_category).get(handlers::inventory::get_all_categories))
         .route("/sell", post(handlers::inventory::sell_item))
         .route("/stock-entry", post(handlers::inventory::add_stock))
+        .route("/movements", get(handlers::inventory::list_movements))
+        .route("/transfers", post(handlers::inventory::create_transfer).get(handlers::inventory::list_transfers))
+        .route("/transfers/{id}", get(handlers::inventory::get_transfer))
+        .route("/transfers/{id}/receive", post(handlers::inventory::receive_transfer))
+        .route("/transfers/{id}/cancel", post(handlers::inventory::cancel_transfer))
+        .route("/counts", post(handlers::inventory::open_count).get(handlers::inventory::list_counts))
+        .route("/counts/{id}", get(handlers::inventory::get_count))
+        .route("/counts/{id}/entries", post(handlers::inventory::record_counts))
+        .route("/counts/{id}/approve", post(handlers::inventory::approve_count))
+        .route("/counts/{id}/cancel", post(handlers::inventory::cancel_count))
+        .route("/counts/{id}/report", get(handlers::inventory::get_count_report))
+        .route("/production-orders", post(handlers::inventory::create_production_order).get(handlers::inventory::list_production_orders))
+        .route("/production-orders/{id}", get(handlers::inventory::get_production_order))
+        .route("/production-orders/{id}/complete", post(handlers::inventory::complete_production_order))
+        .route("/production-orders/{id}/cancel", post(handlers::inventory::cancel_production_order))
+        .route("/reservations", get(handlers::inventory::list_reservations))
+        .route("/reservations/{id}/release", post(handlers::inventory::release_reservation))
+        .route("/availability", get(handlers::inventory::get_pool_availability))
         .layer(axum_middleware::from_fn_with_state(app_state.clone(), tenant_guard));
 
     // 2. Operações (CRM & Pedidos)
@@ -89,20 +116,67 @@ async fn main() {
         .route("/pipelines/{id}/stages", post(handlers::operations::add_stage))
         .route("/orders", post(handlers::operations::create_order))
         .route("/orders/{id}/items", post(handlers::operations::add_order_item))
+        .route("/orders/{id}/fulfilment", put(handlers::operations::set_order_fulfilment))
         .route("/orders/{id}/transition", post(handlers::operations::transition_order))
         // Nota: A rota de PDF saiu daqui e foi para document_routes
         .layer(axum::middleware::from_fn_with_state(app_state.clone(), tenant_guard));
 
+    // 2.1 Financeiro (Contas, Categorias e Títulos)
+    let finance_routes = Router::new()
+        .route("/accounts", post(handlers::finance::create_account).get(handlers::finance::list_accounts))
+        .route("/accounts/{id}/statement", get(handlers::finance::get_account_statement))
+        .route("/categories", post(handlers::finance::create_category).get(handlers::finance::list_categories))
+        .route("/categories/tree", get(handlers::finance::get_category_tree))
+        .route("/categories/{id}", put(handlers::finance::update_category))
+        .route("/titles", post(handlers::finance::create_title).get(handlers::finance::list_titles))
+        .route("/titles/{id}", get(handlers::finance::get_title))
+        .route("/titles/{id}/pix", get(handlers::finance::get_title_pix))
+        .route("/titles/{id}/settle", post(handlers::finance::settle_title))
+        .route("/titles/{id}/movements", get(handlers::finance::list_title_movements))
+        .route("/movements/{id}/reverse", post(handlers::finance::reverse_movement))
+        .route("/transfers", post(handlers::finance::create_transfer))
+        .route("/reports/dre", get(handlers::finance::get_dre_report))
+        .route("/reports/budget", get(handlers::finance::get_budget_report))
+        .route("/budgets", put(handlers::finance::set_budget).get(handlers::finance::list_budgets))
+        .route("/budgets/{id}", delete(handlers::finance::delete_budget))
+        .route("/recurring-titles", post(handlers::finance::create_recurring_title).get(handlers::finance::list_recurring_titles))
+        .route("/recurring-titles/{id}", put(handlers::finance::update_recurring_title))
+        .route("/payment-terms", post(handlers::finance::create_payment_term).get(handlers::finance::list_payment_terms))
+        .route("/cash-sessions", post(handlers::finance::open_cash_session).get(handlers::finance::list_cash_sessions))
+        .route("/cash-sessions/{id}/movements", post(handlers::finance::post_cash_movement))
+        .route("/cash-sessions/{id}/close", post(handlers::finance::close_cash_session))
+        .route("/cash-sessions/{id}/report", get(handlers::finance::get_cash_session_report))
+        .route("/bank-imports", post(handlers::reconciliation::import_bank_statement).get(handlers::reconciliation::list_bank_imports))
+        .route("/bank-lines", get(handlers::reconciliation::list_bank_lines))
+        .route("/bank-lines/{id}/match", post(handlers::reconciliation::match_bank_line))
+        .route("/bank-lines/{id}/settle", post(handlers::reconciliation::settle_bank_line))
+        .route("/bank-lines/{id}/create-title", post(handlers::reconciliation::create_title_from_bank_line))
+        .route("/bank-lines/{id}/ignore", post(handlers::reconciliation::ignore_bank_line))
+        .route("/accounts/{id}/billing-config", get(handlers::billing::get_billing_config).put(handlers::billing::upsert_billing_config))
+        .route("/titles/{id}/boleto", post(handlers::billing::create_boleto))
+        .route("/boletos", get(handlers::billing::list_boletos))
+        .route("/boletos/{id}", get(handlers::billing::get_boleto))
+        .route("/cnab/remittances", post(handlers::billing::generate_remittance))
+        .route("/cnab/returns", post(handlers::billing::import_cnab_return))
+        .route("/cnab/files", get(handlers::billing::list_cnab_files))
+        .layer(axum_middleware::from_fn_with_state(app_state.clone(), tenant_guard));
+
     // 3. Dashboards & Relatórios
     let dashboard_routes = Router::new()
         .route("/summary", get(handlers::dashboard::get_summary))
         .route("/sales-chart", get(handlers::dashboard::get_sales_chart))
+        .route("/cash-flow", get(handlers::dashboard::get_cash_flow))
         .route("/top-products", get(handlers::dashboard::get_top_products))
         .layer(axum::middleware::from_fn_with_state(app_state.clone(), tenant_guard));
 
     // 4. Documentos (PDFs)
     let document_routes = Router::new()
         .route("/orders/{id}/pdf", get(handlers::documents::generate_order_pdf))
+        .route("/cash-sessions/{id}/pdf", get(handlers::documents::generate_cash_session_pdf))
+        .route("/accounts/{id}/statement/pdf", get(handlers::documents::generate_account_statement_pdf))
+        .route("/accounts/{id}/statement/csv", get(handlers::documents::generate_account_statement_csv))
+        .route("/boletos/{id}/pdf", get(handlers::documents::generate_boleto_pdf))
+        .route("/cnab-files/{id}", get(handlers::documents::download_cnab_file))
         .layer(axum::middleware::from_fn_with_state(app_state.clone(), tenant_guard));
 
     // 5. Configurações da Loja
@@ -128,6 +202,7 @@ async fn main() {
         .nest("/api/inventory", inventory_routes)
         .nest("/api/crm", crm_routes)
         .nest("/api/operations", operations_routes)
+        .nest("/api/finance", finance_routes)
         .nest("/api/dashboard", dashboard_routes)
         .nest("/api/documents", document_routes) // Agora em /api/documents/orders/...
         .nest("/api/settings", settings_routes)  // Agora em /api/settings
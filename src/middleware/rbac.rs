@@ -96,4 +96,19 @@ impl PermissionDef for PermInventoryRead {
 pub struct PermCrmRead;
 impl PermissionDef for PermCrmRead {
     fn slug() -> &'static str { "crm:read" }
-}
\ No newline at end of file
+}
+
+pub struct PermFinanceRead;
+impl PermissionDef for PermFinanceRead {
+    fn slug() -> &'static str { "finance:read" }
+}
+
+pub struct PermFinanceWrite;
+impl PermissionDef for PermFinanceWrite {
+    fn slug() -> &'static str { "finance:write" }
+}
+
+pub struct PermCashOperate;
+impl PermissionDef for PermCashOperate {
+    fn slug() -> &'static str { "cash:operate" }
+}
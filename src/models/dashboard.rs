@@ -2,6 +2,8 @@
 
 use serde::{Serialize};
 use rust_decimal::Decimal;
+use chrono::NaiveDate;
+use uuid::Uuid;
 use sqlx::FromRow;
 use utoipa::ToSchema; // <--- 1. Importe o ToSchema
 
@@ -20,6 +22,37 @@ pub struct DashboardSummary {
 
     #[schema(example = "12500.00")]
     pub current_balance: Decimal,   // Saldo somado de todos os bancos
+
+    #[schema(example = "850.00")]
+    pub overdue_receivables: Decimal, // Em atraso (A receber)
+
+    #[schema(example = "320.00")]
+    pub overdue_payables: Decimal,    // Em atraso (A pagar)
+
+    // Despesas do mês acima do % de alerta do orçamento
+    pub over_budget_categories: Vec<BudgetAlert>,
+}
+
+// Categoria de despesa que passou do alerta de orçamento no mês corrente
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlert {
+    pub category_id: Uuid,
+
+    #[schema(example = "2.1")]
+    pub code: Option<String>,
+
+    #[schema(example = "Despesas Administrativas")]
+    pub name: String,
+
+    #[schema(example = "5000.00")]
+    pub budget: Decimal,
+
+    #[schema(example = "4700.00")]
+    pub accrued: Decimal, // Competência do mês, com as subcategorias
+
+    #[schema(example = "94.00")]
+    pub percent_used: Option<Decimal>, // Vazio quando o orçado é zero
 }
 
 // 2. Gráfico de Vendas (Últimos 30 dias)
@@ -45,4 +78,64 @@ pub struct TopProductEntry {
 
     #[schema(example = "1200.00")]
     pub total_revenue: Option<Decimal>,
-}
\ No newline at end of file
+}
+
+// 4. Fluxo de Caixa (Realizado x Projetado)
+
+/// Totais de um dia: movimentações (realizado) ou títulos em aberto (projetado).
+/// Uso interno do repositório.
+#[derive(Debug, FromRow)]
+pub struct CashFlowDayTotal {
+    pub date: NaiveDate,
+    pub inflow: Decimal,
+    pub outflow: Decimal,
+    pub net: Decimal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowPoint {
+    /// Dia (série diária) ou início da semana (série semanal)
+    #[schema(value_type = String, format = Date, example = "2026-01-19")]
+    pub date: NaiveDate,
+
+    #[schema(example = "1200.00")]
+    pub inflow: Decimal,
+
+    #[schema(example = "450.00")]
+    pub outflow: Decimal,
+
+    /// Saldo ao final do dia/semana
+    #[schema(example = "13250.00")]
+    pub balance: Decimal,
+
+    /// false = realizado (movimentações), true = projetado (títulos em aberto)
+    pub projected: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowProjection {
+    #[schema(value_type = String, format = Date, example = "2026-01-19")]
+    pub today: NaiveDate,
+
+    /// Saldo somado das contas ativas
+    #[schema(example = "12500.00")]
+    pub current_balance: Decimal,
+
+    /// Saldo projetado ao final do horizonte
+    #[schema(example = "14800.00")]
+    pub projected_balance: Decimal,
+
+    /// Primeiro dia em que o saldo projetado fica negativo
+    #[schema(value_type = Option<String>, format = Date)]
+    pub first_negative_date: Option<NaiveDate>,
+
+    /// Histórico realizado seguido da projeção. O dia de hoje aparece nas duas
+    /// séries: o ponto realizado termina no saldo atual e o projetado soma os
+    /// títulos vencidos e a vencer hoje.
+    pub daily: Vec<CashFlowPoint>,
+
+    /// Mesmas séries agrupadas por semana (segunda a domingo)
+    pub weekly: Vec<CashFlowPoint>,
+}
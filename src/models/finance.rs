@@ -9,7 +9,7 @@ use utoipa::ToSchema; // <--- 1. Importe o ToSchema
 
 // --- Enums (Mapeando o Postgres) ---
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)] // <--- ToSchema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, ToSchema)] // <--- ToSchema
 #[sqlx(type_name = "title_kind", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TitleKind {
     Receivable, // A Receber
@@ -26,6 +26,73 @@ pub enum TitleStatus {
     Overdue,   // Vencido
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "cash_session_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CashSessionStatus {
+    Open,   // Caixa aberto
+    Closed, // Caixa fechado
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "cash_movement_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CashMovementKind {
+    Sale,       // Venda / Recebimento (+)
+    Payment,    // Pagamento com dinheiro do caixa (-)
+    Deposit,    // Suprimento (+)
+    Withdrawal, // Sangria (-)
+    Adjustment, // Quebra / Sobra no fechamento (+/-)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "bank_statement_format", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BankStatementFormat {
+    Ofx, // Open Financial Exchange (padrão dos bancos)
+    Csv, // Planilha: data;descrição;valor
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "bank_line_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BankLineStatus {
+    Pending, // Aguardando revisão
+    Matched, // Conciliada com uma movimentação
+    Ignored, // Descartada (ex: tarifa já lançada por fora)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "recurrence_frequency", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecurrenceFrequency {
+    Weekly,  // Semanal
+    Monthly, // Mensal (dia 31 vira o último dia nos meses mais curtos)
+    Yearly,  // Anual
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "cnab_layout")]
+pub enum CnabLayout {
+    #[sqlx(rename = "CNAB240")]
+    Cnab240, // FEBRABAN (segmentos P, Q, R / T, U)
+    #[sqlx(rename = "CNAB400")]
+    Cnab400, // Layout Bradesco
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "cnab_file_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CnabFileKind {
+    Remittance, // Remessa (enviada ao banco)
+    Return,     // Retorno (recebida do banco)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "boleto_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BoletoStatus {
+    Pending,    // Aguardando remessa
+    Remitted,   // Enviado ao banco
+    Registered, // Entrada confirmada pelo banco
+    Rejected,   // Recusado pelo banco
+    Paid,       // Liquidado
+    Cancelled,  // Baixado
+}
+
 // --- Structs ---
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)] // <--- ToSchema
@@ -61,15 +128,52 @@ pub struct FinancialCategory {
     #[schema(ignore)]
     pub tenant_id: Uuid,
 
+    // Código no plano de contas (ex: "2.4.01")
+    #[schema(example = "1.1.01")]
+    pub code: Option<String>,
+
     #[schema(example = "Venda de Produtos")]
     pub name: String,
 
+    // Categoria pai (vazio = raiz). Mesmo tipo do pai
+    pub parent_id: Option<Uuid>,
+
     pub kind: TitleKind,
 
     #[schema(example = true)]
     pub is_active: Option<bool>,
 }
 
+// Nó da árvore do plano de contas com os totais do período (títulos por competência)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTreeNode {
+    #[serde(flatten)]
+    pub category: FinancialCategory,
+
+    // Lançado diretamente na categoria
+    #[schema(example = "1200.00")]
+    pub own_total: Decimal,
+
+    // Categoria + subcategorias
+    #[schema(example = "3500.00")]
+    pub total: Decimal,
+
+    #[schema(no_recursion)]
+    pub children: Vec<CategoryTreeNode>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTree {
+    #[schema(value_type = String, format = Date, example = "2026-01-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, format = Date, example = "2026-03-31")]
+    pub end_date: NaiveDate,
+
+    pub roots: Vec<CategoryTreeNode>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)] // <--- ToSchema
 #[serde(rename_all = "camelCase")]
 pub struct FinancialTitle {
@@ -101,6 +205,16 @@ pub struct FinancialTitle {
     #[schema(value_type = String, format = Date, example = "2023-12-01")]
     pub competence_date: NaiveDate,
 
+    // Parcelamento (títulos do mesmo grupo vêm do mesmo pedido). 0 = Entrada
+    pub installment_group_id: Option<Uuid>,
+    #[schema(example = 1)]
+    pub installment_number: Option<i16>,
+    #[schema(example = 3)]
+    pub installment_count: Option<i16>,
+
+    // Recorrência que gerou o título (Aluguel, Mensalidade...)
+    pub recurring_title_id: Option<Uuid>,
+
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -123,5 +237,612 @@ pub struct FinancialMovement {
     #[schema(value_type = String, format = Date, example = "2023-12-20")]
     pub movement_date: NaiveDate,
 
+    #[schema(example = "Baixa: Aluguel Janeiro/2026")]
+    pub description: Option<String>,
+
+    // Parte do valor que foi juros/multa por atraso (não abate o saldo do título)
+    #[schema(example = "1.50")]
+    pub interest_amount: Decimal,
+    #[schema(example = "3.00")]
+    pub fee_amount: Decimal,
+
+    // Caixa (se lançada dentro de uma sessão de caixa)
+    pub cash_session_id: Option<Uuid>,
+    pub cash_kind: Option<CashMovementKind>,
+
+    // Transferência entre contas (as duas pernas compartilham o mesmo id)
+    pub transfer_id: Option<Uuid>,
+
+    // Auditoria
+    pub created_by: Option<Uuid>,
+
+    // Se esta linha é um estorno, aponta para a movimentação original
+    pub reversal_of: Option<Uuid>,
+    // Preenchido na movimentação original quando ela é estornada
+    pub reversed_at: Option<DateTime<Utc>>,
+    #[schema(example = "Pagamento lançado em duplicidade")]
+    pub reversal_reason: Option<String>,
+
     pub created_at: Option<DateTime<Utc>>,
-}
\ No newline at end of file
+}
+
+// Resposta de uma baixa (ou estorno): o título atualizado + a movimentação gerada
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleSettlement {
+    pub title: Option<FinancialTitle>,
+    pub movement: FinancialMovement,
+    pub account: FinancialAccount,
+}
+
+// Cobrança Pix de um título: payload "Copia e Cola" + QR Code em PNG (base64)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PixCharge {
+    pub title_id: Uuid,
+    #[schema(example = "3F2A9C1B7D8E4F60A1B2C3D4E")]
+    pub txid: String,
+    // Saldo + juros e multa até hoje
+    #[schema(example = "150.00")]
+    pub amount: Decimal,
+    pub payload: String,
+    pub qr_code_png: String,
+}
+
+// Resposta de uma transferência: as duas pernas e as contas atualizadas
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTransfer {
+    pub transfer_id: Uuid,
+    pub outgoing: FinancialMovement,
+    pub incoming: FinancialMovement,
+    pub from_account: FinancialAccount,
+    pub to_account: FinancialAccount,
+}
+
+// Linha do extrato: a movimentação + o saldo da conta logo após ela
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementLine {
+    #[serde(flatten)]
+    pub movement: FinancialMovement,
+
+    #[schema(example = "1350.00")]
+    pub running_balance: Decimal,
+}
+
+// Extrato da conta no período
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatement {
+    pub account: FinancialAccount,
+
+    #[schema(value_type = String, format = Date, example = "2026-01-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, format = Date, example = "2026-01-31")]
+    pub end_date: NaiveDate,
+
+    pub opening_balance: Decimal,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub closing_balance: Decimal,
+
+    pub lines: Vec<StatementLine>,
+
+    // Soma de TODAS as movimentações da conta. Deve bater com account.current_balance;
+    // se não bater, o cache de saldo está divergente do histórico.
+    pub ledger_balance: Decimal,
+}
+
+// Condição de Pagamento (Ex: "Entrada 30% + 3x a cada 30 dias")
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentTerm {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    #[schema(example = "Entrada + 3x")]
+    pub name: String,
+
+    #[schema(example = 3)]
+    pub installments: i32,
+
+    #[schema(example = 30)]
+    pub interval_days: i32,
+
+    #[schema(example = 30)]
+    pub first_due_days: i32,
+
+    #[schema(example = "30.00")]
+    pub down_payment_percent: Decimal,
+
+    pub is_active: bool,
+
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// Título Recorrente (Modelo + Regra). Gera os financial_titles com antecedência.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTitle {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    #[schema(example = "Aluguel da loja")]
+    pub description: String,
+    pub kind: TitleKind,
+    #[schema(example = "3500.00")]
+    pub amount: Decimal,
+    pub category_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+
+    pub frequency: RecurrenceFrequency,
+    #[schema(example = 1)]
+    pub interval_count: i32,
+    #[schema(value_type = String, format = Date, example = "2026-02-05")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = Option<String>, format = Date, example = "2026-12-05")]
+    pub end_date: Option<NaiveDate>,
+
+    #[schema(example = 30)]
+    pub lead_days: i32,
+
+    // Ocorrências já geradas e o próximo vencimento a gerar (vazio = encerrada)
+    #[schema(example = 2)]
+    pub generated_count: i32,
+    #[schema(value_type = Option<String>, format = Date, example = "2026-04-05")]
+    pub next_due_date: Option<NaiveDate>,
+
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// Sessão de Caixa (Abertura -> Fechamento)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashSession {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub account_id: Uuid,
+    pub location_id: Uuid,
+
+    pub status: CashSessionStatus,
+
+    pub opened_by: Uuid,
+    #[schema(example = "200.00")]
+    pub opening_float: Decimal,
+    pub opened_at: DateTime<Utc>,
+
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+    #[schema(example = "1350.00")]
+    pub expected_amount: Option<Decimal>,
+    #[schema(example = "1345.00")]
+    pub counted_amount: Option<Decimal>,
+    #[schema(example = "-5.00")]
+    pub difference: Option<Decimal>,
+
+    pub notes: Option<String>,
+}
+
+// Relatório de fechamento (Contado x Esperado)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashSessionReport {
+    pub session: CashSession,
+
+    #[schema(example = "Caixa Balcão")]
+    pub account_name: String,
+    #[schema(example = "Loja Centro")]
+    pub location_name: String,
+
+    // Totais por natureza (valores com sinal)
+    #[schema(example = "1300.00")]
+    pub total_sales: Decimal,
+    #[schema(example = "-50.00")]
+    pub total_payments: Decimal,
+    #[schema(example = "100.00")]
+    pub total_deposits: Decimal,
+    #[schema(example = "-200.00")]
+    pub total_withdrawals: Decimal,
+    #[schema(example = "-5.00")]
+    pub total_adjustments: Decimal,
+
+    // Troco inicial + movimentações (sem a quebra/sobra do fechamento)
+    #[schema(example = "1350.00")]
+    pub expected_amount: Decimal,
+
+    pub movements: Vec<FinancialMovement>,
+}
+
+// Arquivo de extrato bancário importado (OFX / CSV)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankStatementImport {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub account_id: Uuid,
+    pub format: BankStatementFormat,
+
+    #[schema(example = "extrato_janeiro.ofx")]
+    pub file_name: Option<String>,
+
+    #[schema(example = 42)]
+    pub total_lines: i32,
+    // Linhas ignoradas por já terem sido importadas antes
+    #[schema(example = 0)]
+    pub duplicated_lines: i32,
+    // Conciliadas automaticamente com movimentações existentes
+    #[schema(example = 30)]
+    pub matched_lines: i32,
+
+    pub imported_by: Option<Uuid>,
+    pub imported_at: DateTime<Utc>,
+}
+
+// Linha do extrato bancário (fila de conciliação)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankStatementLine {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub import_id: Uuid,
+    pub account_id: Uuid,
+
+    // FITID do OFX (ou chave gerada para CSV)
+    #[schema(example = "20260115001")]
+    pub external_id: String,
+
+    #[schema(value_type = String, format = Date, example = "2026-01-15")]
+    pub posted_date: NaiveDate,
+
+    #[schema(example = "-350.00", example = "Positivo = Crédito, Negativo = Débito")]
+    pub amount: Decimal,
+
+    #[schema(example = "PIX ENVIADO FORNECEDOR XYZ")]
+    pub description: Option<String>,
+
+    pub status: BankLineStatus,
+
+    pub matched_movement_id: Option<Uuid>,
+    // Título em aberto com mesmo valor/vencimento próximo (sugestão para a baixa)
+    pub suggested_title_id: Option<Uuid>,
+
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Resultado da importação: o resumo + as linhas novas (já com a conciliação automática)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankImportResult {
+    pub import: BankStatementImport,
+    pub lines: Vec<BankStatementLine>,
+}
+
+// Revisão de uma linha pendente que gerou uma baixa (título existente ou novo)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankLineResolution {
+    pub line: BankStatementLine,
+    pub settlement: TitleSettlement,
+}
+
+// --- DRE (Demonstração do Resultado do Exercício) ---
+
+// Total de títulos por mês/tipo/categoria (linha crua vinda do banco)
+#[derive(Debug, FromRow)]
+pub struct DreCategoryTotal {
+    pub month: NaiveDate,
+    pub kind: TitleKind,
+    pub category_id: Option<Uuid>,
+    pub total: Decimal,
+}
+
+// Valor mensal genérico (ex: CMV por mês)
+#[derive(Debug, FromRow)]
+pub struct MonthlyAmount {
+    pub month: NaiveDate,
+    pub total: Decimal,
+}
+
+// Linha da DRE: um valor por mês (mesma ordem de `DreReport.months`) + total do período
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DreLine {
+    // Categoria de origem (vazio nas linhas de total e em "Sem categoria")
+    pub category_id: Option<Uuid>,
+
+    #[schema(example = "1.1")]
+    pub code: Option<String>,
+
+    // Nível na árvore do plano de contas (0 = raiz). Os valores já somam as subcategorias
+    #[schema(example = 0)]
+    pub depth: i32,
+
+    #[schema(example = "Vendas de Produtos")]
+    pub label: String,
+
+    #[schema(example = json!(["1500.00", "2300.00"]))]
+    pub values: Vec<Decimal>,
+
+    #[schema(example = "3800.00")]
+    pub total: Decimal,
+}
+
+// DRE por competência: Receita Bruta - CMV = Lucro Bruto - Despesas = Resultado Líquido
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DreReport {
+    #[schema(value_type = String, format = Date, example = "2026-01-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, format = Date, example = "2026-03-31")]
+    pub end_date: NaiveDate,
+
+    // Primeiro dia de cada mês do período (as colunas)
+    #[schema(value_type = Vec<String>, example = json!(["2026-01-01", "2026-02-01", "2026-03-01"]))]
+    pub months: Vec<NaiveDate>,
+
+    // Contas a Receber por categoria
+    pub revenue_lines: Vec<DreLine>,
+    pub gross_revenue: DreLine,
+
+    // Custo das mercadorias vendidas (order_items.unit_cost)
+    pub cogs: DreLine,
+    pub gross_profit: DreLine,
+
+    // Contas a Pagar por categoria
+    pub expense_lines: Vec<DreLine>,
+    pub expenses: DreLine,
+
+    pub net_result: DreLine,
+}
+
+// --- Orçamento por categoria ---
+
+// Orçamento de uma categoria no mês (cobre a categoria e as subcategorias)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryBudget {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub category_id: Uuid,
+
+    // Sempre o 1º dia do mês
+    #[schema(value_type = String, format = Date, example = "2026-03-01")]
+    pub month: NaiveDate,
+
+    #[schema(example = "5000.00")]
+    pub amount: Decimal,
+
+    pub created_by: Option<Uuid>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// Valor por categoria (linha crua vinda do banco)
+#[derive(Debug, FromRow)]
+pub struct CategoryAmount {
+    pub category_id: Uuid,
+    pub total: Decimal,
+}
+
+// Linha do Orçado x Realizado. Valores já somam as subcategorias
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetLine {
+    pub category_id: Uuid,
+    #[schema(example = "2.1")]
+    pub code: Option<String>,
+    #[schema(example = "Despesas Administrativas")]
+    pub name: String,
+    pub kind: TitleKind,
+    #[schema(example = 0)]
+    pub depth: i32,
+
+    // Orçado na própria categoria; vazio = herda a soma das subcategorias
+    #[schema(example = "5000.00")]
+    pub own_budget: Option<Decimal>,
+    // Orçamento valendo para a linha (vazio = sem orçamento na árvore)
+    #[schema(example = "5000.00")]
+    pub budget: Option<Decimal>,
+
+    // Títulos não cancelados por competência
+    #[schema(example = "4700.00")]
+    pub accrued: Decimal,
+    // Baixas no período (sem juros/multa e sem estornos)
+    #[schema(example = "3200.00")]
+    pub settled: Decimal,
+
+    // Competência - Orçado (positivo na despesa = estourou)
+    #[schema(example = "-300.00")]
+    pub variance: Option<Decimal>,
+    #[schema(example = "94.00")]
+    pub percent_used: Option<Decimal>,
+
+    // Despesa que passou do % de alerta das configurações
+    pub over_threshold: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReport {
+    #[schema(value_type = String, format = Date, example = "2026-03-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, format = Date, example = "2026-03-31")]
+    pub end_date: NaiveDate,
+
+    #[schema(example = "90.00")]
+    pub alert_percent: Decimal,
+
+    // Ordem da árvore do plano de contas (pai antes dos filhos)
+    pub lines: Vec<BudgetLine>,
+}
+
+// Convênio de cobrança da conta bancária (dados do beneficiário no banco)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingConfig {
+    pub account_id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    #[schema(example = "237")]
+    pub bank_code: String,
+    #[schema(example = "1234")]
+    pub agency: String,
+    #[schema(example = "5")]
+    pub agency_digit: Option<String>,
+    #[schema(example = "0012345")]
+    pub account_number: String,
+    #[schema(example = "6")]
+    pub account_digit: Option<String>,
+    #[schema(example = "09")]
+    pub wallet: String,
+    #[schema(example = "4567890")]
+    pub agreement_code: String,
+
+    pub layout: CnabLayout,
+
+    // Próximo Nosso Número e próxima remessa
+    #[schema(example = 1)]
+    pub next_our_number: i64,
+    #[schema(example = 1)]
+    pub next_remittance_number: i32,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+// Boleto de um título a receber
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Boleto {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub title_id: Uuid,
+    pub account_id: Uuid,
+
+    #[schema(example = "00000000001")]
+    pub our_number: String,
+    #[schema(example = "150.00")]
+    pub amount: Decimal,
+    #[schema(value_type = String, format = Date, example = "2026-02-10")]
+    pub due_date: NaiveDate,
+
+    #[schema(example = "23791000000000150001234090000000000100123450")]
+    pub barcode: String,
+    #[schema(example = "23791.23405 90000.000001 01001.234508 1 00000000015000")]
+    pub digitable_line: String,
+
+    pub status: BoletoStatus,
+    // Arquivo de remessa em que o boleto foi enviado
+    pub remittance_id: Option<Uuid>,
+
+    // Liquidação informada no retorno
+    #[schema(example = "150.00")]
+    pub paid_amount: Option<Decimal>,
+    #[schema(value_type = Option<String>, format = Date, example = "2026-02-09")]
+    pub paid_date: Option<NaiveDate>,
+    pub movement_id: Option<Uuid>,
+    #[schema(example = "06 - Liquidação")]
+    pub bank_message: Option<String>,
+
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Pagador do boleto (cliente do título), com o endereço do JSON do CRM
+#[derive(Debug, Clone, FromRow)]
+pub struct BoletoPayer {
+    pub name: String,
+    pub document_number: Option<String>,
+    pub street: Option<String>,
+    pub number: Option<String>,
+    pub district: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip_code: Option<String>,
+}
+
+// Arquivo CNAB gerado (remessa) ou importado (retorno)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CnabFile {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub account_id: Uuid,
+    pub kind: CnabFileKind,
+    pub layout: CnabLayout,
+
+    // Nº sequencial da remessa (vazio no retorno)
+    #[schema(example = 12)]
+    pub sequence_number: Option<i32>,
+    #[schema(example = "CB160112.REM")]
+    pub file_name: Option<String>,
+    pub content: String,
+
+    // Remessa: boletos enviados | Retorno: registros de título lidos
+    #[schema(example = 10)]
+    pub record_count: i32,
+    // Boletos liquidados pelo retorno
+    #[schema(example = 4)]
+    pub settled_count: i32,
+
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Um registro de título do arquivo de retorno e o que foi feito com ele
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CnabReturnEntry {
+    #[schema(example = "00000000001")]
+    pub our_number: String,
+    // Código de ocorrência do banco (02 Entrada confirmada, 06 Liquidação...)
+    #[schema(example = "06")]
+    pub occurrence_code: String,
+    #[schema(example = "Liquidação")]
+    pub occurrence: String,
+    #[schema(example = "150.00")]
+    pub paid_amount: Option<Decimal>,
+
+    // Boleto encontrado (vazio = Nosso Número desconhecido)
+    pub boleto: Option<Boleto>,
+    // Baixa gerada pela liquidação
+    pub settlement: Option<TitleSettlement>,
+}
+
+// Resultado do processamento do retorno
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CnabReturnResult {
+    pub file: CnabFile,
+    pub entries: Vec<CnabReturnEntry>,
+}
@@ -52,6 +52,9 @@ pub struct Item {
     pub settings: Option<serde_json::Value>, // JSON Flexível
 
     pub unit_id: Uuid,
+    // Unidades padrão de compra e venda (vazio = unidade base)
+    pub purchase_unit_id: Option<Uuid>,
+    pub sale_unit_id: Option<Uuid>,
     pub category_id: Option<Uuid>,
 
     // Mantemos o preço base (venda) e custo
@@ -90,6 +93,85 @@ pub struct CompositionEntry {
     pub comp_type: CompositionType,
 }
 
+// Onde o item é usado: fichas técnicas que o têm como filho direto
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WhereUsedEntry {
+    pub id: Uuid,
+
+    // Dados do Item Pai (Join)
+    pub parent_item_id: Uuid,
+    #[schema(example = "PROD-BOLO")]
+    pub parent_sku: String,
+    #[schema(example = "Bolo de Cenoura")]
+    pub parent_name: String,
+    pub parent_kind: ItemKind,
+
+    #[schema(example = "0.100")]
+    pub quantity: Decimal,
+    pub comp_type: CompositionType,
+}
+
+// Uma linha da ficha técnica explodida em todos os níveis (apenas COMPONENT).
+// `cumulative_quantity` = quantidade necessária para a quantidade pedida do item raiz.
+// `total_cost` das folhas = quantidade acumulada x custo; dos intermediários = soma dos filhos.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenedBomLine {
+    #[schema(example = 2)]
+    pub level: i32,
+    pub parent_item_id: Uuid,
+
+    pub item_id: Uuid,
+    #[schema(example = "ING-SUGAR")]
+    pub item_sku: String,
+    #[schema(example = "Açúcar Refinado")]
+    pub item_name: String,
+    #[schema(example = "kg")]
+    pub item_unit: String,
+
+    #[schema(example = "0.100")]
+    pub quantity: Decimal,
+    #[schema(example = "1.200")]
+    pub cumulative_quantity: Decimal,
+    #[schema(example = "4.50")]
+    pub unit_cost: Decimal,
+    #[schema(example = "5.40")]
+    pub total_cost: Decimal,
+    pub is_leaf: bool,
+
+    // Caminho desde o item raiz (ordenação e agregação)
+    #[serde(skip)]
+    pub path: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenedBom {
+    pub item_id: Uuid,
+    #[schema(example = "PROD-BOLO")]
+    pub item_sku: String,
+    #[schema(example = "Bolo de Cenoura")]
+    pub item_name: String,
+    #[schema(example = "12.0")]
+    pub quantity: Decimal,
+    // Custo médio deste local (vazio = custo de cadastro)
+    pub location_id: Option<Uuid>,
+
+    #[schema(example = "58.30")]
+    pub total_cost: Decimal,
+    pub lines: Vec<FlattenedBomLine>,
+}
+
+// Escolha feita na venda de um item composto: acessório incluído (sem `replacesItemId`)
+// ou substituto no lugar de um componente
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositionChoice {
+    pub child_item_id: Uuid,
+    pub replaces_item_id: Option<Uuid>,
+}
+
 // --- 2. Categorias ---
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)] // <--- ToSchema
 #[serde(rename_all = "camelCase")]
@@ -149,6 +231,8 @@ pub enum StockMovementReason {
     Correction,
     TransferOut,
     TransferIn,
+    ProductionConsumption, // Componente consumido numa ordem de produção
+    ProductionOutput,      // Lote do item produzido
 }
 
 // --- STOCK MOVEMENT (Histórico) ---
@@ -170,6 +254,7 @@ pub struct StockMovement {
     pub unit_cost: Option<Decimal>,
     pub unit_price: Option<Decimal>,
     pub notes: Option<String>,
+    pub transfer_id: Option<Uuid>, // Preenchido nas movimentações de transferência
     pub created_at: DateTime<Utc>,
 }
 
@@ -190,6 +275,26 @@ pub struct UnitOfMeasure {
     pub updated_at: DateTime<Utc>,
 }
 
+// 1 unidade de origem = `factor` unidades de destino (o inverso é deduzido).
+// Com `item_id`, vale só para o item (ex: caixa com 12).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitConversion {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub item_id: Option<Uuid>,
+    pub from_unit_id: Uuid,
+    pub to_unit_id: Uuid,
+    #[schema(example = "12.0")]
+    pub factor: Decimal,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)] // <--- ToSchema
 #[serde(rename_all = "camelCase")]
 pub struct InventoryBatch {
@@ -210,4 +315,427 @@ pub struct InventoryBatch {
     pub unit_cost: Decimal,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+// --- TRANSFERÊNCIAS ENTRE LOCAIS ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "stock_transfer_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StockTransferStatus {
+    InTransit, // Saiu da origem, ainda não entrou no destino
+    Completed,
+    Cancelled, // Mercadoria em trânsito devolvida à origem
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockTransfer {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub from_location_id: Uuid,
+    pub to_location_id: Uuid,
+    pub status: StockTransferStatus,
+    #[schema(example = "Reposição da loja do centro")]
+    pub notes: Option<String>,
+
+    pub created_by: Option<Uuid>,
+    pub shipped_at: DateTime<Utc>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub received_by: Option<Uuid>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Uma linha por lote/posição que saiu da origem
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockTransferItem {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub transfer_id: Uuid,
+    pub item_id: Uuid,
+    #[schema(example = "LOTE-2023-A")]
+    pub batch_number: String,
+    pub expiration_date: Option<NaiveDate>,
+    #[schema(example = "A1")]
+    pub from_position: String,
+    #[schema(example = "Geral")]
+    pub to_position: String,
+    #[schema(example = "12.0")]
+    pub quantity: Decimal,
+    #[schema(example = "4.50")]
+    pub unit_cost: Decimal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockTransferDetail {
+    #[serde(flatten)]
+    pub transfer: StockTransfer,
+    pub items: Vec<StockTransferItem>,
+}
+
+// O que foi pedido na criação da transferência (antes de resolver os lotes)
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockTransferLine {
+    pub item_id: Uuid,
+    #[schema(example = "12.0")]
+    pub quantity: Decimal,
+    // Sem lote: baixa FIFO (vencimento mais próximo primeiro)
+    #[schema(example = "LOTE-2023-A")]
+    pub batch_number: Option<String>,
+    // Posição de origem do lote informado (padrão: "Geral")
+    #[schema(example = "A1")]
+    pub position: Option<String>,
+    // Posição no destino (padrão: a mesma da origem)
+    #[schema(example = "Geral")]
+    pub to_position: Option<String>,
+}
+
+// --- INVENTÁRIO FÍSICO (CONTAGEM) ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "inventory_count_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InventoryCountStatus {
+    Open,     // Aceitando contagens
+    Approved, // Diferenças lançadas como CORRECTION
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCount {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub location_id: Uuid,
+    pub status: InventoryCountStatus,
+    #[schema(example = "Contagem mensal - corredor A")]
+    pub notes: Option<String>,
+
+    pub created_by: Option<Uuid>,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Uma linha por item/lote/posição. `variance` = contado - esperado (vazio se não contado)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCountLine {
+    pub id: Uuid,
+    pub count_id: Uuid,
+
+    pub item_id: Uuid,
+    #[schema(example = "PROD-001")]
+    pub item_sku: String,
+    #[schema(example = "Coca-Cola 350ml")]
+    pub item_name: String,
+
+    #[schema(example = "LOTE-2023-A")]
+    pub batch_number: String,
+    #[schema(example = "A1")]
+    pub position: String,
+    pub expiration_date: Option<NaiveDate>,
+
+    #[schema(example = "50.0")]
+    pub expected_quantity: Decimal,
+    #[schema(example = "48.0")]
+    pub counted_quantity: Option<Decimal>,
+    #[schema(example = "4.50")]
+    pub unit_cost: Decimal,
+
+    #[schema(example = "-2.0")]
+    pub variance: Option<Decimal>,
+    #[schema(example = "-9.00")]
+    pub variance_value: Option<Decimal>,
+
+    pub counted_by: Option<Uuid>,
+    pub counted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCountDetail {
+    #[serde(flatten)]
+    pub count: InventoryCount,
+    pub lines: Vec<InventoryCountLine>,
+}
+
+// O que a equipe contou num lote/posição
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCountEntry {
+    pub item_id: Uuid,
+    // Padrão: "DEFAULT"
+    #[schema(example = "LOTE-2023-A")]
+    pub batch_number: Option<String>,
+    // Padrão: "Geral"
+    #[schema(example = "A1")]
+    pub position: Option<String>,
+    pub expiration_date: Option<NaiveDate>,
+    #[schema(example = "12.0")]
+    pub quantity: Decimal,
+}
+
+// Resultado financeiro da contagem (diferenças ao custo médio)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCountReport {
+    pub count: InventoryCount,
+
+    pub total_lines: i64,
+    pub counted_lines: i64,
+    pub lines_with_variance: i64,
+
+    #[schema(example = "120.00")]
+    pub surplus_value: Decimal,  // Sobras (contado > esperado)
+    #[schema(example = "-85.50")]
+    pub shortage_value: Decimal, // Faltas (contado < esperado)
+    #[schema(example = "34.50")]
+    pub net_variance_value: Decimal,
+
+    // Apenas as linhas com diferença
+    pub variances: Vec<InventoryCountLine>,
+}
+
+// --- ORDENS DE PRODUÇÃO ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "production_order_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProductionOrderStatus {
+    Planned,   // Componentes reservados na ficha, nada baixado ainda
+    Completed, // Componentes consumidos e lote gerado
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionOrder {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub item_id: Uuid,
+    pub location_id: Uuid,
+    pub status: ProductionOrderStatus,
+
+    #[schema(example = "10.0")]
+    pub planned_quantity: Decimal,
+    #[schema(example = "9.2")]
+    pub produced_quantity: Option<Decimal>,
+    #[schema(example = "92.00")]
+    pub yield_percent: Option<Decimal>,
+    #[schema(example = "8.00")]
+    pub loss_percent: Option<Decimal>,
+
+    #[schema(example = "OP-2026-02-03")]
+    pub batch_number: Option<String>,
+    #[schema(example = "Câmara fria")]
+    pub position: Option<String>,
+    pub expiration_date: Option<NaiveDate>,
+
+    #[schema(example = "87.40")]
+    pub total_cost: Option<Decimal>,
+    #[schema(example = "9.50")]
+    pub unit_cost: Option<Decimal>,
+
+    #[schema(example = "Molho de tomate - produção da manhã")]
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Componente congelado da ficha técnica (quantidade x planejado) e o que foi consumido
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionOrderComponent {
+    pub id: Uuid,
+    pub production_order_id: Uuid,
+
+    pub item_id: Uuid,
+    #[schema(example = "MP-TOMATE")]
+    pub item_sku: String,
+    #[schema(example = "Tomate italiano")]
+    pub item_name: String,
+
+    #[schema(example = "5.0")]
+    pub planned_quantity: Decimal,
+    #[schema(example = "5.4")]
+    pub consumed_quantity: Option<Decimal>,
+    #[schema(example = "32.40")]
+    pub total_cost: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionOrderDetail {
+    #[serde(flatten)]
+    pub order: ProductionOrder,
+    pub components: Vec<ProductionOrderComponent>,
+}
+
+// Consumo real de um componente, quando diferente do planejado (perdas, sobras)
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionConsumption {
+    pub item_id: Uuid,
+    #[schema(example = "5.4")]
+    pub quantity: Decimal,
+}
+
+// --- RESERVAS DE ESTOQUE ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "stock_reservation_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StockReservationStatus {
+    Active,   // Somada em reserved_quantity
+    Consumed, // Baixada na etapa DEDUCT
+    Released, // Pedido cancelado ou liberação manual
+    Expired,  // Prazo vencido
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockReservation {
+    pub id: Uuid,
+
+    #[schema(ignore)]
+    pub tenant_id: Uuid,
+
+    pub order_id: Uuid,
+    pub order_item_id: Uuid,
+    pub item_id: Uuid,
+    pub location_id: Uuid,
+
+    #[schema(example = "2.0")]
+    pub quantity: Decimal,
+    pub status: StockReservationStatus,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Parte de uma linha de pedido atendida por um local (quantidade na unidade base)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FulfilmentAllocation {
+    pub location_id: Uuid,
+    pub quantity: Decimal,
+}
+
+// --- DISPONIBILIDADE POR POOL ---
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationAvailability {
+    pub location_id: Uuid,
+    #[schema(example = "Loja Centro")]
+    pub location_name: String,
+    #[schema(example = "10.0")]
+    pub quantity: Decimal,
+    #[schema(example = "2.0")]
+    pub reserved_quantity: Decimal,
+    #[schema(example = "8.0")]
+    pub available: Decimal, // quantity - reserved_quantity
+}
+
+// Saldo de um item somado nos locais de um pool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolAvailability {
+    pub stock_pool_id: Uuid,
+    #[schema(example = "Estoque Central")]
+    pub stock_pool_name: String,
+    pub item_id: Uuid,
+    #[schema(example = "PROD-ABC-001")]
+    pub item_sku: String,
+    #[schema(example = "Produto Exemplo")]
+    pub item_name: String,
+    #[schema(example = "25.0")]
+    pub quantity: Decimal,
+    #[schema(example = "3.0")]
+    pub reserved_quantity: Decimal,
+    #[schema(example = "22.0")]
+    pub available: Decimal,
+    pub locations: Vec<LocationAvailability>,
+}
+
+// --- HISTÓRICO DE MOVIMENTAÇÕES / KARDEX ---
+
+// Página do histórico de movimentações
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StockMovementPage {
+    pub items: Vec<StockMovement>,
+    #[schema(example = 1)]
+    pub page: i64,
+    #[schema(example = 50)]
+    pub page_size: i64,
+    #[schema(example = 134)]
+    pub total: i64,
+}
+
+// Linha do kardex: a movimentação + saldo e custo médio logo após ela
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KardexLine {
+    #[serde(flatten)]
+    pub movement: StockMovement,
+
+    #[schema(example = "42.0")]
+    pub running_quantity: Decimal,
+    #[schema(example = "4.75")]
+    pub running_average_cost: Decimal,
+    #[schema(example = "199.50")]
+    pub running_value: Decimal,
+}
+
+// Ficha de estoque (kardex) do item no período
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemKardex {
+    pub item_id: Uuid,
+    #[schema(example = "PROD-001")]
+    pub item_sku: String,
+    #[schema(example = "Coca-Cola 350ml")]
+    pub item_name: String,
+    // Vazio = todos os locais somados
+    pub location_id: Option<Uuid>,
+
+    #[schema(value_type = String, format = Date, example = "2026-01-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, format = Date, example = "2026-01-31")]
+    pub end_date: NaiveDate,
+
+    pub opening_quantity: Decimal,
+    pub opening_average_cost: Decimal,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub closing_quantity: Decimal,
+    pub closing_average_cost: Decimal,
+
+    pub lines: Vec<KardexLine>,
+}
@@ -53,6 +53,11 @@ pub struct PipelineStage {
     pub stock_action: Option<String>,
     pub generates_receivable: Option<bool>,
     pub is_locked: Option<bool>,
+    // Condição de pagamento padrão ao gerar o Contas a Receber nesta etapa
+    pub payment_term_id: Option<Uuid>,
+    // Validade (horas) das reservas feitas ao entrar numa etapa RESERVE. Vazio = sem prazo
+    #[schema(example = 48)]
+    pub reservation_hours: Option<i32>,
 }
 
 // --- Structs de Operação ---
@@ -75,6 +80,12 @@ pub struct Order {
     #[schema(example = json!(["urgente", "vip"]))]
     pub tags: Option<Vec<String>>,
     pub notes: Option<String>,
+    // Parcelamento (tem prioridade sobre o da etapa)
+    pub payment_term_id: Option<Uuid>,
+    // Local de atendimento (vazio = primeiro local da loja)
+    pub location_id: Option<Uuid>,
+    #[schema(example = "FIXED")]
+    pub fulfilment_strategy: String, // 'FIXED', 'POOL', 'POOL_SPLIT'
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -99,6 +110,13 @@ pub struct OrderItem {
     pub discount: Decimal,
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
+    // Unidade e quantidade como lançadas. quantity está na unidade base;
+    // unit_price está na unidade lançada (total = unit_quantity * unit_price)
+    pub unit_id: Option<Uuid>,
+    #[schema(example = "1.0")]
+    pub unit_quantity: Option<Decimal>,
+    // Sobrescreve o local de atendimento do pedido
+    pub location_id: Option<Uuid>,
 }
 
 // Como você tinha a OrderDetail no operations.rs antes (conforme conversas anteriores),
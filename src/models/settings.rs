@@ -1,10 +1,39 @@
 // src/models/settings.rs
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use sqlx::FromRow;
 use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+fn validate_non_negative(val: &Decimal) -> Result<(), ValidationError> {
+    if *val < Decimal::ZERO {
+        let mut err = ValidationError::new("range");
+        err.message = Some("A taxa não pode ser negativa.".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_budget_alert_percent(val: &Decimal) -> Result<(), ValidationError> {
+    if *val <= Decimal::ZERO || *val > Decimal::from(999) {
+        let mut err = ValidationError::new("range");
+        err.message = Some("O alerta de orçamento deve ficar entre 0 e 999%.".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_pix_key_type(val: &str) -> Result<(), ValidationError> {
+    if !matches!(val, "CPF" | "CNPJ" | "EMAIL" | "PHONE" | "EVP" | "RANDOM") {
+        let mut err = ValidationError::new("pix_key_type");
+        err.message = Some("Tipo de chave Pix inválido (CPF, CNPJ, EMAIL, PHONE ou EVP).".into());
+        return Err(err);
+    }
+    Ok(())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +56,10 @@ pub struct TenantSettings {
     #[schema(example = "Rua das Flores, 123 - Centro")]
     pub address: Option<String>,
 
+    // Cidade do recebedor (obrigatória no Pix)
+    #[schema(example = "São Paulo")]
+    pub city: Option<String>,
+
     #[schema(example = "(11) 99999-8888")]
     pub phone: Option<String>,
 
@@ -40,10 +73,25 @@ pub struct TenantSettings {
     #[schema(example = "CNPJ", example = "Tipo da chave (CPF, CNPJ, EMAIL, PHONE, EVP)")]
     pub pix_key_type: Option<String>,
 
+    // Cobrança por atraso (em %)
+    #[schema(example = "2.00")]
+    pub late_fee_percent: Decimal,
+
+    #[schema(example = "0.0333")]
+    pub daily_interest_percent: Decimal,
+
+    // Painel sinaliza a categoria de despesa que passar desse % do orçamento do mês
+    #[schema(example = "90.00")]
+    pub budget_alert_percent: Decimal,
+
+    // Venda em um local completa o que faltar com os outros locais do mesmo pool
+    #[schema(example = false)]
+    pub shared_pool_selling: bool,
+
     pub updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSettingsRequest {
     #[schema(example = "Minha Nova Loja")]
@@ -55,6 +103,32 @@ pub struct UpdateSettingsRequest {
     #[schema(example = "chave@pix.com.br")]
     pub pix_key: Option<String>,
 
+    // CPF, CNPJ, EMAIL, PHONE ou EVP (chave aleatória)
+    #[validate(custom(function = "validate_pix_key_type"))]
+    #[schema(example = "EMAIL")]
+    pub pix_key_type: Option<String>,
+
     #[schema(example = "Av. Paulista, 1000")]
     pub address: Option<String>,
+
+    #[validate(length(max = 60))]
+    #[schema(example = "São Paulo")]
+    pub city: Option<String>,
+
+    // Se não informados, mantém as taxas atuais
+    #[validate(custom(function = "validate_non_negative"))]
+    #[schema(example = "2.00")]
+    pub late_fee_percent: Option<Decimal>,
+
+    #[validate(custom(function = "validate_non_negative"))]
+    #[schema(example = "0.0333")]
+    pub daily_interest_percent: Option<Decimal>,
+
+    #[validate(custom(function = "validate_budget_alert_percent"))]
+    #[schema(example = "90.00")]
+    pub budget_alert_percent: Option<Decimal>,
+
+    // Se não informado, mantém o atual
+    #[schema(example = true)]
+    pub shared_pool_selling: Option<bool>,
 }
\ No newline at end of file
@@ -6,4 +6,7 @@ pub mod rbac_service;
 pub mod operation_service;
 pub mod finance_service;
 pub mod dashboard_service;
-pub mod document_service;
\ No newline at end of file
+pub mod document_service;
+pub mod reconciliation_service;
+pub mod billing_service;
+pub mod scheduler;
\ No newline at end of file
@@ -0,0 +1,548 @@
+// src/services/billing_service.rs
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, Postgres, Executor, Acquire};
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{
+    common::{
+        boleto,
+        cnab::{self, RemittanceCompany, RemittanceItem, ReturnOccurrence, ReturnRecord},
+        error::AppError,
+    },
+    db::{BillingRepository, FinanceRepository, SettingsRepository},
+    models::{
+        finance::{
+            BillingConfig, Boleto, BoletoPayer, BoletoStatus, CnabFile, CnabFileKind, CnabLayout,
+            CnabReturnEntry, CnabReturnResult, FinancialTitle, TitleKind, TitleSettlement, TitleStatus
+        },
+        settings::TenantSettings,
+    },
+    services::finance_service::FinanceService,
+};
+
+/// Tudo o que vai impresso no boleto.
+pub struct BoletoDocument {
+    pub boleto: Boleto,
+    pub config: BillingConfig,
+    pub title: FinancialTitle,
+    pub payer: BoletoPayer,
+    pub settings: TenantSettings,
+}
+
+#[derive(Clone)]
+pub struct BillingService {
+    repo: BillingRepository,
+    finance_repo: FinanceRepository,
+    settings_repo: SettingsRepository,
+    finance_service: FinanceService,
+}
+
+impl BillingService {
+    pub fn new(
+        repo: BillingRepository,
+        finance_repo: FinanceRepository,
+        settings_repo: SettingsRepository,
+        finance_service: FinanceService
+    ) -> Self {
+        Self { repo, finance_repo, settings_repo, finance_service }
+    }
+
+    // =========================================================================
+    //  CONVÊNIO DE COBRANÇA
+    // =========================================================================
+
+    pub async fn get_config<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<BillingConfig, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_config(executor, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Convênio de cobrança da conta {}", account_id)))
+    }
+
+    /// Cadastra (ou altera) o convênio da conta. Só grava se o banco conseguir
+    /// montar o código de barras com esses dados.
+    pub async fn upsert_config<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        bank_code: &str,
+        agency: &str,
+        agency_digit: Option<&str>,
+        account_number: &str,
+        account_digit: Option<&str>,
+        wallet: &str,
+        agreement_code: &str,
+        layout: CnabLayout,
+        next_our_number: Option<i64>,
+    ) -> Result<BillingConfig, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        self.finance_repo.get_account(&mut *tx, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+        let config = self.repo.upsert_config(
+            &mut *tx,
+            tenant_id,
+            account_id,
+            bank_code,
+            agency,
+            agency_digit,
+            account_number,
+            account_digit,
+            wallet,
+            agreement_code,
+            layout,
+            next_our_number,
+        ).await?;
+
+        boleto::validate_config(&config)?;
+
+        if config.layout == CnabLayout::Cnab400 && config.bank_code != "237" {
+            return Err(AppError::InvalidBillingConfig("CNAB 400 disponível só para o Bradesco (237)".to_string()));
+        }
+
+        tx.commit().await?;
+
+        Ok(config)
+    }
+
+    // =========================================================================
+    //  BOLETOS
+    // =========================================================================
+
+    /// Emite o boleto do saldo do título na conta informada. O boleto fica
+    /// PENDING até entrar num arquivo de remessa.
+    pub async fn create_boleto<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        account_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<Boleto, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let title = self.finance_repo.get_title(&mut *tx, tenant_id, title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))?;
+
+        if title.kind != TitleKind::Receivable {
+            return Err(Self::validation_error("titleId", "BoletoRequiresReceivable"));
+        }
+
+        if matches!(title.status, TitleStatus::Paid | TitleStatus::Cancelled) {
+            return Err(AppError::TitleNotOpen(title.description));
+        }
+
+        // Banco não registra boleto vencido
+        if title.due_date < Utc::now().date_naive() {
+            return Err(Self::validation_error("dueDate", "BoletoDueDateInPast"));
+        }
+
+        self.get_payer(&mut tx, tenant_id, &title).await?;
+
+        let config = self.get_config(&mut *tx, tenant_id, account_id).await?;
+
+        let sequence = self.repo.take_our_number(&mut *tx, tenant_id, account_id).await?;
+        let our_number = boleto::format_our_number(&config, sequence)?;
+        let barcode = boleto::build_barcode(&config, &our_number, title.amount_balance, title.due_date)?;
+
+        let boleto = self.repo.create_boleto(
+            &mut *tx,
+            tenant_id,
+            title.id,
+            account_id,
+            &our_number,
+            title.amount_balance,
+            title.due_date,
+            &barcode,
+            &boleto::digitable_line(&barcode),
+            user_id,
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(boleto)
+    }
+
+    pub async fn get_boleto<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_id: Uuid,
+    ) -> Result<Boleto, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_boleto(executor, tenant_id, boleto_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Boleto {}", boleto_id)))
+    }
+
+    pub async fn list_boletos<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        title_id: Option<Uuid>,
+        status: Option<BoletoStatus>,
+    ) -> Result<Vec<Boleto>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_boletos(executor, tenant_id, account_id, title_id, status).await
+    }
+
+    /// Dados para imprimir o boleto (PDF).
+    pub async fn get_boleto_document<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_id: Uuid,
+    ) -> Result<BoletoDocument, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let boleto = self.get_boleto(&mut *tx, tenant_id, boleto_id).await?;
+        let config = self.get_config(&mut *tx, tenant_id, boleto.account_id).await?;
+        let title = self.finance_repo.get_title(&mut *tx, tenant_id, boleto.title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", boleto.title_id)))?;
+        let payer = self.get_payer(&mut tx, tenant_id, &title).await?;
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+
+        tx.commit().await?;
+
+        Ok(BoletoDocument { boleto, config, title, payer, settings })
+    }
+
+    // =========================================================================
+    //  REMESSA
+    // =========================================================================
+
+    /// Gera o arquivo de remessa com todos os boletos PENDING da conta e
+    /// marca os boletos como REMITTED.
+    pub async fn generate_remittance<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<CnabFile, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let config = self.get_config(&mut *tx, tenant_id, account_id).await?;
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+
+        let (Some(company_name), Some(company_document)) = (
+            settings.company_name.as_deref().filter(|v| !v.trim().is_empty()),
+            settings.document_number.as_deref().filter(|v| !v.trim().is_empty()),
+        ) else {
+            return Err(Self::validation_error("companyName", "BillingRequiresCompanyDocument"));
+        };
+
+        let boletos = self.repo.list_pending_boletos_for_update(&mut *tx, tenant_id, account_id).await?;
+        if boletos.is_empty() {
+            return Err(Self::validation_error("accountId", "NoBoletosToRemit"));
+        }
+
+        let mut payers = Vec::with_capacity(boletos.len());
+        for boleto in &boletos {
+            let title = self.finance_repo.get_title(&mut *tx, tenant_id, boleto.title_id).await?
+                .ok_or(AppError::ResourceNotFound(format!("Título {}", boleto.title_id)))?;
+            payers.push(self.get_payer(&mut tx, tenant_id, &title).await?);
+        }
+
+        let items: Vec<RemittanceItem> = boletos
+            .iter()
+            .zip(&payers)
+            .map(|(boleto, payer)| RemittanceItem {
+                boleto,
+                payer,
+                late_fee_percent: settings.late_fee_percent,
+                daily_interest_percent: settings.daily_interest_percent,
+            })
+            .collect();
+
+        let sequence = self.repo.take_remittance_number(&mut *tx, tenant_id, account_id).await?;
+        let generated_at = Utc::now().naive_utc();
+        let company = RemittanceCompany { name: company_name, document_number: company_document };
+
+        let content = cnab::build_remittance(&config, &company, sequence, generated_at, &items)?;
+        let file_name = format!("CB{}{:02}.REM", generated_at.format("%d%m"), sequence % 100);
+
+        let file = self.repo.create_cnab_file(
+            &mut *tx,
+            tenant_id,
+            account_id,
+            CnabFileKind::Remittance,
+            config.layout,
+            Some(sequence),
+            Some(&file_name),
+            &content,
+            boletos.len() as i32,
+            user_id,
+        ).await?;
+
+        let ids: Vec<Uuid> = boletos.iter().map(|b| b.id).collect();
+        self.repo.mark_boletos_remitted(&mut *tx, tenant_id, &ids, file.id).await?;
+
+        tx.commit().await?;
+
+        Ok(file)
+    }
+
+    // =========================================================================
+    //  RETORNO
+    // =========================================================================
+
+    /// Processa o arquivo de retorno da conta:
+    /// - Entrada confirmada / rejeitada / baixa: atualiza o status do boleto
+    /// - Liquidação: baixa o título na conta (data do crédito) e marca o boleto como PAID
+    ///
+    /// Reprocessar o mesmo retorno não baixa o título de novo.
+    pub async fn import_return<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        file_name: Option<&str>,
+        content: &str,
+        user_id: Option<Uuid>,
+    ) -> Result<CnabReturnResult, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let config = self.get_config(&mut *tx, tenant_id, account_id).await?;
+
+        let records = cnab::parse_return(&config, content)?;
+        if records.is_empty() {
+            return Err(AppError::InvalidCnabFile("nenhum título encontrado".to_string()));
+        }
+
+        let file = self.repo.create_cnab_file(
+            &mut *tx,
+            tenant_id,
+            account_id,
+            CnabFileKind::Return,
+            config.layout,
+            None,
+            file_name,
+            content,
+            records.len() as i32,
+            user_id,
+        ).await?;
+
+        let mut entries = Vec::with_capacity(records.len());
+        let mut settled = 0;
+
+        for record in &records {
+            let occurrence = cnab::occurrence_description(&record.occurrence_code);
+
+            let Some(boleto) = self.repo
+                .find_boleto_for_update(&mut *tx, tenant_id, account_id, &record.our_number)
+                .await?
+            else {
+                entries.push(CnabReturnEntry {
+                    our_number: record.our_number.clone(),
+                    occurrence_code: record.occurrence_code.clone(),
+                    occurrence,
+                    paid_amount: None,
+                    boleto: None,
+                    settlement: None,
+                });
+                continue;
+            };
+
+            let message = format!("{} - {}", record.occurrence_code, occurrence);
+            let mut settlement = None;
+            let mut paid_amount = None;
+            let awaiting_bank = matches!(boleto.status, BoletoStatus::Pending | BoletoStatus::Remitted);
+
+            let boleto = match cnab::occurrence_kind(&record.occurrence_code) {
+                ReturnOccurrence::Paid if boleto.status != BoletoStatus::Paid => {
+                    let paid_date = record.credit_date
+                        .or(record.occurrence_date)
+                        .unwrap_or_else(|| Utc::now().date_naive());
+
+                    settlement = self.settle_boleto(&mut tx, tenant_id, &boleto, record, paid_date, user_id).await?;
+                    if settlement.is_some() {
+                        settled += 1;
+                    }
+                    paid_amount = Some(record.paid_amount);
+
+                    self.repo.update_boleto_status(
+                        &mut *tx,
+                        tenant_id,
+                        boleto.id,
+                        BoletoStatus::Paid,
+                        &message,
+                        Some(record.paid_amount),
+                        Some(paid_date),
+                        settlement.as_ref().map(|s: &TitleSettlement| s.movement.id),
+                    ).await?
+                }
+                // Mesmo retorno processado de novo
+                ReturnOccurrence::Paid => boleto,
+                ReturnOccurrence::Registered if awaiting_bank => {
+                    self.repo.update_boleto_status(
+                        &mut *tx, tenant_id, boleto.id, BoletoStatus::Registered, &message, None, None, None
+                    ).await?
+                }
+                ReturnOccurrence::Rejected if awaiting_bank => {
+                    self.repo.update_boleto_status(
+                        &mut *tx, tenant_id, boleto.id, BoletoStatus::Rejected, &message, None, None, None
+                    ).await?
+                }
+                ReturnOccurrence::Cancelled if boleto.status != BoletoStatus::Paid => {
+                    self.repo.update_boleto_status(
+                        &mut *tx, tenant_id, boleto.id, BoletoStatus::Cancelled, &message, None, None, None
+                    ).await?
+                }
+                // Demais ocorrências: só guarda a mensagem do banco
+                _ => {
+                    self.repo.update_boleto_status(
+                        &mut *tx, tenant_id, boleto.id, boleto.status, &message, None, None, None
+                    ).await?
+                }
+            };
+
+            entries.push(CnabReturnEntry {
+                our_number: record.our_number.clone(),
+                occurrence_code: record.occurrence_code.clone(),
+                occurrence,
+                paid_amount,
+                boleto: Some(boleto),
+                settlement,
+            });
+        }
+
+        let file = self.repo.update_cnab_settled_count(&mut *tx, tenant_id, file.id, settled).await?;
+
+        tx.commit().await?;
+
+        Ok(CnabReturnResult { file, entries })
+    }
+
+    pub async fn get_cnab_file<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        file_id: Uuid,
+    ) -> Result<CnabFile, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_cnab_file(executor, tenant_id, file_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Arquivo CNAB {}", file_id)))
+    }
+
+    pub async fn list_cnab_files<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        kind: Option<CnabFileKind>,
+    ) -> Result<Vec<CnabFile>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_cnab_files(executor, tenant_id, account_id, kind).await
+    }
+
+    // =========================================================================
+    //  AUXILIARES
+    // =========================================================================
+
+    /// Baixa o título pela liquidação do boleto. O principal é o valor pago sem
+    /// os juros/multa informados pelo banco, limitado ao saldo; a movimentação é o valor pago.
+    /// Título já baixado por fora (ou cancelado): não gera nova baixa.
+    async fn settle_boleto(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        boleto: &Boleto,
+        record: &ReturnRecord,
+        paid_date: chrono::NaiveDate,
+        user_id: Option<Uuid>,
+    ) -> Result<Option<TitleSettlement>, AppError> {
+        let title = self.finance_repo.get_title(&mut *conn, tenant_id, boleto.title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", boleto.title_id)))?;
+
+        if matches!(title.status, TitleStatus::Paid | TitleStatus::Cancelled) {
+            return Ok(None);
+        }
+
+        let principal = (record.paid_amount - record.interest_amount).min(title.amount_balance);
+        if principal <= Decimal::ZERO {
+            return Ok(None);
+        }
+
+        // Juros/multa são os do banco (mais o que passar do saldo): a movimentação
+        // fica com o valor creditado, sem recalcular pelas taxas da loja
+        let charges = record.paid_amount - principal;
+
+        let description = format!("Boleto {}: {}", boleto.our_number, title.description);
+        let settlement = self.finance_service.settle_title_with_charges(
+            &mut *conn,
+            tenant_id,
+            title.id,
+            boleto.account_id,
+            principal,
+            charges,
+            Decimal::ZERO,
+            Some(paid_date),
+            Some(&description),
+            user_id,
+        ).await?;
+
+        Ok(Some(settlement))
+    }
+
+    /// Cliente do título com CPF/CNPJ (o banco não registra boleto sem documento).
+    async fn get_payer(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        title: &FinancialTitle,
+    ) -> Result<BoletoPayer, AppError> {
+        let customer_id = title.customer_id
+            .ok_or_else(|| Self::validation_error("customerId", "BoletoRequiresCustomer"))?;
+
+        let payer = self.repo.get_payer(&mut *conn, tenant_id, customer_id).await?
+            .ok_or_else(|| Self::validation_error("customerId", "BoletoRequiresCustomer"))?;
+
+        let digits = payer.document_number.as_deref().unwrap_or("")
+            .chars().filter(|c| c.is_ascii_digit()).count();
+        if digits != 11 && digits != 14 {
+            return Err(Self::validation_error("customerId", "BoletoRequiresCustomer"));
+        }
+
+        Ok(payer)
+    }
+
+    fn validation_error(field: &'static str, code: &'static str) -> AppError {
+        let mut errors = ValidationErrors::new();
+        errors.add(field, ValidationError::new(code));
+        AppError::ValidationError(errors)
+    }
+}
@@ -1,21 +1,32 @@
 // src/services/dashboard_service.rs
 
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use sqlx::{Postgres, Executor, Acquire};
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 use crate::{
     common::error::AppError,
     db::DashboardRepository,
-    models::dashboard::{DashboardSummary, SalesChartEntry, TopProductEntry},
+    models::dashboard::{
+        BudgetAlert, CashFlowPoint, CashFlowProjection, DashboardSummary, SalesChartEntry, TopProductEntry,
+    },
+    services::finance_service::FinanceService,
 };
 
+/// Limite do horizonte da projeção e do histórico (em dias)
+const MAX_CASH_FLOW_DAYS: i64 = 365;
+
 #[derive(Clone)]
 pub struct DashboardService {
     repo: DashboardRepository,
+    finance_service: FinanceService,
 }
 
 impl DashboardService {
-    pub fn new(repo: DashboardRepository) -> Self {
-        Self { repo }
+    pub fn new(repo: DashboardRepository, finance_service: FinanceService) -> Self {
+        Self { repo, finance_service }
     }
 
     pub async fn get_summary<'e, E>(
@@ -26,7 +37,34 @@ impl DashboardService {
     where
         E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
     {
-        self.repo.get_summary(executor, tenant_id).await
+        let mut tx = executor.begin().await?;
+
+        let mut summary = self.repo.get_summary(&mut *tx, tenant_id).await?;
+
+        // Alertas de orçamento do mês: as mesmas linhas que o relatório marca
+        // (categoria sem orçado herda a soma das subcategorias)
+        let report = self.finance_service.get_budget_report(&mut *tx, tenant_id, None, None).await?;
+        tx.commit().await?;
+
+        let mut alerts: Vec<BudgetAlert> = report.lines.into_iter()
+            .filter(|line| line.over_threshold)
+            .filter_map(|line| {
+                let budget = line.budget?;
+                Some(BudgetAlert {
+                    category_id: line.category_id,
+                    code: line.code,
+                    name: line.name,
+                    budget,
+                    accrued: line.accrued,
+                    percent_used: line.percent_used,
+                })
+            })
+            .collect();
+        // Maior estouro primeiro
+        alerts.sort_by_key(|alert| std::cmp::Reverse(alert.accrued - alert.budget));
+
+        summary.over_budget_categories = alerts;
+        Ok(summary)
     }
 
     pub async fn get_sales_chart<'e, E>(
@@ -50,4 +88,118 @@ impl DashboardService {
     {
         self.repo.get_top_products(executor, tenant_id).await
     }
-}
\ No newline at end of file
+
+    /// Fluxo de caixa diário e semanal: `history_days` dias realizados (saldo
+    /// reconstruído a partir das movimentações) e `days` dias projetados a partir
+    /// do saldo atual e dos títulos em aberto por vencimento.
+    pub async fn get_cash_flow<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        days: i64,
+        history_days: i64,
+    ) -> Result<CashFlowProjection, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut errors = ValidationErrors::new();
+        if !(1..=MAX_CASH_FLOW_DAYS).contains(&days) {
+            errors.add("days", ValidationError::new("InvalidCashFlowHorizon"));
+        }
+        if !(0..=MAX_CASH_FLOW_DAYS).contains(&history_days) {
+            errors.add("historyDays", ValidationError::new("InvalidCashFlowHistory"));
+        }
+        if !errors.is_empty() {
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let today = Utc::now().date_naive();
+        let since = today - Duration::days(history_days);
+        let until = today + Duration::days(days);
+
+        let mut tx = executor.begin().await?;
+        let current_balance = self.repo.get_current_balance(&mut *tx, tenant_id).await?;
+        let realized = self.repo.get_realized_cash_flow(&mut *tx, tenant_id, since).await?;
+        let projected = self.repo.get_projected_cash_flow(&mut *tx, tenant_id, today, until).await?;
+        tx.commit().await?;
+
+        // A. Realizado: volta no tempo a partir do saldo atual. Movimentações com
+        // data futura já estão no saldo das contas, então saem do saldo de hoje.
+        let realized_by_day: HashMap<NaiveDate, _> = realized.iter().map(|r| (r.date, r)).collect();
+        let mut balance = current_balance
+            - realized.iter().filter(|r| r.date > today).map(|r| r.net).sum::<Decimal>();
+
+        let mut daily = Vec::new();
+        let mut date = today;
+        while date >= since {
+            let (inflow, outflow, net) = realized_by_day
+                .get(&date)
+                .map(|r| (r.inflow, r.outflow, r.net))
+                .unwrap_or_default();
+
+            daily.push(CashFlowPoint { date, inflow, outflow, balance, projected: false });
+            balance -= net;
+            date -= Duration::days(1);
+        }
+        daily.reverse();
+
+        // B. Projetado: parte do saldo atual somando o saldo dos títulos por dia
+        let projected_by_day: HashMap<NaiveDate, _> = projected.iter().map(|p| (p.date, p)).collect();
+        let mut balance = current_balance;
+        let mut first_negative_date = None;
+
+        let mut date = today;
+        while date <= until {
+            let (inflow, outflow, net) = projected_by_day
+                .get(&date)
+                .map(|p| (p.inflow, p.outflow, p.net))
+                .unwrap_or_default();
+
+            balance += net;
+            if balance < Decimal::ZERO && first_negative_date.is_none() {
+                first_negative_date = Some(date);
+            }
+
+            daily.push(CashFlowPoint { date, inflow, outflow, balance, projected: true });
+            date += Duration::days(1);
+        }
+
+        let weekly = Self::group_by_week(&daily);
+
+        Ok(CashFlowProjection {
+            today,
+            current_balance,
+            projected_balance: balance,
+            first_negative_date,
+            daily,
+            weekly,
+        })
+    }
+
+    /// Agrupa os pontos diários por semana (segunda a domingo), mantendo as séries
+    /// realizada e projetada separadas. O saldo da semana é o do último dia.
+    fn group_by_week(daily: &[CashFlowPoint]) -> Vec<CashFlowPoint> {
+        let mut weekly: Vec<CashFlowPoint> = Vec::new();
+
+        for point in daily {
+            let week_start = point.date - Duration::days(i64::from(point.date.weekday().num_days_from_monday()));
+
+            match weekly.last_mut() {
+                Some(week) if week.date == week_start && week.projected == point.projected => {
+                    week.inflow += point.inflow;
+                    week.outflow += point.outflow;
+                    week.balance = point.balance;
+                }
+                _ => weekly.push(CashFlowPoint {
+                    date: week_start,
+                    inflow: point.inflow,
+                    outflow: point.outflow,
+                    balance: point.balance,
+                    projected: point.projected,
+                }),
+            }
+        }
+
+        weekly
+    }
+}
@@ -1,27 +1,36 @@
 // src/services/document_service.rs
 
+use chrono::NaiveDate;
 use sqlx::{Postgres, Executor, Acquire};
 use uuid::Uuid;
 use genpdf::{elements, style, Element, Margins};
-use image::Luma;
-use qrcode::QrCode;
+use rust_decimal::Decimal;
 use crate::{
-    common::error::AppError,
+    common::{boleto, error::AppError, pix},
     db::{
         OperationsRepository,
         SettingsRepository
     },
+    models::finance::CashMovementKind,
+    services::{billing_service::BillingService, finance_service::FinanceService},
 };
 
 #[derive(Clone)]
 pub struct DocumentService {
     repo: OperationsRepository,
     settings_repo: SettingsRepository,
+    finance_service: FinanceService,
+    billing_service: BillingService,
 }
 
 impl DocumentService {
-    pub fn new(repo: OperationsRepository, settings_repo: SettingsRepository) -> Self {
-        Self { repo, settings_repo }
+    pub fn new(
+        repo: OperationsRepository,
+        settings_repo: SettingsRepository,
+        finance_service: FinanceService,
+        billing_service: BillingService
+    ) -> Self {
+        Self { repo, settings_repo, finance_service, billing_service }
     }
 
     pub async fn generate_order_pdf<'e, E>(
@@ -58,11 +67,13 @@ impl DocumentService {
             let name_row = sqlx::query!("SELECT name FROM items WHERE id = $1", item.item_id)
                 .fetch_one(&mut *tx).await?;
 
+            // Quantidade na unidade lançada, a mesma do preço
+            let quantity = item.unit_quantity.unwrap_or(item.quantity);
             print_items.push(ItemPrintData {
                 name: name_row.name,
-                quantity: item.quantity,
+                quantity,
                 price: item.unit_price,
-                total: (item.quantity * item.unit_price) - item.discount
+                total: (quantity * item.unit_price) - item.discount
             });
         }
 
@@ -81,11 +92,11 @@ impl DocumentService {
 
         // --- CABEÇALHO DINÂMICO ---
         // Se tiver nome da empresa configurado, usa. Senão, usa o padrão.
-        let title_text = settings.company_name.unwrap_or("MAESTRO ERP".to_string());
+        let title_text = settings.company_name.clone().unwrap_or("MAESTRO ERP".to_string());
         doc.push(elements::Paragraph::new(title_text)
             .styled(style::Style::new().bold().with_font_size(18)));
 
-        if let Some(doc_num) = settings.document_number {
+        if let Some(doc_num) = &settings.document_number {
             doc.push(elements::Paragraph::new(format!("CNPJ/CPF: {}", doc_num))
                 .styled(style::Style::new().with_font_size(10)));
         }
@@ -146,32 +157,36 @@ impl DocumentService {
         doc.push(elements::Break::new(2));
 
         // --- ÁREA DE PAGAMENTO (QR CODE) ---
-        if let Some(key) = settings.pix_key {
+        if let Some(key) = &settings.pix_key {
             doc.push(elements::Paragraph::new("PAGAMENTO VIA PIX")
                 .styled(style::Style::new().bold().with_font_size(12)));
 
             doc.push(elements::Paragraph::new(format!("Chave: {}", key)));
             doc.push(elements::Break::new(1));
 
-            // Gera o QR Code
-            // Obs: Isso gera um QR Code simples da string da chave.
-            // Para "Pix Copia e Cola" oficial (EMV BR Code), precisaríamos de uma lib específica de Payload Pix.
-            // Por enquanto, assumimos que o usuário vai colocar o payload ou apenas a chave (email/cpf).
-            // Se for chave simples, o QR Code será apenas o texto da chave.
-
-            let code = QrCode::new(key.as_bytes())
-                .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
-
-            // Renderiza para imagem
-            let image_buffer = code.render::<Luma<u8>>().build();
-            let dynamic_image = image::DynamicImage::ImageLuma8(image_buffer);
-
-            // Converte para genpdf::Image
-            let pdf_image = genpdf::elements::Image::from_dynamic_image(dynamic_image)
-                .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?
-                .with_scale(genpdf::Scale::new(0.5, 0.5)); // Ajuste o tamanho conforme necessário
-
-            doc.push(pdf_image);
+            // BR Code (Pix Copia e Cola) com o valor do pedido. Sem nome ou cidade
+            // nas configurações o payload seria recusado pelos bancos: fica só a chave.
+            let txid = format!("PED{}", order_detail.header.display_id);
+            match pix::build_pix_payload_from_settings(&settings, Some(order_detail.header.total_amount), &txid) {
+                Ok(payload) => {
+                    let pdf_image = genpdf::elements::Image::from_dynamic_image(pix::render_qr_image(&payload)?)
+                        .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?
+                        .with_scale(genpdf::Scale::new(0.5, 0.5)); // Ajuste o tamanho conforme necessário
+
+                    doc.push(pdf_image);
+                    doc.push(elements::Break::new(1));
+                    doc.push(elements::Paragraph::new("Pix Copia e Cola:")
+                        .styled(style::Style::new().bold().with_font_size(8)));
+                    // O genpdf não quebra palavras longas: o payload vai em linhas fixas
+                    let chars: Vec<char> = payload.chars().collect();
+                    for line in chars.chunks(70) {
+                        doc.push(elements::Paragraph::new(line.iter().collect::<String>())
+                            .styled(style::Style::new().with_font_size(7)));
+                    }
+                }
+                Err(AppError::PixNotConfigured(_)) => {}
+                Err(e) => return Err(e),
+            }
         }
 
         // --- RODAPÉ ---
@@ -188,4 +203,449 @@ impl DocumentService {
 
         Ok(buffer)
     }
-}
\ No newline at end of file
+
+    /// Relatório de fechamento de caixa (Contado x Esperado)
+    pub async fn generate_cash_session_pdf<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<Vec<u8>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        // 1. Busca os Dados
+        let report = self.finance_service.get_cash_session_report(&mut *tx, tenant_id, cash_session_id).await?;
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+
+        tx.commit().await?;
+
+        // 2. Configura o PDF
+        let font_family = genpdf::fonts::from_files("./fonts", "Roboto", None)
+            .map_err(|_| AppError::FontNotFound("Fonte não encontrada na pasta ./fonts".to_string()))?;
+
+        let mut doc = genpdf::Document::new(font_family);
+        doc.set_title("Fechamento de Caixa");
+        let mut decorator = genpdf::SimplePageDecorator::new();
+        decorator.set_margins(10);
+        doc.set_page_decorator(decorator);
+
+        // --- CABEÇALHO ---
+        let title_text = settings.company_name.unwrap_or("MAESTRO ERP".to_string());
+        doc.push(elements::Paragraph::new(title_text)
+            .styled(style::Style::new().bold().with_font_size(18)));
+
+        doc.push(elements::Break::new(1.5));
+
+        doc.push(elements::Paragraph::new("FECHAMENTO DE CAIXA")
+            .styled(style::Style::new().bold().with_font_size(14)));
+
+        doc.push(elements::Paragraph::new(format!("Caixa: {} - {}", report.account_name, report.location_name)));
+        doc.push(elements::Paragraph::new(format!("Abertura: {}", report.session.opened_at.format("%d/%m/%Y %H:%M"))));
+
+        match report.session.closed_at {
+            Some(closed_at) => doc.push(elements::Paragraph::new(format!("Fechamento: {}", closed_at.format("%d/%m/%Y %H:%M")))),
+            None => doc.push(elements::Paragraph::new("Fechamento: (caixa em aberto)")),
+        }
+
+        doc.push(elements::Break::new(2));
+
+        // --- RESUMO ---
+        let style_bold = style::Style::new().bold();
+        let mut summary = elements::TableLayout::new(vec![4, 2]);
+        summary.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+        let lines = [
+            ("Troco inicial", report.session.opening_float),
+            ("Vendas / Recebimentos", report.total_sales),
+            ("Pagamentos", report.total_payments),
+            ("Suprimentos", report.total_deposits),
+            ("Sangrias", report.total_withdrawals),
+        ];
+
+        for (label, value) in lines {
+            summary.row()
+                .element(elements::Paragraph::new(label))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", value)))
+                .push()
+                .expect("Table row error");
+        }
+
+        summary.row()
+            .element(elements::Paragraph::new("ESPERADO").styled(style_bold))
+            .element(elements::Paragraph::new(format!("R$ {:.2}", report.expected_amount)).styled(style_bold))
+            .push()
+            .expect("Table row error");
+
+        if let Some(counted) = report.session.counted_amount {
+            summary.row()
+                .element(elements::Paragraph::new("CONTADO").styled(style_bold))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", counted)).styled(style_bold))
+                .push()
+                .expect("Table row error");
+
+            summary.row()
+                .element(elements::Paragraph::new("DIFERENÇA (Quebra/Sobra)").styled(style_bold))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", report.session.difference.unwrap_or_default())).styled(style_bold))
+                .push()
+                .expect("Table row error");
+        }
+
+        doc.push(summary);
+        doc.push(elements::Break::new(2));
+
+        // --- MOVIMENTAÇÕES ---
+        doc.push(elements::Paragraph::new("MOVIMENTAÇÕES").styled(style::Style::new().bold().with_font_size(12)));
+
+        let mut table = elements::TableLayout::new(vec![2, 2, 4, 2]);
+        table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+        table.row()
+            .element(elements::Paragraph::new("Hora").styled(style_bold))
+            .element(elements::Paragraph::new("Tipo").styled(style_bold))
+            .element(elements::Paragraph::new("Descrição").styled(style_bold))
+            .element(elements::Paragraph::new("Valor").styled(style_bold))
+            .push()
+            .expect("Table error");
+
+        for movement in report.movements {
+            let kind_label = match movement.cash_kind {
+                Some(CashMovementKind::Sale) => "Venda",
+                Some(CashMovementKind::Payment) => "Pagamento",
+                Some(CashMovementKind::Deposit) => "Suprimento",
+                Some(CashMovementKind::Withdrawal) => "Sangria",
+                Some(CashMovementKind::Adjustment) => "Ajuste",
+                None => "-",
+            };
+
+            let time = movement.created_at
+                .map(|d| d.format("%H:%M").to_string())
+                .unwrap_or_default();
+
+            table.row()
+                .element(elements::Paragraph::new(time))
+                .element(elements::Paragraph::new(kind_label))
+                .element(elements::Paragraph::new(movement.description.unwrap_or_default()))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", movement.amount)))
+                .push()
+                .expect("Table row error");
+        }
+
+        doc.push(table);
+
+        if let Some(notes) = report.session.notes {
+            doc.push(elements::Break::new(1));
+            doc.push(elements::Paragraph::new(format!("Observações: {}", notes))
+                .styled(style::Style::new().italic().with_font_size(9)));
+        }
+
+        // 3. Renderiza para Buffer (Memória)
+        let mut buffer = Vec::new();
+        doc.render(&mut buffer)
+            .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
+
+        Ok(buffer)
+    }
+
+    /// Extrato da conta em PDF (saldo anterior, lançamentos com saldo acumulado e saldo final)
+    pub async fn generate_account_statement_pdf<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<u8>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        // 1. Busca os Dados
+        let statement = self.finance_service
+            .get_account_statement(&mut *tx, tenant_id, account_id, start_date, end_date)
+            .await?;
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+
+        tx.commit().await?;
+
+        // 2. Configura o PDF
+        let font_family = genpdf::fonts::from_files("./fonts", "Roboto", None)
+            .map_err(|_| AppError::FontNotFound("Fonte não encontrada na pasta ./fonts".to_string()))?;
+
+        let mut doc = genpdf::Document::new(font_family);
+        doc.set_title(format!("Extrato - {}", statement.account.name));
+        let mut decorator = genpdf::SimplePageDecorator::new();
+        decorator.set_margins(10);
+        doc.set_page_decorator(decorator);
+
+        // --- CABEÇALHO ---
+        let title_text = settings.company_name.unwrap_or("MAESTRO ERP".to_string());
+        doc.push(elements::Paragraph::new(title_text)
+            .styled(style::Style::new().bold().with_font_size(18)));
+
+        doc.push(elements::Break::new(1.5));
+
+        doc.push(elements::Paragraph::new("EXTRATO DA CONTA")
+            .styled(style::Style::new().bold().with_font_size(14)));
+
+        match statement.account.bank_name.as_deref() {
+            Some(bank) => doc.push(elements::Paragraph::new(format!("Conta: {} ({})", statement.account.name, bank))),
+            None => doc.push(elements::Paragraph::new(format!("Conta: {}", statement.account.name))),
+        }
+
+        doc.push(elements::Paragraph::new(format!(
+            "Período: {} a {}",
+            statement.start_date.format("%d/%m/%Y"),
+            statement.end_date.format("%d/%m/%Y")
+        )));
+
+        doc.push(elements::Break::new(2));
+
+        // --- LANÇAMENTOS ---
+        let style_bold = style::Style::new().bold();
+        let mut table = elements::TableLayout::new(vec![2, 5, 2, 2]);
+        table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+        table.row()
+            .element(elements::Paragraph::new("Data").styled(style_bold))
+            .element(elements::Paragraph::new("Descrição").styled(style_bold))
+            .element(elements::Paragraph::new("Valor").styled(style_bold))
+            .element(elements::Paragraph::new("Saldo").styled(style_bold))
+            .push()
+            .expect("Table error");
+
+        table.row()
+            .element(elements::Paragraph::new(statement.start_date.format("%d/%m/%Y").to_string()))
+            .element(elements::Paragraph::new("SALDO ANTERIOR").styled(style_bold))
+            .element(elements::Paragraph::new(""))
+            .element(elements::Paragraph::new(format!("R$ {:.2}", statement.opening_balance)).styled(style_bold))
+            .push()
+            .expect("Table row error");
+
+        for line in statement.lines {
+            table.row()
+                .element(elements::Paragraph::new(line.movement.movement_date.format("%d/%m/%Y").to_string()))
+                .element(elements::Paragraph::new(line.movement.description.unwrap_or_default()))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", line.movement.amount)))
+                .element(elements::Paragraph::new(format!("R$ {:.2}", line.running_balance)))
+                .push()
+                .expect("Table row error");
+        }
+
+        table.row()
+            .element(elements::Paragraph::new(statement.end_date.format("%d/%m/%Y").to_string()))
+            .element(elements::Paragraph::new("SALDO FINAL").styled(style_bold))
+            .element(elements::Paragraph::new(""))
+            .element(elements::Paragraph::new(format!("R$ {:.2}", statement.closing_balance)).styled(style_bold))
+            .push()
+            .expect("Table row error");
+
+        doc.push(table);
+        doc.push(elements::Break::new(1));
+
+        doc.push(elements::Paragraph::new(format!(
+            "Entradas: R$ {:.2}   Saídas: R$ {:.2}",
+            statement.total_in, statement.total_out
+        )).styled(style::Style::new().with_font_size(9)));
+
+        // 3. Renderiza para Buffer (Memória)
+        let mut buffer = Vec::new();
+        doc.render(&mut buffer)
+            .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
+
+        Ok(buffer)
+    }
+
+    /// Extrato da conta em CSV (separador `;`, padrão do Excel em pt-BR)
+    pub async fn generate_account_statement_csv<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<u8>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let statement = self.finance_service
+            .get_account_statement(executor, tenant_id, account_id, start_date, end_date)
+            .await?;
+
+        // Escapa campos de texto (aspas duplas, separador e quebras de linha)
+        let field = |value: &str| -> String {
+            if value.contains([';', '"', '\n', '\r']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        };
+
+        let mut csv = String::from("data;descricao;valor;saldo;titulo_id;transferencia_id;estorno_de\n");
+
+        csv.push_str(&format!(
+            "{};{};;{:.2};;;\n",
+            statement.start_date, field("Saldo anterior"), statement.opening_balance
+        ));
+
+        for line in &statement.lines {
+            let m = &line.movement;
+            csv.push_str(&format!(
+                "{};{};{:.2};{:.2};{};{};{}\n",
+                m.movement_date,
+                field(m.description.as_deref().unwrap_or_default()),
+                m.amount,
+                line.running_balance,
+                m.title_id.map(|id| id.to_string()).unwrap_or_default(),
+                m.transfer_id.map(|id| id.to_string()).unwrap_or_default(),
+                m.reversal_of.map(|id| id.to_string()).unwrap_or_default(),
+            ));
+        }
+
+        csv.push_str(&format!(
+            "{};{};;{:.2};;;\n",
+            statement.end_date, field("Saldo final"), statement.closing_balance
+        ));
+
+        Ok(csv.into_bytes())
+    }
+
+    /// Boleto de cobrança: linha digitável, dados do beneficiário e do pagador
+    /// e o código de barras (Intercalado 2 de 5).
+    pub async fn generate_boleto_pdf<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        boleto_id: Uuid,
+    ) -> Result<Vec<u8>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        // 1. Busca os Dados
+        let data = self.billing_service.get_boleto_document(executor, tenant_id, boleto_id).await?;
+        let (b, config, settings) = (&data.boleto, &data.config, &data.settings);
+
+        // 2. Configura o PDF
+        let font_family = genpdf::fonts::from_files("./fonts", "Roboto", None)
+            .map_err(|_| AppError::FontNotFound("Fonte não encontrada na pasta ./fonts".to_string()))?;
+
+        let mut doc = genpdf::Document::new(font_family);
+        doc.set_title(format!("Boleto {}", b.our_number));
+        let mut decorator = genpdf::SimplePageDecorator::new();
+        decorator.set_margins(10);
+        doc.set_page_decorator(decorator);
+
+        let style_label = style::Style::new().with_font_size(7);
+        let style_bold = style::Style::new().bold();
+
+        // --- CABEÇALHO: Banco | Código | Linha digitável ---
+        doc.push(elements::Paragraph::new(format!(
+            "{}  |  {}  |  {}",
+            boleto::bank_name(&config.bank_code),
+            boleto::bank_code_label(&config.bank_code),
+            b.digitable_line
+        )).styled(style::Style::new().bold().with_font_size(11)));
+
+        doc.push(elements::Break::new(1));
+
+        // --- DADOS DO TÍTULO ---
+        let agency = match &config.agency_digit {
+            Some(digit) => format!("{}-{}", config.agency, digit),
+            None => config.agency.clone(),
+        };
+        let account = match &config.account_digit {
+            Some(digit) => format!("{}-{}", config.account_number, digit),
+            None => config.account_number.clone(),
+        };
+
+        let beneficiary = format!(
+            "{} - CNPJ/CPF: {}",
+            settings.company_name.as_deref().unwrap_or("MAESTRO ERP"),
+            settings.document_number.as_deref().unwrap_or("-")
+        );
+
+        let fields = [
+            ("Local de pagamento", "Pagável em qualquer banco até o vencimento".to_string()),
+            ("Beneficiário", beneficiary),
+            ("Agência / Código do Beneficiário", format!("{} / {}", agency, account)),
+            ("Nosso Número", boleto::our_number_label(config, &b.our_number)?),
+            ("Número do Documento", data.title.description.clone()),
+            ("Data do Documento", b.created_at.format("%d/%m/%Y").to_string()),
+            ("Vencimento", b.due_date.format("%d/%m/%Y").to_string()),
+            ("Valor do Documento", format!("R$ {:.2}", b.amount)),
+        ];
+
+        let mut table = elements::TableLayout::new(vec![2, 5]);
+        table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+        for (label, value) in fields {
+            table.row()
+                .element(elements::Paragraph::new(label).styled(style_label))
+                .element(elements::Paragraph::new(value))
+                .push()
+                .expect("Table row error");
+        }
+
+        doc.push(table);
+        doc.push(elements::Break::new(1));
+
+        // --- INSTRUÇÕES (encargos por atraso das configurações da loja) ---
+        doc.push(elements::Paragraph::new("Instruções").styled(style_bold));
+
+        if settings.late_fee_percent > Decimal::ZERO {
+            doc.push(elements::Paragraph::new(format!(
+                "Após o vencimento, multa de {:.2}%.", settings.late_fee_percent
+            )));
+        }
+        if settings.daily_interest_percent > Decimal::ZERO {
+            let per_day = (b.amount * settings.daily_interest_percent / Decimal::ONE_HUNDRED).round_dp(2);
+            doc.push(elements::Paragraph::new(format!(
+                "Após o vencimento, juros de R$ {:.2} ao dia.", per_day
+            )));
+        }
+        doc.push(elements::Paragraph::new("Não receber após 60 dias do vencimento."));
+
+        doc.push(elements::Break::new(1));
+
+        // --- PAGADOR ---
+        let payer = &data.payer;
+        doc.push(elements::Paragraph::new("Pagador").styled(style_bold));
+        doc.push(elements::Paragraph::new(format!(
+            "{} - CPF/CNPJ: {}",
+            payer.name,
+            payer.document_number.as_deref().unwrap_or("-")
+        )));
+
+        let address: Vec<&str> = [
+            payer.street.as_deref(),
+            payer.number.as_deref(),
+            payer.district.as_deref(),
+            payer.city.as_deref(),
+            payer.state.as_deref(),
+            payer.zip_code.as_deref(),
+        ].into_iter().flatten().collect();
+
+        if !address.is_empty() {
+            doc.push(elements::Paragraph::new(address.join(", ")));
+        }
+
+        doc.push(elements::Break::new(2));
+
+        // --- CÓDIGO DE BARRAS ---
+        let barcode = genpdf::elements::Image::from_dynamic_image(boleto::render_barcode_image(&b.barcode))
+            .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?
+            .with_scale(genpdf::Scale::new(1.5, 1.5)); // ~103mm, a largura do padrão FEBRABAN
+
+        doc.push(barcode);
+
+        // 3. Renderiza para Buffer (Memória)
+        let mut buffer = Vec::new();
+        doc.render(&mut buffer)
+            .map_err(|e| AppError::InternalServerError(anyhow::Error::msg(e.to_string())))?;
+
+        Ok(buffer)
+    }
+}
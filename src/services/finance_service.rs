@@ -1,27 +1,174 @@
 // src/services/finance_service.rs
 
-use chrono::Utc;
-use rust_decimal::Decimal;
-use sqlx::{Postgres, Executor};
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::{PgConnection, Postgres, Executor, Acquire};
 use uuid::Uuid;
+use base64::prelude::{BASE64_STANDARD, Engine as _};
+use validator::{ValidationError, ValidationErrors};
 
 use crate::{
-    common::error::AppError,
-    db::FinanceRepository,
-    models::finance::{FinancialTitle, TitleKind},
+    common::{error::AppError, pix},
+    db::{FinanceRepository, SettingsRepository},
+    models::finance::{
+        AccountStatement, AccountTransfer, CashMovementKind, CashSession, CashSessionReport, CashSessionStatus,
+        BudgetLine, BudgetReport, CategoryBudget, CategoryTree, CategoryTreeNode, DreLine, DreReport,
+        FinancialAccount, FinancialCategory, FinancialMovement, FinancialTitle, PaymentTerm, PixCharge,
+        RecurrenceFrequency, RecurringTitle, StatementLine, TitleKind, TitleSettlement, TitleStatus
+    },
 };
 
+// Plano de contas padrão (criado junto com a loja): (código, nome, tipo).
+// O pai de cada conta é o código sem o último segmento ("2.4.01" -> "2.4").
+// Sem "Compra de Mercadorias": o custo das vendas já entra na DRE como CMV.
+const DEFAULT_CHART_OF_ACCOUNTS: &[(&str, &str, TitleKind)] = &[
+    ("1", "Receitas", TitleKind::Receivable),
+    ("1.1", "Receitas Operacionais", TitleKind::Receivable),
+    ("1.1.01", "Venda de Produtos", TitleKind::Receivable),
+    ("1.1.02", "Prestação de Serviços", TitleKind::Receivable),
+    ("1.2", "Receitas Financeiras", TitleKind::Receivable),
+    ("1.2.01", "Rendimentos de Aplicações", TitleKind::Receivable),
+    ("1.2.02", "Juros e Multas Recebidos", TitleKind::Receivable),
+    ("1.3", "Outras Receitas", TitleKind::Receivable),
+    ("1.3.01", "Receitas Diversas", TitleKind::Receivable),
+    ("2", "Despesas", TitleKind::Payable),
+    ("2.1", "Impostos sobre Vendas", TitleKind::Payable),
+    ("2.1.01", "Simples Nacional (DAS)", TitleKind::Payable),
+    ("2.1.02", "ICMS", TitleKind::Payable),
+    ("2.1.03", "ISS", TitleKind::Payable),
+    ("2.2", "Pessoal", TitleKind::Payable),
+    ("2.2.01", "Salários", TitleKind::Payable),
+    ("2.2.02", "Pró-Labore", TitleKind::Payable),
+    ("2.2.03", "FGTS", TitleKind::Payable),
+    ("2.2.04", "INSS", TitleKind::Payable),
+    ("2.2.05", "Vale-Transporte e Vale-Refeição", TitleKind::Payable),
+    ("2.3", "Ocupação", TitleKind::Payable),
+    ("2.3.01", "Aluguel", TitleKind::Payable),
+    ("2.3.02", "Condomínio", TitleKind::Payable),
+    ("2.3.03", "IPTU", TitleKind::Payable),
+    ("2.3.04", "Energia Elétrica", TitleKind::Payable),
+    ("2.3.05", "Água e Esgoto", TitleKind::Payable),
+    ("2.4", "Despesas Administrativas", TitleKind::Payable),
+    ("2.4.01", "Internet e Telefone", TitleKind::Payable),
+    ("2.4.02", "Contabilidade", TitleKind::Payable),
+    ("2.4.03", "Sistemas e Softwares", TitleKind::Payable),
+    ("2.4.04", "Material de Escritório", TitleKind::Payable),
+    ("2.5", "Despesas Comerciais", TitleKind::Payable),
+    ("2.5.01", "Marketing e Publicidade", TitleKind::Payable),
+    ("2.5.02", "Comissões sobre Vendas", TitleKind::Payable),
+    ("2.5.03", "Taxas de Cartão e Meios de Pagamento", TitleKind::Payable),
+    ("2.6", "Despesas Financeiras", TitleKind::Payable),
+    ("2.6.01", "Tarifas Bancárias", TitleKind::Payable),
+    ("2.6.02", "Juros e Multas Pagos", TitleKind::Payable),
+    ("2.7", "Outras Despesas", TitleKind::Payable),
+    ("2.7.01", "Despesas Diversas", TitleKind::Payable),
+];
+
+// Teto de ocorrências geradas por modelo numa única rodada (evita laço longo
+// ao criar um modelo semanal com início muito no passado)
+const MAX_RECURRING_OCCURRENCES_PER_RUN: i32 = 120;
+
 #[derive(Clone)]
 pub struct FinanceService {
     repo: FinanceRepository,
+    settings_repo: SettingsRepository,
 }
 
 impl FinanceService {
-    pub fn new(repo: FinanceRepository) -> Self {
-        Self { repo }
+    pub fn new(repo: FinanceRepository, settings_repo: SettingsRepository) -> Self {
+        Self { repo, settings_repo }
+    }
+
+    /// Status de um título a partir do saldo em aberto.
+    /// PENDING (nada pago) -> PARTIAL (pago em parte) -> PAID (quitado)
+    /// Com saldo e vencimento no passado, volta a ser OVERDUE.
+    fn status_for_balance(
+        amount_original: Decimal,
+        amount_balance: Decimal,
+        due_date: NaiveDate,
+        today: NaiveDate,
+    ) -> TitleStatus {
+        if amount_balance <= Decimal::ZERO {
+            TitleStatus::Paid
+        } else if due_date < today {
+            TitleStatus::Overdue
+        } else if amount_balance < amount_original {
+            TitleStatus::Partial
+        } else {
+            TitleStatus::Pending
+        }
+    }
+
+    /// Multa (uma vez) + juros simples ao dia sobre o principal pago em atraso.
+    /// Retorna (juros, multa), arredondados em centavos.
+    fn late_charges(
+        principal: Decimal,
+        due_date: NaiveDate,
+        payment_date: NaiveDate,
+        late_fee_percent: Decimal,
+        daily_interest_percent: Decimal,
+    ) -> (Decimal, Decimal) {
+        let days_late = (payment_date - due_date).num_days();
+        if days_late <= 0 {
+            return (Decimal::ZERO, Decimal::ZERO);
+        }
+
+        let hundred = Decimal::ONE_HUNDRED;
+        let fee = (principal * late_fee_percent / hundred).round_dp(2);
+        let interest = (principal * daily_interest_percent / hundred * Decimal::from(days_late)).round_dp(2);
+
+        (interest, fee)
+    }
+
+    /// Sinal da movimentação: RECEIVABLE entra (+), PAYABLE sai (-).
+    fn signed_amount(kind: TitleKind, amount: Decimal) -> Decimal {
+        match kind {
+            TitleKind::Receivable => amount,
+            TitleKind::Payable => -amount,
+        }
+    }
+
+    /// Divide o total conforme a condição de pagamento.
+    /// Retorna (nº da parcela, valor, vencimento). Nº 0 = Entrada.
+    /// O resto do arredondamento (centavos) fica na última parcela.
+    fn plan_installments(
+        total: Decimal,
+        term: &PaymentTerm,
+        today: NaiveDate,
+    ) -> Vec<(i16, Decimal, NaiveDate)> {
+        let mut plan = Vec::new();
+
+        // 1. Entrada (vence na geração)
+        let down_payment = (total * term.down_payment_percent / Decimal::ONE_HUNDRED).round_dp(2);
+        if down_payment > Decimal::ZERO {
+            plan.push((0, down_payment, today));
+        }
+
+        // 2. Parcelas
+        let financed = total - down_payment;
+        let count = term.installments.max(1);
+        let base = (financed / Decimal::from(count))
+            .round_dp_with_strategy(2, RoundingStrategy::ToZero);
+        let first_due = today + Duration::days(i64::from(term.first_due_days));
+
+        for n in 1..=count {
+            let amount = if n == count {
+                financed - base * Decimal::from(count - 1)
+            } else {
+                base
+            };
+            let due_date = first_due + Duration::days(i64::from(term.interval_days) * i64::from(n - 1));
+
+            plan.push((n as i16, amount, due_date));
+        }
+
+        plan
     }
 
-    /// Cria um Título a Receber automaticamente a partir de um pedido finalizado
+    /// Cria o Contas a Receber de um pedido finalizado.
+    /// Sem condição de pagamento: um único título vencendo hoje.
+    /// Com condição de pagamento: N títulos vinculados (entrada + parcelas).
     pub async fn create_receivable_for_order<'e, E>(
         &self,
         executor: E,
@@ -30,30 +177,1853 @@ impl FinanceService {
         display_id: i32,
         amount: Decimal,
         customer_id: Option<Uuid>,
-    ) -> Result<FinancialTitle, AppError>
+        payment_term_id: Option<Uuid>,
+    ) -> Result<Vec<FinancialTitle>, AppError>
     where
-        E: Executor<'e, Database = Postgres>,
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
     {
-        // [CORREÇÃO] Removemos a busca de categorias que causava o erro de "moved value".
-        // Como decidido, por enquanto passamos None no category_id.
-        // Futuramente, passaremos o ID da categoria como argumento.
+        let mut tx = executor.begin().await?;
 
         let description = format!("Venda Pedido #{}", display_id);
-        let due_date = Utc::now().date_naive(); // Vence hoje
+        let today = Utc::now().date_naive();
+
+        // Condição informada precisa existir (não vira "à vista hoje" em silêncio)
+        let term = match payment_term_id {
+            Some(id) => Some(
+                self.repo.get_payment_term(&mut *tx, tenant_id, id).await?
+                    .ok_or(AppError::ResourceNotFound(format!("Condição de pagamento {}", id)))?
+            ),
+            None => None,
+        };
+
+        let titles = match term {
+            // Parcelado
+            Some(term) if term.installments > 1 || term.down_payment_percent > Decimal::ZERO => {
+                let group_id = Uuid::new_v4();
+                let count = term.installments as i16;
+                let mut titles = Vec::new();
+
+                for (number, value, due_date) in Self::plan_installments(amount, &term, today) {
+                    let label = if number == 0 {
+                        format!("{} - Entrada", description)
+                    } else {
+                        format!("{} - Parcela {}/{}", description, number, count)
+                    };
+
+                    let title = self.repo.create_order_installment(
+                        &mut *tx,
+                        tenant_id,
+                        &label,
+                        value,
+                        due_date,
+                        customer_id,
+                        order_id,
+                        group_id,
+                        number,
+                        count
+                    ).await?;
+
+                    titles.push(title);
+                }
+
+                titles
+            }
+
+            // À vista (com prazo, se houver condição de 1x)
+            term => {
+                let due_date = term
+                    .map(|t| today + Duration::days(i64::from(t.first_due_days)))
+                    .unwrap_or(today);
+
+                // Categoria: por enquanto None (Futuro: Buscar "Vendas")
+                let title = self.repo.create_title(
+                    &mut *tx,
+                    tenant_id,
+                    &description,
+                    TitleKind::Receivable,
+                    amount,
+                    due_date,
+                    None,
+                    customer_id,
+                    Some(order_id),
+                    None // Competência = hoje
+                ).await?;
+
+                vec![title]
+            }
+        };
+
+        tx.commit().await?;
+
+        Ok(titles)
+    }
+
+    // =========================================================================
+    //  CONTAS BANCÁRIAS (Caixa)
+    // =========================================================================
+
+    pub async fn create_account<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        name: &str,
+        bank_name: Option<&str>,
+    ) -> Result<FinancialAccount, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.create_account(executor, tenant_id, name, bank_name).await
+    }
+
+    pub async fn list_accounts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Vec<FinancialAccount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_all_accounts(executor, tenant_id).await
+    }
+
+    // =========================================================================
+    //  CATEGORIAS (Plano de Contas)
+    // =========================================================================
+
+    /// A categoria pai precisa existir e ser do mesmo tipo (Receita x Despesa).
+    async fn check_category_parent(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        parent_id: Option<Uuid>,
+        kind: TitleKind,
+    ) -> Result<(), AppError> {
+        let Some(parent_id) = parent_id else { return Ok(()) };
+
+        let parent = self.repo.get_category(&mut *conn, tenant_id, parent_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Categoria {}", parent_id)))?;
+
+        if parent.kind != kind {
+            let mut errors = ValidationErrors::new();
+            errors.add("parentId", ValidationError::new("CategoryParentKindMismatch"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok(())
+    }
+
+    /// A categoria e seus ancestrais, do nó até a raiz.
+    /// O limite de passos protege contra ciclos.
+    fn category_path(by_id: &HashMap<Uuid, &FinancialCategory>, category_id: Uuid) -> Vec<Uuid> {
+        let mut path = vec![category_id];
+        let mut current = by_id.get(&category_id).and_then(|c| c.parent_id);
+
+        while let Some(id) = current {
+            if path.len() > by_id.len() || path.contains(&id) {
+                break;
+            }
+            path.push(id);
+            current = by_id.get(&id).and_then(|c| c.parent_id);
+        }
+
+        path
+    }
+
+    /// Filhos de cada nó, na ordem do repositório (código, nome). Raízes em `None`.
+    fn category_children(categories: &[FinancialCategory]) -> HashMap<Option<Uuid>, Vec<&FinancialCategory>> {
+        let mut children: HashMap<Option<Uuid>, Vec<&FinancialCategory>> = HashMap::new();
+        for category in categories {
+            children.entry(category.parent_id).or_default().push(category);
+        }
+        children
+    }
+
+    /// Percorre a árvore em profundidade: pai antes dos filhos, com o nível de cada nó.
+    fn category_tree_order<'a>(
+        children: &HashMap<Option<Uuid>, Vec<&'a FinancialCategory>>,
+        parent_id: Option<Uuid>,
+        depth: i32,
+        out: &mut Vec<(&'a FinancialCategory, i32)>,
+    ) {
+        for category in children.get(&parent_id).into_iter().flatten() {
+            out.push((category, depth));
+            Self::category_tree_order(children, Some(category.id), depth + 1, out);
+        }
+    }
+
+    fn build_category_nodes(
+        children: &HashMap<Option<Uuid>, Vec<&FinancialCategory>>,
+        parent_id: Option<Uuid>,
+        own_totals: &HashMap<Uuid, Decimal>,
+    ) -> Vec<CategoryTreeNode> {
+        children.get(&parent_id).into_iter().flatten()
+            .map(|category| {
+                let nodes = Self::build_category_nodes(children, Some(category.id), own_totals);
+                let own_total = own_totals.get(&category.id).copied().unwrap_or(Decimal::ZERO);
+
+                CategoryTreeNode {
+                    category: (*category).clone(),
+                    own_total,
+                    total: own_total + nodes.iter().map(|n| n.total).sum::<Decimal>(),
+                    children: nodes,
+                }
+            })
+            .collect()
+    }
+
+    pub async fn create_category<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        code: Option<&str>,
+        name: &str,
+        kind: TitleKind,
+        parent_id: Option<Uuid>,
+    ) -> Result<FinancialCategory, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        self.check_category_parent(&mut tx, tenant_id, parent_id, kind).await?;
+
+        let category = self.repo.create_category(&mut *tx, tenant_id, code, name, kind, parent_id).await?;
+
+        tx.commit().await?;
+        Ok(category)
+    }
+
+    /// Renomeia, recodifica, move na árvore ou (des)ativa a categoria.
+    /// O tipo não muda: os títulos lançados nela continuariam do outro lado da DRE.
+    pub async fn update_category<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        category_id: Uuid,
+        code: Option<&str>,
+        name: &str,
+        parent_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<FinancialCategory, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let category = self.repo.get_category(&mut *tx, tenant_id, category_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Categoria {}", category_id)))?;
+
+        self.check_category_parent(&mut tx, tenant_id, parent_id, category.kind).await?;
+
+        // O novo pai não pode ser a própria categoria nem estar abaixo dela
+        if let Some(parent_id) = parent_id {
+            let categories = self.repo.get_all_categories(&mut *tx, tenant_id).await?;
+            let by_id: HashMap<Uuid, &FinancialCategory> = categories.iter().map(|c| (c.id, c)).collect();
+
+            if Self::category_path(&by_id, parent_id).contains(&category_id) {
+                let mut errors = ValidationErrors::new();
+                errors.add("parentId", ValidationError::new("CategoryParentCycle"));
+                return Err(AppError::ValidationError(errors));
+            }
+        }
+
+        let category = self.repo.update_category(
+            &mut *tx,
+            tenant_id,
+            category_id,
+            code,
+            name,
+            parent_id,
+            is_active.unwrap_or(category.is_active.unwrap_or(true)),
+        ).await?;
+
+        tx.commit().await?;
+        Ok(category)
+    }
+
+    pub async fn list_categories<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Vec<FinancialCategory>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_all_categories(executor, tenant_id).await
+    }
+
+    /// Árvore do plano de contas com o total de cada nó no período (títulos não
+    /// cancelados por competência). O total do nó soma as subcategorias.
+    /// Sem datas, assume de 1º de janeiro do ano corrente até hoje.
+    pub async fn get_category_tree<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<CategoryTree, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let end_date = end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let start_date = start_date
+            .unwrap_or_else(|| NaiveDate::from_ymd_opt(end_date.year(), 1, 1).unwrap_or(end_date));
+
+        if start_date > end_date {
+            let mut errors = ValidationErrors::new();
+            errors.add("startDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+        let categories = self.repo.get_all_categories(&mut *tx, tenant_id).await?;
+        let totals = self.repo.get_dre_category_totals(&mut *tx, tenant_id, start_date, end_date).await?;
+        tx.commit().await?;
+
+        let mut own_totals: HashMap<Uuid, Decimal> = HashMap::new();
+        for row in totals {
+            if let Some(category_id) = row.category_id {
+                *own_totals.entry(category_id).or_default() += row.total;
+            }
+        }
+
+        let children = Self::category_children(&categories);
+
+        Ok(CategoryTree {
+            start_date,
+            end_date,
+            roots: Self::build_category_nodes(&children, None, &own_totals),
+        })
+    }
+
+    /// Cria o plano de contas padrão de uma loja nova.
+    pub async fn seed_default_categories<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+        let mut ids: HashMap<&str, Uuid> = HashMap::new();
+
+        for (code, name, kind) in DEFAULT_CHART_OF_ACCOUNTS {
+            let parent_id = code.rsplit_once('.').and_then(|(parent, _)| ids.get(parent).copied());
+
+            let category = self.repo.create_category(&mut *tx, tenant_id, Some(code), name, *kind, parent_id).await?;
+            ids.insert(code, category.id);
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    //  TÍTULOS MANUAIS (Aluguel, Fornecedores, etc.)
+    // =========================================================================
+
+    /// Lança um título a pagar/receber sem passar por um pedido.
+    /// Se houver categoria, ela precisa existir e ser do mesmo tipo (Receita x Despesa).
+    pub async fn create_manual_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        description: &str,
+        kind: TitleKind,
+        amount: Decimal,
+        due_date: NaiveDate,
+        competence_date: Option<NaiveDate>,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+    ) -> Result<FinancialTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        self.check_category_kind(&mut tx, tenant_id, category_id, kind).await?;
 
-        // Agora o 'executor' é usado apenas uma vez aqui, resolvendo o erro E0382
         let title = self.repo.create_title(
-            executor,
+            &mut *tx,
             tenant_id,
-            &description,
-            TitleKind::Receivable,
+            description,
+            kind,
             amount,
             due_date,
-            None, // category_id (Futuro: Buscar "Vendas")
+            category_id,
             customer_id,
-            Some(order_id)
+            None, // Título manual não tem pedido
+            competence_date
         ).await?;
 
+        tx.commit().await?;
         Ok(title)
     }
-}
\ No newline at end of file
+
+    /// A categoria, se houver, precisa existir e ser do mesmo tipo do título.
+    async fn check_category_kind(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        category_id: Option<Uuid>,
+        kind: TitleKind,
+    ) -> Result<(), AppError> {
+        let Some(cat_id) = category_id else { return Ok(()) };
+
+        let category = self.repo.get_category(&mut *conn, tenant_id, cat_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Categoria {}", cat_id)))?;
+
+        if category.kind != kind {
+            let mut errors = ValidationErrors::new();
+            errors.add("categoryId", ValidationError::new("CategoryKindMismatch"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<FinancialTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.get_title(executor, tenant_id, title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))
+    }
+
+    /// Cobrança Pix do título a receber: saldo + juros e multa até hoje.
+    /// O txid vem do ID do título, então o mesmo título gera sempre o mesmo txid.
+    pub async fn get_title_pix<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<PixCharge, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let title = self.repo.get_title(&mut *tx, tenant_id, title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))?;
+
+        if title.kind != TitleKind::Receivable {
+            let mut errors = ValidationErrors::new();
+            errors.add("kind", ValidationError::new("PixRequiresReceivable"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        if matches!(title.status, TitleStatus::Paid | TitleStatus::Cancelled) {
+            return Err(AppError::TitleNotOpen(title.description));
+        }
+
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+        tx.commit().await?;
+
+        let (interest, fee) = Self::late_charges(
+            title.amount_balance,
+            title.due_date,
+            Utc::now().date_naive(),
+            settings.late_fee_percent,
+            settings.daily_interest_percent,
+        );
+        let amount = title.amount_balance + interest + fee;
+
+        let txid: String = title.id.simple().to_string().to_uppercase().chars().take(25).collect();
+        let payload = pix::build_pix_payload_from_settings(&settings, Some(amount), &txid)?;
+        let png = pix::render_qr_png(&payload)?;
+
+        Ok(PixCharge {
+            title_id: title.id,
+            txid,
+            amount,
+            payload,
+            qr_code_png: BASE64_STANDARD.encode(png),
+        })
+    }
+
+    pub async fn list_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        kind: Option<TitleKind>,
+        status: Option<TitleStatus>,
+        due_from: Option<NaiveDate>,
+        due_to: Option<NaiveDate>,
+        recurring_title_id: Option<Uuid>,
+    ) -> Result<Vec<FinancialTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_titles(executor, tenant_id, kind, status, due_from, due_to, recurring_title_id).await
+    }
+
+    // =========================================================================
+    //  CONDIÇÕES DE PAGAMENTO
+    // =========================================================================
+
+    pub async fn create_payment_term<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        name: &str,
+        installments: i32,
+        interval_days: i32,
+        first_due_days: i32,
+        down_payment_percent: Decimal,
+    ) -> Result<PaymentTerm, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.create_payment_term(
+            executor, tenant_id, name, installments, interval_days, first_due_days, down_payment_percent
+        ).await
+    }
+
+    pub async fn list_payment_terms<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<Vec<PaymentTerm>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_payment_terms(executor, tenant_id).await
+    }
+
+    // =========================================================================
+    //  TÍTULOS RECORRENTES (Aluguel, Salários, Mensalidades)
+    // =========================================================================
+
+    /// Vencimento da ocorrência `n` (0 = data de início). Sempre calculado a partir
+    /// do início, para o dia 31 não "escorregar" depois de um fevereiro.
+    /// None se passar da data final.
+    fn recurrence_due_date(recurring: &RecurringTitle, n: i32) -> Option<NaiveDate> {
+        let steps = recurring.interval_count.checked_mul(n)?;
+
+        let due_date = match recurring.frequency {
+            RecurrenceFrequency::Weekly => recurring.start_date
+                .checked_add_signed(Duration::weeks(i64::from(steps))),
+            RecurrenceFrequency::Monthly => recurring.start_date
+                .checked_add_months(Months::new(u32::try_from(steps).ok()?)),
+            RecurrenceFrequency::Yearly => recurring.start_date
+                .checked_add_months(Months::new(u32::try_from(steps.checked_mul(12)?).ok()?)),
+        }?;
+
+        match recurring.end_date {
+            Some(end_date) if due_date > end_date => None,
+            _ => Some(due_date),
+        }
+    }
+
+    /// Gera os títulos do modelo que vencem até hoje + antecedência e avança o
+    /// próximo vencimento. Vencimento já gerado (ex: outra rodada) só avança.
+    /// Retorna o modelo atualizado e quantos títulos foram criados.
+    async fn generate_recurring_occurrences(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        recurring: RecurringTitle,
+        today: NaiveDate,
+    ) -> Result<(RecurringTitle, u64), AppError> {
+        if !recurring.is_active {
+            return Ok((recurring, 0));
+        }
+
+        let horizon = today + Duration::days(i64::from(recurring.lead_days));
+        let mut generated_count = recurring.generated_count;
+        let mut next_due_date = recurring.next_due_date;
+        let mut created = 0;
+
+        while let Some(due_date) = next_due_date {
+            if due_date > horizon
+                || generated_count - recurring.generated_count >= MAX_RECURRING_OCCURRENCES_PER_RUN
+            {
+                break;
+            }
+
+            if self.repo.create_recurring_occurrence(&mut *conn, tenant_id, &recurring, due_date).await?.is_some() {
+                created += 1;
+            }
+
+            generated_count += 1;
+            next_due_date = Self::recurrence_due_date(&recurring, generated_count);
+        }
+
+        if generated_count == recurring.generated_count {
+            return Ok((recurring, 0));
+        }
+
+        let recurring = self.repo.update_recurring_progress(
+            &mut *conn, tenant_id, recurring.id, generated_count, next_due_date
+        ).await?;
+
+        Ok((recurring, created))
+    }
+
+    /// Cria o modelo e já gera as ocorrências dentro da antecedência.
+    pub async fn create_recurring_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        description: &str,
+        kind: TitleKind,
+        amount: Decimal,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+        frequency: RecurrenceFrequency,
+        interval_count: i32,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+        lead_days: i32,
+        user_id: Uuid,
+    ) -> Result<RecurringTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        if end_date.is_some_and(|end| end < start_date) {
+            let mut errors = ValidationErrors::new();
+            errors.add("endDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        self.check_category_kind(&mut tx, tenant_id, category_id, kind).await?;
+
+        let recurring = self.repo.create_recurring_title(
+            &mut *tx,
+            tenant_id,
+            description,
+            kind,
+            amount,
+            category_id,
+            customer_id,
+            frequency,
+            interval_count,
+            start_date,
+            end_date,
+            lead_days,
+            user_id,
+        ).await?;
+
+        let (recurring, _) = self.generate_recurring_occurrences(
+            &mut tx, tenant_id, recurring, Utc::now().date_naive()
+        ).await?;
+
+        tx.commit().await?;
+        Ok(recurring)
+    }
+
+    pub async fn list_recurring_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        kind: Option<TitleKind>,
+    ) -> Result<Vec<RecurringTitle>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_recurring_titles(executor, tenant_id, kind).await
+    }
+
+    /// Edita o modelo. Vale para as ocorrências ainda não geradas; os títulos já
+    /// gerados são independentes (edite ou cancele cada um).
+    /// A regra (frequência, intervalo, início) não muda: para isso, encerre e crie outro.
+    pub async fn update_recurring_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        recurring_id: Uuid,
+        description: &str,
+        amount: Decimal,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+        end_date: Option<NaiveDate>,
+        lead_days: Option<i32>,
+        is_active: Option<bool>,
+    ) -> Result<RecurringTitle, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let mut recurring = self.repo.get_recurring_title_for_update(&mut *tx, tenant_id, recurring_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Recorrência {}", recurring_id)))?;
+
+        if end_date.is_some_and(|end| end < recurring.start_date) {
+            let mut errors = ValidationErrors::new();
+            errors.add("endDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        self.check_category_kind(&mut tx, tenant_id, category_id, recurring.kind).await?;
+
+        // A data final pode antecipar (ou reabrir) o próximo vencimento
+        recurring.end_date = end_date;
+        let next_due_date = Self::recurrence_due_date(&recurring, recurring.generated_count);
+
+        let recurring = self.repo.update_recurring_title(
+            &mut *tx,
+            tenant_id,
+            recurring_id,
+            description,
+            amount,
+            category_id,
+            customer_id,
+            end_date,
+            lead_days.unwrap_or(recurring.lead_days),
+            is_active.unwrap_or(recurring.is_active),
+            next_due_date,
+        ).await?;
+
+        // Uma antecedência maior pode liberar ocorrências agora
+        let (recurring, _) = self.generate_recurring_occurrences(
+            &mut tx, tenant_id, recurring, Utc::now().date_naive()
+        ).await?;
+
+        tx.commit().await?;
+        Ok(recurring)
+    }
+
+    /// Rotina em segundo plano: gera as ocorrências de todos os modelos da loja
+    /// que entraram na antecedência. Retorna quantos títulos foram criados.
+    pub async fn generate_recurring_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let today = Utc::now().date_naive();
+        let mut tx = executor.begin().await?;
+
+        let due = self.repo.list_due_recurring_titles(&mut *tx, tenant_id, today).await?;
+
+        let mut created = 0;
+        for recurring in due {
+            let (_, count) = self.generate_recurring_occurrences(&mut tx, tenant_id, recurring, today).await?;
+            created += count;
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    // =========================================================================
+    //  BAIXAS (Pagamentos / Recebimentos) & ESTORNOS
+    // =========================================================================
+
+    /// Baixa (total ou parcial) de um título em uma conta.
+    /// Numa única transação: gera a movimentação, reduz o saldo do título,
+    /// atualiza o status e o saldo da conta.
+    /// Se `amount` não for informado, quita o saldo em aberto.
+    /// Pago após o vencimento, soma multa e juros (taxas da loja) ao valor movimentado;
+    /// `amount` continua sendo o principal que abate o saldo do título.
+    pub async fn settle_title<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        account_id: Uuid,
+        amount: Option<Decimal>,
+        movement_date: Option<NaiveDate>,
+        description: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<TitleSettlement, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let settlement = self.settle_title_in(
+            &mut tx, tenant_id, title_id, account_id, amount, None,
+            movement_date, description, user_id
+        ).await?;
+
+        tx.commit().await?;
+        Ok(settlement)
+    }
+
+    /// Baixa com os valores efetivamente recebidos/pagos (retorno do banco, extrato):
+    /// `amount` abate o saldo e `interest`/`fee` entram como informados, sem recalcular
+    /// pelas taxas da loja. A movimentação fica com `amount + interest + fee`.
+    pub async fn settle_title_with_charges<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        account_id: Uuid,
+        amount: Decimal,
+        interest: Decimal,
+        fee: Decimal,
+        movement_date: Option<NaiveDate>,
+        description: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<TitleSettlement, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        if interest < Decimal::ZERO || fee < Decimal::ZERO {
+            let mut errors = ValidationErrors::new();
+            errors.add("amount", ValidationError::new("SettlementChargesNegative"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let settlement = self.settle_title_in(
+            &mut tx, tenant_id, title_id, account_id, Some(amount), Some((interest, fee)),
+            movement_date, description, user_id
+        ).await?;
+
+        tx.commit().await?;
+        Ok(settlement)
+    }
+
+    // Sem `charges` (juros, multa), calcula pelas taxas da loja
+    async fn settle_title_in(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        title_id: Uuid,
+        account_id: Uuid,
+        amount: Option<Decimal>,
+        charges: Option<(Decimal, Decimal)>,
+        movement_date: Option<NaiveDate>,
+        description: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<TitleSettlement, AppError> {
+        // 1. Trava o título (evita duas baixas simultâneas no mesmo saldo)
+        let title = self.repo.get_title_for_update(&mut *conn, tenant_id, title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))?;
+
+        if matches!(title.status, TitleStatus::Paid | TitleStatus::Cancelled) {
+            return Err(AppError::TitleNotOpen(title.description));
+        }
+
+        let amount = amount.unwrap_or(title.amount_balance);
+        if amount > title.amount_balance {
+            return Err(AppError::AmountExceedsBalance(format!("{:.2}", title.amount_balance)));
+        }
+
+        // 2. Trava a conta
+        let account = self.repo.get_account_for_update(&mut *conn, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+        if !account.is_active.unwrap_or(true) {
+            return Err(AppError::AccountInactive(account.name));
+        }
+
+        // 3. Juros e multa por atraso
+        let movement_date = movement_date.unwrap_or_else(|| Utc::now().date_naive());
+        let (interest, fee) = match charges {
+            Some(charges) => charges,
+            None => {
+                let settings = self.settings_repo.get_settings(&mut *conn, tenant_id).await?;
+                Self::late_charges(
+                    amount,
+                    title.due_date,
+                    movement_date,
+                    settings.late_fee_percent,
+                    settings.daily_interest_percent,
+                )
+            }
+        };
+
+        // 4. Movimentação (o dinheiro de verdade)
+        let signed = Self::signed_amount(title.kind, amount + interest + fee);
+        let description = description
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Baixa: {}", title.description));
+
+        let mut movement = self.repo.create_movement(
+            &mut *conn,
+            tenant_id,
+            account_id,
+            Some(title_id),
+            None,
+            signed,
+            movement_date,
+            interest,
+            fee,
+            Some(&description),
+            user_id,
+            None
+        ).await?;
+
+        // Se a conta é uma gaveta com caixa aberto, a baixa entra no caixa
+        if let Some(session) = self.repo.get_open_cash_session_for_account(&mut *conn, tenant_id, account_id).await? {
+            let cash_kind = match title.kind {
+                TitleKind::Receivable => CashMovementKind::Sale,
+                TitleKind::Payable => CashMovementKind::Payment,
+            };
+            movement = self.repo
+                .attach_movement_to_cash_session(&mut *conn, tenant_id, movement.id, session.id, cash_kind)
+                .await?;
+        }
+
+        // 5. Título: saldo e status
+        let new_balance = title.amount_balance - amount;
+        let new_status = Self::status_for_balance(
+            title.amount_original,
+            new_balance,
+            title.due_date,
+            Utc::now().date_naive(),
+        );
+
+        let updated_title = self.repo
+            .update_title_balance(&mut *conn, tenant_id, title_id, new_balance, new_status)
+            .await?;
+
+        // 6. Conta: cache de saldo
+        let updated_account = self.repo
+            .update_account_balance(&mut *conn, tenant_id, account_id, signed)
+            .await?;
+
+        Ok(TitleSettlement {
+            title: Some(updated_title),
+            movement,
+            account: updated_account,
+        })
+    }
+
+    /// Estorna uma movimentação. Nada é apagado: gera uma movimentação inversa
+    /// apontando para a original, marca a original como estornada,
+    /// devolve o saldo ao título (se houver) e desfaz o efeito na conta.
+    /// Numa transferência, as duas pernas são estornadas juntas.
+    pub async fn reverse_movement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        movement_id: Uuid,
+        reason: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<TitleSettlement, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        // 1. Trava a movimentação original
+        let original = self.repo.get_movement_for_update(&mut *tx, tenant_id, movement_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Movimentação {}", movement_id)))?;
+
+        if original.reversed_at.is_some() {
+            return Err(AppError::MovementAlreadyReversed(movement_id.to_string()));
+        }
+
+        // Estorno de estorno não é permitido (lance uma nova baixa)
+        if original.reversal_of.is_some() {
+            let mut errors = ValidationErrors::new();
+            errors.add("movementId", ValidationError::new("CannotReverseReversal"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        // 2. Título (se a movimentação veio de uma baixa)
+        let mut updated_title = None;
+        if let Some(title_id) = original.title_id {
+            let title = self.repo.get_title_for_update(&mut *tx, tenant_id, title_id).await?
+                .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))?;
+
+            // Só o principal volta para o saldo (juros/multa não fazem parte do título)
+            let principal = original.amount.abs() - original.interest_amount - original.fee_amount;
+            let new_balance = (title.amount_balance + principal).min(title.amount_original);
+            let new_status = Self::status_for_balance(
+                title.amount_original,
+                new_balance,
+                title.due_date,
+                Utc::now().date_naive(),
+            );
+
+            updated_title = Some(
+                self.repo
+                    .update_title_balance(&mut *tx, tenant_id, title_id, new_balance, new_status)
+                    .await?
+            );
+        }
+
+        // 3. Movimentação inversa + saldo da conta
+        let (reversal, updated_account) =
+            self.reverse_leg(&mut tx, tenant_id, &original, reason, user_id).await?;
+
+        // 4. Transferência: a outra perna é estornada junto (o par continua somando zero)
+        if let Some(transfer_id) = original.transfer_id {
+            let legs = self.repo.list_transfer_movements(&mut *tx, tenant_id, transfer_id).await?;
+
+            for leg in legs.iter().filter(|m| m.id != original.id && m.reversal_of.is_none()) {
+                let leg = self.repo.get_movement_for_update(&mut *tx, tenant_id, leg.id).await?
+                    .ok_or(AppError::ResourceNotFound(format!("Movimentação {}", leg.id)))?;
+
+                if leg.reversed_at.is_none() {
+                    self.reverse_leg(&mut tx, tenant_id, &leg, reason, user_id).await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(TitleSettlement {
+            title: updated_title,
+            movement: reversal,
+            account: updated_account,
+        })
+    }
+
+    /// Gera a movimentação inversa de `original`, marca a original como estornada
+    /// e desfaz o efeito no saldo da conta.
+    async fn reverse_leg(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        original: &FinancialMovement,
+        reason: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<(FinancialMovement, FinancialAccount), AppError> {
+        let description = format!(
+            "Estorno: {}",
+            original.description.as_deref().unwrap_or("Movimentação")
+        );
+
+        let mut reversal = self.repo.create_movement(
+            &mut *conn,
+            tenant_id,
+            original.account_id,
+            original.title_id,
+            original.transfer_id,
+            -original.amount,
+            Utc::now().date_naive(),
+            -original.interest_amount,
+            -original.fee_amount,
+            Some(&description),
+            user_id,
+            Some(original.id)
+        ).await?;
+
+        // Estorno de movimentação de caixa: entra no mesmo caixa, se ainda estiver aberto
+        if let (Some(session_id), Some(cash_kind)) = (original.cash_session_id, original.cash_kind) {
+            let session = self.repo.get_cash_session(&mut *conn, tenant_id, session_id).await?;
+            if session.is_some_and(|s| s.status == CashSessionStatus::Open) {
+                reversal = self.repo
+                    .attach_movement_to_cash_session(&mut *conn, tenant_id, reversal.id, session_id, cash_kind)
+                    .await?;
+            }
+        }
+
+        self.repo.mark_movement_reversed(&mut *conn, tenant_id, original.id, reason).await?;
+
+        let account = self.repo
+            .update_account_balance(&mut *conn, tenant_id, original.account_id, -original.amount)
+            .await?;
+
+        Ok((reversal, account))
+    }
+
+    pub async fn list_title_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        title_id: Uuid,
+    ) -> Result<Vec<FinancialMovement>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_title_movements(executor, tenant_id, title_id).await
+    }
+
+    // =========================================================================
+    //  TRANSFERÊNCIAS ENTRE CONTAS
+    // =========================================================================
+
+    /// Transfere dinheiro entre duas contas da loja (ex: Gaveta -> Banco).
+    /// Numa única transação: saída na origem, entrada no destino (mesmo transfer_id)
+    /// e os dois saldos atualizados. Sem título: não é receita nem despesa.
+    /// Se alguma das contas for uma gaveta com caixa aberto, a perna entra no caixa
+    /// como Sangria (saída) ou Suprimento (entrada).
+    pub async fn transfer_between_accounts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: Decimal,
+        movement_date: Option<NaiveDate>,
+        description: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<AccountTransfer, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        if from_account_id == to_account_id {
+            let mut errors = ValidationErrors::new();
+            errors.add("toAccountId", ValidationError::new("SameAccountTransfer"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        // 1. Trava as duas contas sempre na mesma ordem (evita deadlock entre transferências opostas)
+        let (first_id, second_id) = if from_account_id < to_account_id {
+            (from_account_id, to_account_id)
+        } else {
+            (to_account_id, from_account_id)
+        };
+
+        for account_id in [first_id, second_id] {
+            let account = self.repo.get_account_for_update(&mut *tx, tenant_id, account_id).await?
+                .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+            if !account.is_active.unwrap_or(true) {
+                return Err(AppError::AccountInactive(account.name));
+            }
+        }
+
+        // 2. O par de movimentações
+        let transfer_id = Uuid::new_v4();
+        let movement_date = movement_date.unwrap_or_else(|| Utc::now().date_naive());
+        let description = description.unwrap_or("Transferência entre contas");
+
+        let mut outgoing = self.repo.create_movement(
+            &mut *tx,
+            tenant_id,
+            from_account_id,
+            None,
+            Some(transfer_id),
+            -amount,
+            movement_date,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Some(description),
+            user_id,
+            None
+        ).await?;
+
+        let mut incoming = self.repo.create_movement(
+            &mut *tx,
+            tenant_id,
+            to_account_id,
+            None,
+            Some(transfer_id),
+            amount,
+            movement_date,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Some(description),
+            user_id,
+            None
+        ).await?;
+
+        // 3. Gavetas com caixa aberto
+        if let Some(session) = self.repo.get_open_cash_session_for_account(&mut *tx, tenant_id, from_account_id).await? {
+            outgoing = self.repo
+                .attach_movement_to_cash_session(&mut *tx, tenant_id, outgoing.id, session.id, CashMovementKind::Withdrawal)
+                .await?;
+        }
+        if let Some(session) = self.repo.get_open_cash_session_for_account(&mut *tx, tenant_id, to_account_id).await? {
+            incoming = self.repo
+                .attach_movement_to_cash_session(&mut *tx, tenant_id, incoming.id, session.id, CashMovementKind::Deposit)
+                .await?;
+        }
+
+        // 4. Saldos
+        let from_account = self.repo
+            .update_account_balance(&mut *tx, tenant_id, from_account_id, -amount)
+            .await?;
+        let to_account = self.repo
+            .update_account_balance(&mut *tx, tenant_id, to_account_id, amount)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(AccountTransfer {
+            transfer_id,
+            outgoing,
+            incoming,
+            from_account,
+            to_account,
+        })
+    }
+
+    // =========================================================================
+    //  EXTRATO DA CONTA
+    // =========================================================================
+
+    /// Extrato da conta no período: saldo anterior, cada movimentação com o saldo
+    /// acumulado e saldo final. Sem datas, assume o mês corrente até hoje.
+    pub async fn get_account_statement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<AccountStatement, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let today = Utc::now().date_naive();
+        let end_date = end_date.unwrap_or(today);
+        let start_date = start_date.unwrap_or_else(|| end_date.with_day(1).unwrap_or(end_date));
+
+        if start_date > end_date {
+            let mut errors = ValidationErrors::new();
+            errors.add("startDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        // Mesma transação: saldo anterior e movimentações enxergam o mesmo estado
+        let mut tx = executor.begin().await?;
+
+        let account = self.repo.get_account(&mut *tx, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+        let (opening_balance, ledger_balance) = self.repo
+            .get_account_ledger_balances(&mut *tx, tenant_id, account_id, start_date)
+            .await?;
+
+        let movements = self.repo
+            .list_account_movements(&mut *tx, tenant_id, account_id, start_date, end_date)
+            .await?;
+
+        tx.commit().await?;
+
+        let mut balance = opening_balance;
+        let mut total_in = Decimal::ZERO;
+        let mut total_out = Decimal::ZERO;
+
+        let lines = movements.into_iter().map(|movement| {
+            balance += movement.amount;
+            if movement.amount >= Decimal::ZERO {
+                total_in += movement.amount;
+            } else {
+                total_out += movement.amount;
+            }
+            StatementLine { movement, running_balance: balance }
+        }).collect();
+
+        Ok(AccountStatement {
+            account,
+            start_date,
+            end_date,
+            opening_balance,
+            total_in,
+            total_out,
+            closing_balance: balance,
+            lines,
+            ledger_balance,
+        })
+    }
+
+    // =========================================================================
+    //  CAIXA (Abertura / Sangria / Suprimento / Fechamento)
+    // =========================================================================
+
+    /// Abre um caixa para o operador no local, sobre uma conta (a gaveta).
+    pub async fn open_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        location_id: Uuid,
+        user_id: Uuid,
+        opening_float: Decimal,
+        notes: Option<&str>,
+    ) -> Result<CashSession, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let account = self.repo.get_account_for_update(&mut *tx, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+        if !account.is_active.unwrap_or(true) {
+            return Err(AppError::AccountInactive(account.name));
+        }
+
+        let session = self.repo.create_cash_session(
+            &mut *tx, tenant_id, account_id, location_id, user_id, opening_float, notes
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    /// Lançamento manual no caixa: Venda de balcão, Suprimento ou Sangria.
+    /// `amount` é sempre positivo; o sinal vem da natureza.
+    pub async fn post_cash_movement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+        kind: CashMovementKind,
+        amount: Decimal,
+        description: Option<&str>,
+        user_id: Uuid,
+    ) -> Result<FinancialMovement, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let signed = match kind {
+            CashMovementKind::Sale | CashMovementKind::Deposit => amount,
+            CashMovementKind::Withdrawal => -amount,
+            // Pagamentos vêm das baixas e ajustes do fechamento
+            CashMovementKind::Payment | CashMovementKind::Adjustment => {
+                let mut errors = ValidationErrors::new();
+                errors.add("kind", ValidationError::new("InvalidCashMovementKind"));
+                return Err(AppError::ValidationError(errors));
+            }
+        };
+
+        let mut tx = executor.begin().await?;
+
+        let session = self.repo.get_cash_session_for_update(&mut *tx, tenant_id, cash_session_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Caixa {}", cash_session_id)))?;
+
+        if session.status != CashSessionStatus::Open {
+            return Err(AppError::CashSessionClosed(cash_session_id.to_string()));
+        }
+
+        let description = description.map(str::to_string).unwrap_or_else(|| match kind {
+            CashMovementKind::Deposit => "Suprimento de caixa".to_string(),
+            CashMovementKind::Withdrawal => "Sangria de caixa".to_string(),
+            _ => "Venda de balcão".to_string(),
+        });
+
+        let movement = self.repo.create_movement(
+            &mut *tx,
+            tenant_id,
+            session.account_id,
+            None,
+            None,
+            signed,
+            Utc::now().date_naive(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Some(&description),
+            Some(user_id),
+            None
+        ).await?;
+
+        let movement = self.repo
+            .attach_movement_to_cash_session(&mut *tx, tenant_id, movement.id, session.id, kind)
+            .await?;
+
+        self.repo.update_account_balance(&mut *tx, tenant_id, session.account_id, signed).await?;
+
+        tx.commit().await?;
+
+        Ok(movement)
+    }
+
+    /// Fecha o caixa: compara o contado com o esperado (troco + movimentações).
+    /// A diferença (quebra/sobra) vira um ADJUSTMENT para a conta bater com a gaveta.
+    pub async fn close_cash_session<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+        user_id: Uuid,
+        counted_amount: Decimal,
+        notes: Option<&str>,
+    ) -> Result<CashSessionReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let session = self.repo.get_cash_session_for_update(&mut *tx, tenant_id, cash_session_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Caixa {}", cash_session_id)))?;
+
+        if session.status != CashSessionStatus::Open {
+            return Err(AppError::CashSessionClosed(cash_session_id.to_string()));
+        }
+
+        // 1. Esperado
+        let movements = self.repo.list_cash_session_movements(&mut *tx, tenant_id, session.id).await?;
+        let expected = session.opening_float + movements.iter().map(|m| m.amount).sum::<Decimal>();
+        let difference = counted_amount - expected;
+
+        // 2. Quebra / Sobra
+        if difference != Decimal::ZERO {
+            let description = if difference < Decimal::ZERO { "Quebra de caixa" } else { "Sobra de caixa" };
+
+            let adjustment = self.repo.create_movement(
+                &mut *tx,
+                tenant_id,
+                session.account_id,
+                None,
+                None,
+                difference,
+                Utc::now().date_naive(),
+                Decimal::ZERO,
+                Decimal::ZERO,
+                Some(description),
+                Some(user_id),
+                None
+            ).await?;
+
+            self.repo
+                .attach_movement_to_cash_session(&mut *tx, tenant_id, adjustment.id, session.id, CashMovementKind::Adjustment)
+                .await?;
+
+            self.repo.update_account_balance(&mut *tx, tenant_id, session.account_id, difference).await?;
+        }
+
+        // 3. Fecha
+        let session = self.repo.close_cash_session(
+            &mut *tx, tenant_id, session.id, user_id, expected, counted_amount, notes
+        ).await?;
+
+        let report = self.build_cash_report(&mut tx, tenant_id, session).await?;
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    pub async fn get_cash_session_report<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        cash_session_id: Uuid,
+    ) -> Result<CashSessionReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        // Snapshot consistente (sessão + movimentações)
+        let mut tx = executor.begin().await?;
+
+        let session = self.repo.get_cash_session(&mut *tx, tenant_id, cash_session_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Caixa {}", cash_session_id)))?;
+
+        let report = self.build_cash_report(&mut tx, tenant_id, session).await?;
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    pub async fn list_cash_sessions<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<CashSessionStatus>,
+    ) -> Result<Vec<CashSession>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_cash_sessions(executor, tenant_id, status).await
+    }
+
+    /// Totais por natureza + esperado (sem a quebra/sobra).
+    async fn build_cash_report(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        session: CashSession,
+    ) -> Result<CashSessionReport, AppError> {
+        let movements = self.repo.list_cash_session_movements(&mut *conn, tenant_id, session.id).await?;
+        let (account_name, location_name) = self.repo
+            .get_cash_session_labels(&mut *conn, tenant_id, session.id)
+            .await?;
+
+        let total_of = |kind: CashMovementKind| -> Decimal {
+            movements.iter()
+                .filter(|m| m.cash_kind == Some(kind))
+                .map(|m| m.amount)
+                .sum()
+        };
+
+        let total_sales = total_of(CashMovementKind::Sale);
+        let total_payments = total_of(CashMovementKind::Payment);
+        let total_deposits = total_of(CashMovementKind::Deposit);
+        let total_withdrawals = total_of(CashMovementKind::Withdrawal);
+        let total_adjustments = total_of(CashMovementKind::Adjustment);
+
+        let expected_amount = session.opening_float
+            + total_sales + total_payments + total_deposits + total_withdrawals;
+
+        Ok(CashSessionReport {
+            session,
+            account_name,
+            location_name,
+            total_sales,
+            total_payments,
+            total_deposits,
+            total_withdrawals,
+            total_adjustments,
+            expected_amount,
+            movements,
+        })
+    }
+
+    // =========================================================================
+    //  RELATÓRIOS (DRE por competência)
+    // =========================================================================
+
+    /// DRE do período, mês a mês, pela data de competência dos títulos:
+    /// Receita Bruta (a receber por categoria) - CMV (custo dos itens dos pedidos)
+    /// = Lucro Bruto - Despesas (a pagar por categoria) = Resultado Líquido.
+    /// As categorias seguem a árvore do plano de contas, com as subcategorias somadas no pai.
+    /// Sem datas, assume de 1º de janeiro do ano corrente até hoje.
+    pub async fn get_dre_report<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<DreReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let end_date = end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let start_date = start_date
+            .unwrap_or_else(|| NaiveDate::from_ymd_opt(end_date.year(), 1, 1).unwrap_or(end_date));
+
+        if start_date > end_date {
+            let mut errors = ValidationErrors::new();
+            errors.add("startDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+        let categories = self.repo.get_all_categories(&mut *tx, tenant_id).await?;
+        let totals = self.repo.get_dre_category_totals(&mut *tx, tenant_id, start_date, end_date).await?;
+        let monthly_cogs = self.repo.get_monthly_cogs(&mut *tx, tenant_id, start_date, end_date).await?;
+        tx.commit().await?;
+
+        // Colunas: primeiro dia de cada mês do período
+        let mut months = Vec::new();
+        let mut month = start_date.with_day(1).unwrap_or(start_date);
+        while month <= end_date {
+            months.push(month);
+            month = month.checked_add_months(Months::new(1)).unwrap_or(NaiveDate::MAX);
+        }
+
+        let column = |date: NaiveDate| months.iter().position(|m| *m == date);
+        let empty_line = |category_id: Option<Uuid>, label: &str| DreLine {
+            category_id,
+            code: None,
+            label: label.to_string(),
+            depth: 0,
+            values: vec![Decimal::ZERO; months.len()],
+            total: Decimal::ZERO,
+        };
+
+        // Valores por (tipo, categoria). Cada título soma na sua categoria e em
+        // todos os ancestrais, então o nó pai já traz as subcategorias
+        let by_id: HashMap<Uuid, &FinancialCategory> = categories.iter().map(|c| (c.id, c)).collect();
+        let mut values: HashMap<(TitleKind, Option<Uuid>), Vec<Decimal>> = HashMap::new();
+
+        for row in totals {
+            let Some(idx) = column(row.month) else { continue };
+
+            let nodes: Vec<Option<Uuid>> = match row.category_id {
+                Some(id) => Self::category_path(&by_id, id).into_iter().map(Some).collect(),
+                None => vec![None],
+            };
+
+            for node in nodes {
+                values.entry((row.kind, node))
+                    .or_insert_with(|| vec![Decimal::ZERO; months.len()])[idx] += row.total;
+            }
+        }
+
+        // Linhas na ordem da árvore (pai antes dos filhos); "Sem categoria" por último
+        let mut tree = Vec::new();
+        Self::category_tree_order(&Self::category_children(&categories), None, 0, &mut tree);
+
+        let dre_lines = |kind: TitleKind| -> Vec<DreLine> {
+            let nodes = tree.iter().map(|(c, depth)| (Some(*c), *depth)).chain([(None, 0)]);
+
+            nodes
+                .filter_map(|(category, depth)| {
+                    let values = values.get(&(kind, category.map(|c| c.id)))?.clone();
+                    Some(DreLine {
+                        category_id: category.map(|c| c.id),
+                        code: category.and_then(|c| c.code.clone()),
+                        label: category.map_or("Sem categoria", |c| c.name.as_str()).to_string(),
+                        depth,
+                        total: values.iter().copied().sum(),
+                        values,
+                    })
+                })
+                .collect()
+        };
+
+        let revenue_lines = dre_lines(TitleKind::Receivable);
+        let expense_lines = dre_lines(TitleKind::Payable);
+
+        let mut cogs = empty_line(None, "(-) CMV - Custo das Mercadorias Vendidas");
+        for row in monthly_cogs {
+            if let Some(idx) = column(row.month) {
+                let value = row.total.round_dp(2);
+                cogs.values[idx] += value;
+                cogs.total += value;
+            }
+        }
+
+        let gross_revenue = Self::sum_dre_lines("Receita Bruta", &revenue_lines, months.len());
+        let gross_profit = Self::subtract_dre_lines("Lucro Bruto", &gross_revenue, &cogs);
+        let expenses = Self::sum_dre_lines("(-) Despesas", &expense_lines, months.len());
+        let net_result = Self::subtract_dre_lines("Resultado Líquido", &gross_profit, &expenses);
+
+        Ok(DreReport {
+            start_date,
+            end_date,
+            months,
+            revenue_lines,
+            gross_revenue,
+            cogs,
+            gross_profit,
+            expense_lines,
+            expenses,
+            net_result,
+        })
+    }
+
+    /// Soma as linhas raiz (as subcategorias já estão dentro delas).
+    fn sum_dre_lines(label: &str, lines: &[DreLine], columns: usize) -> DreLine {
+        let mut values = vec![Decimal::ZERO; columns];
+        for line in lines.iter().filter(|l| l.depth == 0) {
+            for (total, value) in values.iter_mut().zip(&line.values) {
+                *total += *value;
+            }
+        }
+
+        DreLine {
+            category_id: None,
+            code: None,
+            label: label.to_string(),
+            depth: 0,
+            total: values.iter().copied().sum(),
+            values,
+        }
+    }
+
+    fn subtract_dre_lines(label: &str, a: &DreLine, b: &DreLine) -> DreLine {
+        DreLine {
+            category_id: None,
+            code: None,
+            label: label.to_string(),
+            depth: 0,
+            values: a.values.iter().zip(&b.values).map(|(x, y)| x - y).collect(),
+            total: a.total - b.total,
+        }
+    }
+
+    // =========================================================================
+    //  ORÇAMENTOS (Orçado x Realizado por categoria)
+    // =========================================================================
+
+    /// Define o orçamento da categoria no mês (qualquer dia do mês vale pelo mês todo).
+    pub async fn set_budget<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        category_id: Uuid,
+        month: NaiveDate,
+        amount: Decimal,
+        user_id: Uuid,
+    ) -> Result<CategoryBudget, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        self.repo.get_category(&mut *tx, tenant_id, category_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Categoria {}", category_id)))?;
+
+        let month = month.with_day(1).unwrap_or(month);
+        let budget = self.repo.upsert_budget(&mut *tx, tenant_id, category_id, month, amount, user_id).await?;
+
+        tx.commit().await?;
+        Ok(budget)
+    }
+
+    /// Orçamentos dos meses do período. Sem datas, o mês corrente.
+    pub async fn list_budgets<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        category_id: Option<Uuid>,
+    ) -> Result<Vec<CategoryBudget>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let (start_date, end_date) = Self::budget_period(start_date, end_date)?;
+        let start_month = start_date.with_day(1).unwrap_or(start_date);
+
+        self.repo.list_budgets(executor, tenant_id, start_month, end_date, category_id).await
+    }
+
+    pub async fn delete_budget<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if !self.repo.delete_budget(executor, tenant_id, budget_id).await? {
+            return Err(AppError::ResourceNotFound(format!("Orçamento {}", budget_id)));
+        }
+        Ok(())
+    }
+
+    /// Orçado x Realizado do período, na árvore do plano de contas.
+    /// O orçado soma os meses que tocam o período. Uma categoria sem orçamento próprio
+    /// herda a soma do orçado nas filhas; com orçamento próprio, ele vale para a subárvore.
+    /// Realizado: competência (títulos não cancelados) e baixas (principal pago no período).
+    /// Sem datas, assume o mês corrente.
+    pub async fn get_budget_report<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<BudgetReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let (start_date, end_date) = Self::budget_period(start_date, end_date)?;
+        let start_month = start_date.with_day(1).unwrap_or(start_date);
+
+        let mut tx = executor.begin().await?;
+        let categories = self.repo.get_all_categories(&mut *tx, tenant_id).await?;
+        let budgets = self.repo.get_budget_totals(&mut *tx, tenant_id, start_month, end_date).await?;
+        let accrued_totals = self.repo.get_dre_category_totals(&mut *tx, tenant_id, start_date, end_date).await?;
+        let settled_totals = self.repo.get_settled_category_totals(&mut *tx, tenant_id, start_date, end_date).await?;
+        let settings = self.settings_repo.get_settings(&mut *tx, tenant_id).await?;
+        tx.commit().await?;
+
+        // Realizado: cada valor soma na categoria e em todos os ancestrais
+        let by_id: HashMap<Uuid, &FinancialCategory> = categories.iter().map(|c| (c.id, c)).collect();
+        let mut accrued: HashMap<Uuid, Decimal> = HashMap::new();
+        let mut settled: HashMap<Uuid, Decimal> = HashMap::new();
+
+        let accrued_rows = accrued_totals.iter().filter_map(|r| r.category_id.map(|id| (id, r.total)));
+        for (category_id, total) in accrued_rows {
+            for node in Self::category_path(&by_id, category_id) {
+                *accrued.entry(node).or_default() += total;
+            }
+        }
+        for row in &settled_totals {
+            for node in Self::category_path(&by_id, row.category_id) {
+                *settled.entry(node).or_default() += row.total;
+            }
+        }
+
+        let own_budgets: HashMap<Uuid, Decimal> = budgets.iter().map(|b| (b.category_id, b.total)).collect();
+
+        let children = Self::category_children(&categories);
+        let mut tree = Vec::new();
+        Self::category_tree_order(&children, None, 0, &mut tree);
+
+        // Orçado valendo: de baixo para cima (na ordem da árvore, os filhos vêm depois do pai)
+        let mut budget_of: HashMap<Uuid, Decimal> = HashMap::new();
+        for (category, _) in tree.iter().rev() {
+            let budget = own_budgets.get(&category.id).copied().or_else(|| {
+                children.get(&Some(category.id)).into_iter().flatten()
+                    .filter_map(|child| budget_of.get(&child.id).copied())
+                    .reduce(|a, b| a + b)
+            });
+            if let Some(budget) = budget {
+                budget_of.insert(category.id, budget);
+            }
+        }
+
+        let alert_percent = settings.budget_alert_percent;
+        let lines = tree.iter()
+            .filter_map(|(category, depth)| {
+                let budget = budget_of.get(&category.id).copied();
+                let accrued = accrued.get(&category.id).copied().unwrap_or(Decimal::ZERO);
+                let settled = settled.get(&category.id).copied().unwrap_or(Decimal::ZERO);
+
+                if budget.is_none() && accrued.is_zero() && settled.is_zero() {
+                    return None;
+                }
+
+                let percent_used = budget
+                    .filter(|b| *b > Decimal::ZERO)
+                    .map(|b| (accrued * Decimal::ONE_HUNDRED / b).round_dp(2));
+
+                let over_threshold = category.kind == TitleKind::Payable
+                    && accrued > Decimal::ZERO
+                    && budget.is_some_and(|b| accrued * Decimal::ONE_HUNDRED >= b * alert_percent);
+
+                Some(BudgetLine {
+                    category_id: category.id,
+                    code: category.code.clone(),
+                    name: category.name.clone(),
+                    kind: category.kind,
+                    depth: *depth,
+                    own_budget: own_budgets.get(&category.id).copied(),
+                    budget,
+                    accrued,
+                    settled,
+                    variance: budget.map(|b| accrued - b),
+                    percent_used,
+                    over_threshold,
+                })
+            })
+            .collect();
+
+        Ok(BudgetReport {
+            start_date,
+            end_date,
+            alert_percent,
+            lines,
+        })
+    }
+
+    /// Período do orçamento: sem datas, o mês corrente inteiro.
+    fn budget_period(
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<(NaiveDate, NaiveDate), AppError> {
+        let today = Utc::now().date_naive();
+        let start_date = start_date.unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+        let end_date = end_date.unwrap_or_else(|| {
+            start_date.with_day(1)
+                .and_then(|d| d.checked_add_months(Months::new(1)))
+                .and_then(|d| d.pred_opt())
+                .unwrap_or(start_date)
+        });
+
+        if start_date > end_date {
+            let mut errors = ValidationErrors::new();
+            errors.add("startDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok((start_date, end_date))
+    }
+
+    // =========================================================================
+    //  VENCIDOS (Rotina em segundo plano)
+    // =========================================================================
+
+    /// Marca como OVERDUE os títulos em aberto já vencidos da loja.
+    pub async fn mark_overdue_titles<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.mark_overdue_titles(executor, tenant_id, Utc::now().date_naive()).await
+    }
+}
@@ -2,27 +2,44 @@
 
 use crate::{
     common::error::AppError,
-    db::InventoryRepository,
+    db::{InventoryRepository, SettingsRepository},
     // Importamos os novos enums e structs
     models::inventory::{
         Category, InventoryLevel, Item, ItemKind, StockMovementReason,
-        UnitOfMeasure, CompositionType, CompositionEntry
+        UnitOfMeasure, UnitConversion, CompositionType, CompositionEntry,
+        StockTransfer, StockTransferDetail, StockTransferItem, StockTransferLine, StockTransferStatus,
+        InventoryCount, InventoryCountDetail, InventoryCountEntry, InventoryCountLine,
+        InventoryCountReport, InventoryCountStatus,
+        ItemKardex, KardexLine, StockMovementPage, CompositionChoice,
+        ProductionOrder, ProductionOrderDetail, ProductionOrderStatus, ProductionConsumption,
+        WhereUsedEntry, FlattenedBom, StockReservation, StockReservationStatus, FulfilmentAllocation,
+        PoolAvailability
     },
 };
 use rust_decimal::Decimal;
-use sqlx::{PgPool, Postgres, Executor};
+use std::collections::HashMap;
+use sqlx::{PgConnection, PgPool, Postgres, Executor};
 use uuid::Uuid;
-use chrono::NaiveDate;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde_json::Value;
+use validator::{ValidationError, ValidationErrors};
+
+/// Paginação do histórico de movimentações
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Níveis de ficha técnica explodidos numa venda (proteção contra ciclos)
+const MAX_COMPOSITION_DEPTH: usize = 16;
 
 #[derive(Clone)]
 pub struct InventoryService {
     inventory_repo: InventoryRepository,
+    settings_repo: SettingsRepository,
 }
 
 impl InventoryService {
-    pub fn new(inventory_repo: InventoryRepository, _pool: PgPool) -> Self {
-        Self { inventory_repo }
+    pub fn new(inventory_repo: InventoryRepository, settings_repo: SettingsRepository, _pool: PgPool) -> Self {
+        Self { inventory_repo, settings_repo }
     }
 
     fn calculate_new_average_cost(
@@ -53,6 +70,8 @@ impl InventoryService {
         location_id: Option<Uuid>, // Obrigatório apenas se for criar estoque inicial
         category_id: Option<Uuid>,
         base_unit_id: Uuid,
+        purchase_unit_id: Option<Uuid>,
+        sale_unit_id: Option<Uuid>,
         sku: &str,
         name: &str,
         description: Option<&str>,
@@ -76,11 +95,16 @@ impl InventoryService {
         let new_item = self.inventory_repo
             .create_item(
                 &mut *tx, tenant_id, sku, name, description,
-                base_unit_id, category_id,
+                base_unit_id, purchase_unit_id, sale_unit_id, category_id,
                 kind, settings, Some(initial_cost), sale_price, min_stock
             )
             .await?;
 
+        // Unidades de compra/venda precisam converter para a base (conversões gerais)
+        for unit_id in [purchase_unit_id, sale_unit_id].into_iter().flatten() {
+            self.get_unit_factor(&mut *tx, tenant_id, &new_item, unit_id).await?;
+        }
+
         // 2. Se for PRODUTO Físico e tiver estoque inicial, cria saldo
         // Se for SERVIÇO ou RECURSO, ignoramos estoque inicial (não faz sentido estocar "Consulta Médica")
         if kind == ItemKind::Product {
@@ -118,6 +142,8 @@ impl InventoryService {
     //  COMPOSIÇÃO (FICHA TÉCNICA) - NOVO
     // =========================================================================
 
+    /// Inclui (ou atualiza) um filho na ficha técnica. Recusa qualquer ciclo no grafo:
+    /// A -> A, A -> B -> A, A -> B -> C -> A...
     pub async fn add_composition_item<'e, E>(
         &self,
         executor: E,
@@ -128,16 +154,24 @@ impl InventoryService {
         comp_type: CompositionType,
     ) -> Result<(), AppError>
     where
-        E: Executor<'e, Database = Postgres>,
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
     {
-        // VALIDAÇÃO DE SEGURANÇA: Previne ciclo infinito simples (A -> A)
         if parent_id == child_id {
-            return Err(AppError::ValidationError(
-                validator::ValidationErrors::new() // Erro genérico, ideal criar AppError::CircularDependency
-            ));
+            return Err(AppError::CompositionCycle(parent_id.to_string()));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        self.inventory_repo.lock_compositions(&mut *tx, tenant_id).await?;
+
+        if self.inventory_repo.composition_creates_cycle(&mut *tx, tenant_id, parent_id, child_id).await? {
+            return Err(AppError::CompositionCycle(parent_id.to_string()));
         }
 
-        self.inventory_repo.add_composition_item(executor, tenant_id, parent_id, child_id, quantity, comp_type).await
+        self.inventory_repo.add_composition_item(&mut *tx, tenant_id, parent_id, child_id, quantity, comp_type).await?;
+
+        tx.commit().await?;
+        Ok(())
     }
 
     pub async fn get_item_composition<'e, E>(
@@ -152,6 +186,86 @@ impl InventoryService {
         self.inventory_repo.get_item_composition(executor, tenant_id, parent_id).await
     }
 
+    /// Itens cuja ficha técnica usa `child_id` diretamente.
+    pub async fn get_where_used<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        child_id: Uuid,
+    ) -> Result<Vec<WhereUsedEntry>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.get_where_used(executor, tenant_id, child_id).await
+    }
+
+    /// Ficha técnica explodida em todos os níveis para `quantity` unidades do item,
+    /// com quantidades acumuladas e custo. O custo de um intermediário é a soma dos
+    /// filhos (não o custo dele próprio); o custo total é a soma do primeiro nível.
+    pub async fn get_flattened_composition<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        parent_id: Uuid,
+        quantity: Option<Decimal>,
+        location_id: Option<Uuid>,
+    ) -> Result<FlattenedBom, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let quantity = quantity.unwrap_or(Decimal::ONE);
+        if quantity <= Decimal::ZERO {
+            let mut errors = ValidationErrors::new();
+            errors.add("quantity", ValidationError::new("BomQuantityPositive"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, parent_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", parent_id)))?;
+
+        let mut lines = self.inventory_repo
+            .get_flattened_composition(&mut *tx, tenant_id, parent_id, quantity, location_id, MAX_COMPOSITION_DEPTH as i32)
+            .await?;
+
+        tx.commit().await?;
+
+        // Agrega de baixo para cima: cada linha soma no pai (caminho sem o último item)
+        let index: HashMap<Vec<Uuid>, usize> = lines.iter()
+            .enumerate()
+            .map(|(i, line)| (line.path.clone(), i))
+            .collect();
+
+        let mut children_cost: Vec<Option<Decimal>> = vec![None; lines.len()];
+        let mut order: Vec<usize> = (0..lines.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(lines[i].level));
+
+        let mut total_cost = Decimal::ZERO;
+        for i in order {
+            let line = &mut lines[i];
+            line.total_cost = children_cost[i]
+                .unwrap_or(line.cumulative_quantity * line.unit_cost)
+                .round_dp(2);
+
+            if line.level == 1 {
+                total_cost += line.total_cost;
+            } else if let Some(&parent) = index.get(&line.path[..line.path.len() - 1]) {
+                *children_cost[parent].get_or_insert(Decimal::ZERO) += line.total_cost;
+            }
+        }
+
+        Ok(FlattenedBom {
+            item_id: item.id,
+            item_sku: item.sku,
+            item_name: item.name,
+            quantity,
+            location_id,
+            total_cost,
+            lines,
+        })
+    }
+
     pub async fn remove_composition_item<'e, E>(
         &self,
         executor: E,
@@ -231,6 +345,130 @@ impl InventoryService {
         self.inventory_repo.create_unit(executor, tenant_id, name, symbol).await
     }
 
+    /// Cadastra (ou atualiza) a conversão 1 `from_unit_id` = `factor` `to_unit_id`.
+    /// Com `item_id`, vale só para o item (ex: caixa com 12 unidades).
+    pub async fn create_unit_conversion<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        from_unit_id: Uuid,
+        to_unit_id: Uuid,
+        factor: Decimal,
+    ) -> Result<UnitConversion, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if from_unit_id == to_unit_id {
+            let mut errors = ValidationErrors::new();
+            errors.add("toUnitId", ValidationError::new("ConversionSameUnit"));
+            return Err(AppError::ValidationError(errors));
+        }
+        if factor <= Decimal::ZERO {
+            let mut errors = ValidationErrors::new();
+            errors.add("factor", ValidationError::new("ConversionFactorPositive"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        self.inventory_repo
+            .upsert_unit_conversion(executor, tenant_id, item_id, from_unit_id, to_unit_id, factor)
+            .await
+    }
+
+    pub async fn list_unit_conversions<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+    ) -> Result<Vec<UnitConversion>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.list_unit_conversions(executor, tenant_id, item_id).await
+    }
+
+    pub async fn delete_unit_conversion<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        conversion_id: Uuid,
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if !self.inventory_repo.delete_unit_conversion(executor, tenant_id, conversion_id).await? {
+            return Err(AppError::ResourceNotFound(format!("Conversão {}", conversion_id)));
+        }
+        Ok(())
+    }
+
+    /// Define as unidades padrão de compra e de venda do item (vazio = unidade base).
+    pub async fn set_item_units<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        purchase_unit_id: Option<Uuid>,
+        sale_unit_id: Option<Uuid>,
+    ) -> Result<Item, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        for unit_id in [purchase_unit_id, sale_unit_id].into_iter().flatten() {
+            self.get_unit_factor(&mut *tx, tenant_id, &item, unit_id).await?;
+        }
+
+        let item = self.inventory_repo
+            .update_item_units(&mut *tx, tenant_id, item_id, purchase_unit_id, sale_unit_id)
+            .await?;
+
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    /// Quantas unidades base do item cabem em 1 `unit_id`.
+    /// Sem conversão cadastrada (do item ou geral), a unidade não é aceita.
+    pub async fn get_unit_factor<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item: &Item,
+        unit_id: Uuid,
+    ) -> Result<Decimal, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if unit_id == item.unit_id {
+            return Ok(Decimal::ONE);
+        }
+
+        self.inventory_repo
+            .get_unit_factor(executor, tenant_id, item.id, unit_id, item.unit_id)
+            .await?
+            .ok_or_else(|| {
+                let mut errors = ValidationErrors::new();
+                errors.add("unitId", ValidationError::new("UnitNotConvertible"));
+                AppError::ValidationError(errors)
+            })
+    }
+
+    /// Converte a quantidade lançada para a unidade base. O saldo guarda 2 casas:
+    /// conversão que gere mais casas (ex: 1 UN de uma CX de 12) é recusada em vez de arredondada.
+    pub fn to_base_quantity(quantity: Decimal, factor: Decimal) -> Result<Decimal, AppError> {
+        let base_quantity = (quantity * factor).normalize();
+        if factor != Decimal::ONE && base_quantity.scale() > 2 {
+            let mut errors = ValidationErrors::new();
+            errors.add("quantity", ValidationError::new("UnitQuantityTooPrecise"));
+            return Err(AppError::ValidationError(errors));
+        }
+        Ok(base_quantity)
+    }
+
     pub async fn create_category<'e, E>(
         &self,
         executor: E,
@@ -257,6 +495,7 @@ impl InventoryService {
         location_id: Uuid,
         quantity: Decimal,
         unit_cost: Decimal,
+        unit_id: Option<Uuid>,
         reason: StockMovementReason,
         notes: Option<&str>,
         batch_number: Option<String>,
@@ -272,6 +511,16 @@ impl InventoryService {
 
         let mut tx = executor.begin().await?;
 
+        // Quantidade e custo chegam na unidade de compra (ou na informada): normaliza para a base
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+        let factor = match unit_id.or(item.purchase_unit_id) {
+            Some(unit_id) => self.get_unit_factor(&mut *tx, tenant_id, &item, unit_id).await?,
+            None => Decimal::ONE,
+        };
+        let quantity = Self::to_base_quantity(quantity, factor)?;
+        let unit_cost = unit_cost / factor;
+
         let final_batch = batch_number.unwrap_or_else(|| "DEFAULT".to_string());
         let final_position = position.unwrap_or_else(|| "Geral".to_string());
 
@@ -308,6 +557,13 @@ impl InventoryService {
         Ok(updated_level)
     }
 
+    /// Venda de um item. Itens com ficha técnica (BUNDLE, prato com receita) são
+    /// explodidos: os COMPONENT baixam recursivamente. Um PRODUCT composto usa primeiro
+    /// o próprio estoque (preparado antes) e só monta na hora o que faltar.
+    /// ACCESSORY e SUBSTITUTE só baixam se escolhidos em `choices`.
+    /// Quantidade e preço vêm em `unit_id` (padrão: unidade de venda do item) e são
+    /// convertidos para a unidade base.
+    /// Retorna o custo total consumido (ao custo médio de cada local).
     pub async fn sell_item<'e, E>(
         &self,
         executor: E,
@@ -316,23 +572,220 @@ impl InventoryService {
         location_id: Uuid,
         quantity: Decimal,
         unit_price: Decimal,
+        unit_id: Option<Uuid>,
         consume_reservation: bool,
         notes: Option<&str>,
         specific_batch_number: Option<String>,
         specific_position: Option<String>,
-    ) -> Result<(), AppError>
+        choices: &[CompositionChoice],
+    ) -> Result<Decimal, AppError>
     where
         E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
     {
-        // Aqui também seria ideal checar se item.kind == PRODUCT
-        // Se for SERVICE, a gente deveria apenas registrar a venda sem baixar estoque.
-        // Mas isso ficará para o módulo de "Sales/Orders". O `sell_item` aqui é puramente movimentação de estoque.
-
         let mut tx = executor.begin().await?;
 
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        let factor = match unit_id.or(item.sale_unit_id) {
+            Some(unit_id) => self.get_unit_factor(&mut *tx, tenant_id, &item, unit_id).await?,
+            None => Decimal::ONE,
+        };
+        let quantity = Self::to_base_quantity(quantity, factor)?;
+        let unit_price = unit_price / factor;
+        let composition = self.inventory_repo.get_item_composition(&mut *tx, tenant_id, item_id).await?;
+        Self::check_composition_choices(&composition, choices)?;
+
+        let components: Vec<&CompositionEntry> = composition.iter()
+            .filter(|entry| entry.comp_type == CompositionType::Component)
+            .collect();
+
+        // 1. Estoque próprio (produto simples: tudo; produto composto: o que houver)
+        let mut total_cost = Decimal::ZERO;
+        let mut to_assemble = quantity;
+
+        if item.kind == ItemKind::Product {
+            // Reservado = já separado do estoque próprio
+            let own = if components.is_empty() || consume_reservation {
+                quantity
+            } else {
+                self.available_quantity(&mut tx, tenant_id, item_id, location_id).await?.min(quantity)
+            };
+
+            if own > Decimal::ZERO {
+                total_cost += self.deduct_stock(
+                    &mut tx, tenant_id, item_id, location_id, own, Some(unit_price),
+                    consume_reservation, StockMovementReason::Sale, notes,
+                    specific_batch_number, specific_position
+                ).await?;
+            }
+            to_assemble -= own;
+        }
+
+        // 2. Acessórios escolhidos sempre baixam; componentes (com as trocas) só o que falta montar
+        let quantity_of = |child_id: Uuid| composition.iter()
+            .find(|entry| entry.child_item_id == child_id)
+            .map(|entry| entry.quantity)
+            .unwrap_or(Decimal::ZERO);
+
+        let mut requirements: Vec<(Uuid, Decimal)> = choices.iter()
+            .filter(|choice| choice.replaces_item_id.is_none())
+            .map(|choice| (choice.child_item_id, quantity_of(choice.child_item_id) * quantity))
+            .collect();
+
+        if to_assemble > Decimal::ZERO {
+            for entry in &components {
+                let substitute = choices.iter().find(|choice| choice.replaces_item_id == Some(entry.child_item_id));
+                match substitute {
+                    Some(choice) => requirements.push((choice.child_item_id, quantity_of(choice.child_item_id) * to_assemble)),
+                    None => requirements.push((entry.child_item_id, entry.quantity * to_assemble)),
+                }
+            }
+        }
+
+        let component_notes = format!("{} (componente de {})", notes.unwrap_or("Venda"), item.sku);
+        total_cost += self.explode_components(
+            &mut tx, tenant_id, location_id, requirements, StockMovementReason::Sale, &component_notes
+        ).await?;
+
+        tx.commit().await?;
+        Ok(total_cost)
+    }
+
+    /// Confere as escolhas de uma venda contra a ficha técnica do item.
+    pub async fn validate_composition_choices<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        choices: &[CompositionChoice],
+    ) -> Result<(), AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if choices.is_empty() {
+            return Ok(());
+        }
+
+        let composition = self.inventory_repo.get_item_composition(executor, tenant_id, item_id).await?;
+        Self::check_composition_choices(&composition, choices)
+    }
+
+    // Acessório precisa ser ACCESSORY do item; substituto precisa ser SUBSTITUTE
+    // e trocar um COMPONENT do mesmo item.
+    fn check_composition_choices(composition: &[CompositionEntry], choices: &[CompositionChoice]) -> Result<(), AppError> {
+        let type_of = |child_id: Uuid| composition.iter()
+            .find(|entry| entry.child_item_id == child_id)
+            .map(|entry| entry.comp_type);
+
+        for choice in choices {
+            let valid = match choice.replaces_item_id {
+                None => type_of(choice.child_item_id) == Some(CompositionType::Accessory),
+                Some(component_id) => {
+                    type_of(choice.child_item_id) == Some(CompositionType::Substitute)
+                        && type_of(component_id) == Some(CompositionType::Component)
+                }
+            };
+
+            if !valid {
+                let mut errors = ValidationErrors::new();
+                errors.add("choices", ValidationError::new("InvalidCompositionChoice"));
+                return Err(AppError::ValidationError(errors));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Baixa os componentes nível a nível (sem recursão). Componentes que também têm
+    /// ficha técnica usam o próprio estoque primeiro e explodem o restante.
+    async fn explode_components(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        location_id: Uuid,
+        requirements: Vec<(Uuid, Decimal)>,
+        reason: StockMovementReason,
+        notes: &str,
+    ) -> Result<Decimal, AppError> {
+        let mut pending: Vec<(Uuid, Decimal, usize)> = requirements.into_iter()
+            .map(|(item_id, quantity)| (item_id, quantity, 1))
+            .collect();
+        let mut total_cost = Decimal::ZERO;
+
+        while let Some((item_id, quantity, depth)) = pending.pop() {
+            if quantity <= Decimal::ZERO { continue; }
+            if depth > MAX_COMPOSITION_DEPTH {
+                return Err(AppError::CompositionCycle(item_id.to_string()));
+            }
+
+            let item = self.inventory_repo.get_item(&mut *conn, tenant_id, item_id).await?
+                .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+            let components: Vec<CompositionEntry> = self.inventory_repo
+                .get_item_composition(&mut *conn, tenant_id, item_id).await?
+                .into_iter()
+                .filter(|entry| entry.comp_type == CompositionType::Component)
+                .collect();
+
+            let mut to_assemble = quantity;
+            if item.kind == ItemKind::Product {
+                let own = if components.is_empty() {
+                    quantity
+                } else {
+                    self.available_quantity(&mut *conn, tenant_id, item_id, location_id).await?.min(quantity)
+                };
+
+                if own > Decimal::ZERO {
+                    total_cost += self.deduct_stock(
+                        &mut *conn, tenant_id, item_id, location_id, own, None,
+                        false, reason.clone(), Some(notes), None, None
+                    ).await?;
+                }
+                to_assemble -= own;
+            }
+
+            if to_assemble > Decimal::ZERO {
+                for entry in components {
+                    pending.push((entry.child_item_id, entry.quantity * to_assemble, depth + 1));
+                }
+            }
+        }
+
+        Ok(total_cost)
+    }
+
+    async fn available_quantity(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<Decimal, AppError> {
+        let level = self.inventory_repo
+            .get_inventory_level_for_update(&mut *conn, tenant_id, item_id, location_id)
+            .await?;
+
+        Ok(level.map(|l| (l.quantity - l.reserved_quantity).max(Decimal::ZERO)).unwrap_or(Decimal::ZERO))
+    }
+
+    /// Baixa física de um item (nível + lotes + histórico). Retorna o custo ao custo médio.
+    async fn deduct_stock(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        unit_price: Option<Decimal>,
+        consume_reservation: bool,
+        reason: StockMovementReason,
+        notes: Option<&str>,
+        specific_batch_number: Option<String>,
+        specific_position: Option<String>,
+    ) -> Result<Decimal, AppError> {
         // 1. Valida Saldo Total
         let level_opt = self.inventory_repo
-            .get_inventory_level_for_update(&mut *tx, tenant_id, item_id, location_id)
+            .get_inventory_level_for_update(&mut *conn, tenant_id, item_id, location_id)
             .await?;
         let level = level_opt.ok_or(AppError::UniqueConstraintViolation("Item não existe no estoque".into()))?;
 
@@ -346,7 +799,7 @@ impl InventoryService {
         let reserved_delta = if consume_reservation { Some(-quantity) } else { None };
 
         self.inventory_repo.update_inventory_level(
-            &mut *tx, tenant_id, item_id, location_id, quantity_delta, reserved_delta, None, None, None
+            &mut *conn, tenant_id, item_id, location_id, quantity_delta, reserved_delta, None, None, None
         ).await?;
 
         // 3. Baixa nos Lotes (FIFO ou Específico)
@@ -358,7 +811,7 @@ impl InventoryService {
             position_for_history = target_pos.clone();
 
             self.inventory_repo.update_batch_quantity(
-                &mut *tx, tenant_id, item_id, location_id,
+                &mut *conn, tenant_id, item_id, location_id,
                 &target_batch,
                 &target_pos,
                 None,
@@ -367,7 +820,7 @@ impl InventoryService {
             ).await?;
         } else {
             let batches = self.inventory_repo
-                .get_batches_for_consumption(&mut *tx, tenant_id, item_id, location_id)
+                .get_batches_for_consumption(&mut *conn, tenant_id, item_id, location_id)
                 .await?;
 
             for batch in batches {
@@ -377,7 +830,7 @@ impl InventoryService {
                 let to_take = if available >= remaining_to_deduct { remaining_to_deduct } else { available };
 
                 self.inventory_repo.update_batch_quantity(
-                    &mut *tx, tenant_id, item_id, location_id,
+                    &mut *conn, tenant_id, item_id, location_id,
                     &batch.batch_number,
                     &batch.position,
                     None,
@@ -391,12 +844,1378 @@ impl InventoryService {
 
         // 4. Grava Histórico
         self.inventory_repo.record_stock_movement(
-            &mut *tx, tenant_id, item_id, location_id, quantity_delta,
-            StockMovementReason::Sale, None, Some(unit_price), notes,
+            &mut *conn, tenant_id, item_id, location_id, quantity_delta,
+            reason, Some(level.average_cost), unit_price, notes,
             Some(&position_for_history)
         ).await?;
 
+        Ok(quantity * level.average_cost)
+    }
+
+    // =========================================================================
+    //  TRANSFERÊNCIAS ENTRE LOCAIS
+    // =========================================================================
+
+    /// Move estoque de um local para outro levando lote, posição e custo de cada lote.
+    /// Numa única transação: TRANSFER_OUT na origem e, se `in_transit` for falso,
+    /// TRANSFER_IN no destino (as duas com o mesmo transfer_id).
+    /// Em trânsito, a entrada só acontece no `receive_transfer`.
+    pub async fn create_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        from_location_id: Uuid,
+        to_location_id: Uuid,
+        lines: &[StockTransferLine],
+        in_transit: bool,
+        notes: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<StockTransferDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        if from_location_id == to_location_id {
+            let mut errors = ValidationErrors::new();
+            errors.add("toLocationId", ValidationError::new("TransferSameLocation"));
+            return Err(AppError::ValidationError(errors));
+        }
+        if lines.is_empty() {
+            let mut errors = ValidationErrors::new();
+            errors.add("items", ValidationError::new("TransferRequiresItems"));
+            return Err(AppError::ValidationError(errors));
+        }
+        if lines.iter().any(|line| line.quantity <= Decimal::ZERO) {
+            let mut errors = ValidationErrors::new();
+            errors.add("items", ValidationError::new("TransferQuantityPositive"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        for location_id in [from_location_id, to_location_id] {
+            if !self.inventory_repo.get_location_exists(&mut *tx, tenant_id, location_id).await? {
+                return Err(AppError::ResourceNotFound(format!("Local {}", location_id)));
+            }
+        }
+
+        let mut transfer = self.inventory_repo
+            .create_transfer(&mut *tx, tenant_id, from_location_id, to_location_id, notes, user_id)
+            .await?;
+
+        // 1. Saída da origem (lote a lote)
+        let mut items = Vec::new();
+        for line in lines {
+            let shipped = self.ship_transfer_line(&mut tx, tenant_id, &transfer, line).await?;
+            items.extend(shipped);
+        }
+
+        // 2. Entrega imediata: entra no destino na mesma transação
+        if !in_transit {
+            self.post_transfer_entries(&mut tx, tenant_id, transfer.id, to_location_id, &items, false).await?;
+            transfer = self.inventory_repo
+                .close_transfer(&mut *tx, tenant_id, transfer.id, StockTransferStatus::Completed, user_id)
+                .await?;
+        }
+
         tx.commit().await?;
-        Ok(())
+        Ok(StockTransferDetail { transfer, items })
+    }
+
+    /// Dá entrada no destino de uma transferência em trânsito.
+    pub async fn receive_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<StockTransferDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        self.finish_transfer(executor, tenant_id, transfer_id, StockTransferStatus::Completed, user_id).await
+    }
+
+    /// Cancela uma transferência em trânsito: a mercadoria volta para a origem,
+    /// nos mesmos lotes e posições de onde saiu.
+    pub async fn cancel_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<StockTransferDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        self.finish_transfer(executor, tenant_id, transfer_id, StockTransferStatus::Cancelled, user_id).await
+    }
+
+    pub async fn get_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+    ) -> Result<StockTransferDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        // Snapshot consistente (cabeçalho + itens)
+        let mut tx = executor.begin().await?;
+
+        let transfer = self.inventory_repo.get_transfer(&mut *tx, tenant_id, transfer_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Transferência {}", transfer_id)))?;
+        let items = self.inventory_repo.get_transfer_items(&mut *tx, tenant_id, transfer_id).await?;
+
+        tx.commit().await?;
+        Ok(StockTransferDetail { transfer, items })
+    }
+
+    pub async fn list_transfers<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<StockTransferStatus>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<StockTransfer>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.list_transfers(executor, tenant_id, status, location_id).await
     }
-}
\ No newline at end of file
+
+    async fn finish_transfer<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        status: StockTransferStatus,
+        user_id: Option<Uuid>,
+    ) -> Result<StockTransferDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let transfer = self.inventory_repo.get_transfer_for_update(&mut *tx, tenant_id, transfer_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Transferência {}", transfer_id)))?;
+
+        if transfer.status != StockTransferStatus::InTransit {
+            return Err(AppError::StockTransferNotInTransit(transfer_id.to_string()));
+        }
+
+        let items = self.inventory_repo.get_transfer_items(&mut *tx, tenant_id, transfer_id).await?;
+
+        // Recebida: entra no destino. Cancelada: volta para a origem.
+        let (location_id, returning) = match status {
+            StockTransferStatus::Cancelled => (transfer.from_location_id, true),
+            _ => (transfer.to_location_id, false),
+        };
+        self.post_transfer_entries(&mut tx, tenant_id, transfer_id, location_id, &items, returning).await?;
+
+        let transfer = self.inventory_repo
+            .close_transfer(&mut *tx, tenant_id, transfer_id, status, user_id)
+            .await?;
+
+        tx.commit().await?;
+        Ok(StockTransferDetail { transfer, items })
+    }
+
+    /// Baixa uma linha da origem (lote informado ou FIFO) e registra o que saiu de cada lote.
+    async fn ship_transfer_line(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        transfer: &StockTransfer,
+        line: &StockTransferLine,
+    ) -> Result<Vec<StockTransferItem>, AppError> {
+        let from_location_id = transfer.from_location_id;
+
+        // 1. Saldo disponível (físico - reservado)
+        let level = self.inventory_repo
+            .get_inventory_level_for_update(&mut *conn, tenant_id, line.item_id, from_location_id)
+            .await?
+            .ok_or(AppError::InsufficientStock(format!("item {}", line.item_id)))?;
+
+        let available = level.quantity - level.reserved_quantity;
+        if available < line.quantity {
+            return Err(AppError::InsufficientStock(format!("item {} (disponível {})", line.item_id, available)));
+        }
+
+        // 2. De quais lotes sai
+        let batches = match &line.batch_number {
+            Some(batch_number) => {
+                let position = line.position.as_deref().unwrap_or("Geral");
+                let batch = self.inventory_repo
+                    .get_batch_for_update(&mut *conn, tenant_id, line.item_id, from_location_id, batch_number, position)
+                    .await?
+                    .ok_or(AppError::ResourceNotFound(format!("Lote {} ({})", batch_number, position)))?;
+                vec![batch]
+            }
+            None => self.inventory_repo
+                .get_batches_for_consumption(&mut *conn, tenant_id, line.item_id, from_location_id)
+                .await?,
+        };
+
+        let mut remaining = line.quantity;
+        let mut items = Vec::new();
+
+        for batch in batches {
+            if remaining <= Decimal::ZERO { break; }
+            if batch.quantity <= Decimal::ZERO { continue; }
+            let to_take = remaining.min(batch.quantity);
+            let to_position = line.to_position.as_deref().unwrap_or(&batch.position);
+
+            self.inventory_repo.update_batch_quantity(
+                &mut *conn, tenant_id, line.item_id, from_location_id,
+                &batch.batch_number, &batch.position, None,
+                -to_take, Decimal::ZERO
+            ).await?;
+
+            self.inventory_repo.record_transfer_movement(
+                &mut *conn, tenant_id, line.item_id, from_location_id, -to_take,
+                StockMovementReason::TransferOut, Some(batch.unit_cost), None,
+                transfer.notes.as_deref(), Some(&batch.position), Some(transfer.id)
+            ).await?;
+
+            let item = self.inventory_repo.add_transfer_item(
+                &mut *conn, tenant_id, transfer.id, line.item_id,
+                &batch.batch_number, batch.expiration_date,
+                &batch.position, to_position,
+                to_take, batch.unit_cost
+            ).await?;
+
+            items.push(item);
+            remaining -= to_take;
+        }
+
+        // Lotes não cobrem o saldo do nível (ou o lote informado é menor que o pedido)
+        if remaining > Decimal::ZERO {
+            return Err(AppError::InsufficientStock(format!("item {} (faltam {} nos lotes)", line.item_id, remaining)));
+        }
+
+        // 3. Nível da origem (saída não altera o custo médio)
+        self.inventory_repo.update_inventory_level(
+            &mut *conn, tenant_id, line.item_id, from_location_id, -line.quantity,
+            None, None, None, None
+        ).await?;
+
+        Ok(items)
+    }
+
+    /// Entrada dos itens da transferência num local, recalculando o custo médio.
+    /// `returning` = devolução à origem (cancelamento): volta para a posição de onde saiu.
+    async fn post_transfer_entries(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        transfer_id: Uuid,
+        location_id: Uuid,
+        items: &[StockTransferItem],
+        returning: bool,
+    ) -> Result<(), AppError> {
+        let notes = if returning { "Transferência cancelada" } else { "Recebimento de transferência" };
+
+        for item in items {
+            let position = if returning { &item.from_position } else { &item.to_position };
+
+            self.inventory_repo.update_batch_quantity(
+                &mut *conn, tenant_id, item.item_id, location_id,
+                &item.batch_number, position, item.expiration_date,
+                item.quantity, item.unit_cost
+            ).await?;
+
+            let current_level = self.inventory_repo
+                .get_inventory_level_for_update(&mut *conn, tenant_id, item.item_id, location_id)
+                .await?;
+
+            let (current_qty, current_avg) = match &current_level {
+                Some(level) => (level.quantity, level.average_cost),
+                None => (Decimal::ZERO, Decimal::ZERO),
+            };
+
+            let new_avg_cost = self.calculate_new_average_cost(current_qty, current_avg, item.quantity, item.unit_cost);
+
+            self.inventory_repo.update_inventory_level(
+                &mut *conn, tenant_id, item.item_id, location_id, item.quantity,
+                None, Some(new_avg_cost), None, None
+            ).await?;
+
+            self.inventory_repo.record_transfer_movement(
+                &mut *conn, tenant_id, item.item_id, location_id, item.quantity,
+                StockMovementReason::TransferIn, Some(item.unit_cost), None,
+                Some(notes), Some(position), Some(transfer_id)
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    // =========================================================================
+    //  INVENTÁRIO FÍSICO (CONTAGEM)
+    // =========================================================================
+
+    /// Abre a contagem de um local congelando o saldo esperado de cada lote/posição.
+    /// `item_ids` limita a contagem a alguns itens (contagem cíclica).
+    pub async fn open_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        location_id: Uuid,
+        item_ids: Option<&[Uuid]>,
+        notes: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<InventoryCountDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let count = self.inventory_repo
+            .create_count(&mut *tx, tenant_id, location_id, notes, user_id)
+            .await?;
+
+        self.inventory_repo
+            .snapshot_count_lines(&mut *tx, tenant_id, count.id, location_id, item_ids)
+            .await?;
+
+        let lines = self.inventory_repo.get_count_lines(&mut *tx, tenant_id, count.id).await?;
+
+        tx.commit().await?;
+        Ok(InventoryCountDetail { count, lines })
+    }
+
+    /// Lança as quantidades contadas. Com `accumulate`, soma ao que já foi lançado
+    /// (cada aparelho manda o que contou); sem, substitui a contagem da linha.
+    pub async fn record_counts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        entries: &[InventoryCountEntry],
+        accumulate: bool,
+        user_id: Option<Uuid>,
+    ) -> Result<InventoryCountDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        if entries.is_empty() {
+            let mut errors = ValidationErrors::new();
+            errors.add("entries", ValidationError::new("CountRequiresEntries"));
+            return Err(AppError::ValidationError(errors));
+        }
+        if entries.iter().any(|entry| entry.quantity.is_sign_negative()) {
+            let mut errors = ValidationErrors::new();
+            errors.add("entries", ValidationError::new("CountQuantityNegative"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let count = self.get_open_count_for_update(&mut tx, tenant_id, count_id).await?;
+
+        for entry in entries {
+            self.inventory_repo.upsert_count_line(
+                &mut *tx, tenant_id, count.id, count.location_id, entry.item_id,
+                entry.batch_number.as_deref().unwrap_or("DEFAULT"),
+                entry.position.as_deref().unwrap_or("Geral"),
+                entry.expiration_date,
+                entry.quantity,
+                accumulate,
+                user_id
+            ).await?;
+        }
+
+        let lines = self.inventory_repo.get_count_lines(&mut *tx, tenant_id, count.id).await?;
+
+        tx.commit().await?;
+        Ok(InventoryCountDetail { count, lines })
+    }
+
+    /// Aprova a contagem: cada diferença (contado - esperado congelado) vira uma
+    /// movimentação de CORRECTION no lote/posição, ao custo médio congelado.
+    /// Linhas não contadas são ignoradas, a menos que `zero_uncounted` seja verdadeiro.
+    pub async fn approve_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        zero_uncounted: bool,
+        user_id: Option<Uuid>,
+    ) -> Result<InventoryCountReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let count = self.get_open_count_for_update(&mut tx, tenant_id, count_id).await?;
+
+        if zero_uncounted {
+            self.inventory_repo.zero_uncounted_lines(&mut *tx, tenant_id, count.id, user_id).await?;
+        }
+
+        let lines = self.inventory_repo.get_count_lines(&mut *tx, tenant_id, count.id).await?;
+        let notes = format!("Inventário {}", count.id);
+
+        for line in &lines {
+            let Some(variance) = line.variance else { continue };
+            if variance.is_zero() { continue; }
+
+            // A diferença foi medida contra o saldo do início da contagem: se o lote ou o local
+            // foram movimentados depois, a correção pode passar do que existe agora
+            if variance < Decimal::ZERO {
+                let level = self.inventory_repo
+                    .get_inventory_level_for_update(&mut *tx, tenant_id, line.item_id, count.location_id)
+                    .await?;
+                let batch = self.inventory_repo
+                    .get_batch_for_update(
+                        &mut *tx, tenant_id, line.item_id, count.location_id, &line.batch_number, &line.position
+                    )
+                    .await?;
+
+                let batch_quantity = batch.map(|b| b.quantity).unwrap_or(Decimal::ZERO);
+                let level_quantity = level.map(|l| l.quantity).unwrap_or(Decimal::ZERO);
+                if batch_quantity + variance < Decimal::ZERO || level_quantity + variance < Decimal::ZERO {
+                    return Err(AppError::InventoryCountNegativeStock(format!(
+                        "item {} lote {} ({}): saldo atual {}, correção {}",
+                        line.item_id, line.batch_number, line.position, batch_quantity.min(level_quantity), variance
+                    )));
+                }
+            }
+
+            self.inventory_repo.update_batch_quantity(
+                &mut *tx, tenant_id, line.item_id, count.location_id,
+                &line.batch_number, &line.position, line.expiration_date,
+                variance, line.unit_cost
+            ).await?;
+
+            // Ajuste ao custo médio: não altera o custo médio do local
+            self.inventory_repo.update_inventory_level(
+                &mut *tx, tenant_id, line.item_id, count.location_id, variance,
+                None, None, None, None
+            ).await?;
+
+            self.inventory_repo.record_stock_movement(
+                &mut *tx, tenant_id, line.item_id, count.location_id, variance,
+                StockMovementReason::Correction, Some(line.unit_cost), None,
+                Some(&notes), Some(&line.position)
+            ).await?;
+        }
+
+        let count = self.inventory_repo
+            .close_count(&mut *tx, tenant_id, count.id, InventoryCountStatus::Approved, user_id)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Self::build_count_report(count, lines))
+    }
+
+    /// Descarta a contagem sem mexer no estoque.
+    pub async fn cancel_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<InventoryCount, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let count = self.get_open_count_for_update(&mut tx, tenant_id, count_id).await?;
+        let count = self.inventory_repo
+            .close_count(&mut *tx, tenant_id, count.id, InventoryCountStatus::Cancelled, user_id)
+            .await?;
+
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    pub async fn get_count<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<InventoryCountDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        // Snapshot consistente (cabeçalho + linhas)
+        let mut tx = executor.begin().await?;
+
+        let count = self.inventory_repo.get_count(&mut *tx, tenant_id, count_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Contagem {}", count_id)))?;
+        let lines = self.inventory_repo.get_count_lines(&mut *tx, tenant_id, count_id).await?;
+
+        tx.commit().await?;
+        Ok(InventoryCountDetail { count, lines })
+    }
+
+    /// Diferenças da contagem valorizadas ao custo médio (prévia, se ainda aberta).
+    pub async fn get_count_report<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<InventoryCountReport, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let detail = self.get_count(executor, tenant_id, count_id).await?;
+        Ok(Self::build_count_report(detail.count, detail.lines))
+    }
+
+    pub async fn list_counts<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<InventoryCountStatus>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<InventoryCount>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.list_counts(executor, tenant_id, status, location_id).await
+    }
+
+    async fn get_open_count_for_update(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        count_id: Uuid,
+    ) -> Result<InventoryCount, AppError> {
+        let count = self.inventory_repo.get_count_for_update(&mut *conn, tenant_id, count_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Contagem {}", count_id)))?;
+
+        if count.status != InventoryCountStatus::Open {
+            return Err(AppError::InventoryCountNotOpen(count_id.to_string()));
+        }
+
+        Ok(count)
+    }
+
+    fn build_count_report(count: InventoryCount, lines: Vec<InventoryCountLine>) -> InventoryCountReport {
+        let total_lines = lines.len() as i64;
+        let counted_lines = lines.iter().filter(|l| l.counted_quantity.is_some()).count() as i64;
+
+        let variances: Vec<InventoryCountLine> = lines
+            .into_iter()
+            .filter(|l| l.variance.is_some_and(|v| !v.is_zero()))
+            .collect();
+
+        let mut surplus_value = Decimal::ZERO;
+        let mut shortage_value = Decimal::ZERO;
+        for line in &variances {
+            let value = line.variance_value.unwrap_or(Decimal::ZERO);
+            if value.is_sign_positive() {
+                surplus_value += value;
+            } else {
+                shortage_value += value;
+            }
+        }
+
+        InventoryCountReport {
+            count,
+            total_lines,
+            counted_lines,
+            lines_with_variance: variances.len() as i64,
+            surplus_value,
+            shortage_value,
+            net_variance_value: surplus_value + shortage_value,
+            variances,
+        }
+    }
+
+    // =========================================================================
+    //  ORDENS DE PRODUÇÃO
+    // =========================================================================
+
+    /// Abre uma ordem de produção congelando os COMPONENT da ficha técnica
+    /// multiplicados pela quantidade planejada. Nada é baixado ainda.
+    pub async fn create_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        planned_quantity: Decimal,
+        notes: Option<&str>,
+        user_id: Option<Uuid>,
+    ) -> Result<ProductionOrderDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        if planned_quantity <= Decimal::ZERO {
+            let mut errors = ValidationErrors::new();
+            errors.add("plannedQuantity", ValidationError::new("ProductionQuantityPositive"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+        let components: Vec<CompositionEntry> = self.inventory_repo
+            .get_item_composition(&mut *tx, tenant_id, item_id).await?
+            .into_iter()
+            .filter(|entry| entry.comp_type == CompositionType::Component)
+            .collect();
+
+        // Só produto físico com ficha técnica gera lote
+        if item.kind != ItemKind::Product || components.is_empty() {
+            let mut errors = ValidationErrors::new();
+            errors.add("itemId", ValidationError::new("ProductionRequiresComposition"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let order = self.inventory_repo
+            .create_production_order(&mut *tx, tenant_id, item_id, location_id, planned_quantity, notes, user_id)
+            .await?;
+
+        for entry in &components {
+            self.inventory_repo.add_production_component(
+                &mut *tx, tenant_id, order.id, entry.child_item_id, entry.quantity * planned_quantity
+            ).await?;
+        }
+
+        let components = self.inventory_repo.get_production_components(&mut *tx, tenant_id, order.id).await?;
+
+        tx.commit().await?;
+        Ok(ProductionOrderDetail { order, components })
+    }
+
+    /// Conclui a produção: baixa os componentes (o planejado ou o consumo real
+    /// informado em `consumptions`) e gera um lote do item pai com custo unitário =
+    /// custo dos componentes / quantidade produzida. Componentes que também têm
+    /// ficha técnica usam o próprio estoque e explodem o que faltar.
+    /// Rendimento = produzido / planejado; perda = o que faltou para 100%.
+    pub async fn complete_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+        produced_quantity: Decimal,
+        consumptions: &[ProductionConsumption],
+        batch_number: Option<String>,
+        position: Option<String>,
+        expiration_date: Option<NaiveDate>,
+        user_id: Option<Uuid>,
+    ) -> Result<ProductionOrderDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        if produced_quantity <= Decimal::ZERO {
+            let mut errors = ValidationErrors::new();
+            errors.add("producedQuantity", ValidationError::new("ProductionQuantityPositive"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let order = self.get_planned_production_for_update(&mut tx, tenant_id, production_order_id).await?;
+        let components = self.inventory_repo.get_production_components(&mut *tx, tenant_id, order.id).await?;
+
+        let invalid_consumption = consumptions.iter().any(|consumption| {
+            consumption.quantity.is_sign_negative()
+                || !components.iter().any(|component| component.item_id == consumption.item_id)
+        });
+        if invalid_consumption {
+            let mut errors = ValidationErrors::new();
+            errors.add("consumptions", ValidationError::new("InvalidProductionConsumption"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        // 1. Baixa dos componentes
+        let notes = format!("Ordem de produção {}", order.id);
+        let mut total_cost = Decimal::ZERO;
+
+        for component in &components {
+            let quantity = consumptions.iter()
+                .find(|consumption| consumption.item_id == component.item_id)
+                .map(|consumption| consumption.quantity)
+                .unwrap_or(component.planned_quantity);
+
+            let cost = self.explode_components(
+                &mut tx, tenant_id, order.location_id, vec![(component.item_id, quantity)],
+                StockMovementReason::ProductionConsumption, &notes
+            ).await?;
+
+            self.inventory_repo
+                .set_production_consumption(&mut *tx, tenant_id, component.id, quantity, cost.round_dp(4))
+                .await?;
+            total_cost += cost;
+        }
+
+        // 2. Lote novo do item produzido
+        let total_cost = total_cost.round_dp(4);
+        let unit_cost = (total_cost / produced_quantity).round_dp(4);
+        let final_batch = batch_number.unwrap_or_else(|| format!("OP-{}", &order.id.simple().to_string()[..8]));
+        let final_position = position.unwrap_or_else(|| "Geral".to_string());
+
+        self.inventory_repo.update_batch_quantity(
+            &mut *tx, tenant_id, order.item_id, order.location_id, &final_batch,
+            &final_position, expiration_date, produced_quantity, unit_cost
+        ).await?;
+
+        let current_level = self.inventory_repo
+            .get_inventory_level_for_update(&mut *tx, tenant_id, order.item_id, order.location_id)
+            .await?;
+
+        let (current_qty, current_avg) = match &current_level {
+            Some(level) => (level.quantity, level.average_cost),
+            None => (Decimal::ZERO, Decimal::ZERO),
+        };
+
+        let new_avg_cost = self.calculate_new_average_cost(current_qty, current_avg, produced_quantity, unit_cost);
+
+        self.inventory_repo.update_inventory_level(
+            &mut *tx, tenant_id, order.item_id, order.location_id, produced_quantity,
+            None, Some(new_avg_cost), None, None
+        ).await?;
+
+        self.inventory_repo.record_stock_movement(
+            &mut *tx, tenant_id, order.item_id, order.location_id, produced_quantity,
+            StockMovementReason::ProductionOutput, Some(unit_cost), None,
+            Some(&notes), Some(&final_position)
+        ).await?;
+
+        // 3. Rendimento e perda
+        let yield_percent = (produced_quantity / order.planned_quantity * Decimal::ONE_HUNDRED).round_dp(2);
+        let loss_percent = (Decimal::ONE_HUNDRED - yield_percent).max(Decimal::ZERO);
+
+        let order = self.inventory_repo.complete_production_order(
+            &mut *tx, tenant_id, order.id, produced_quantity, yield_percent, loss_percent,
+            &final_batch, &final_position, expiration_date, total_cost, unit_cost, user_id
+        ).await?;
+
+        let components = self.inventory_repo.get_production_components(&mut *tx, tenant_id, order.id).await?;
+
+        tx.commit().await?;
+        Ok(ProductionOrderDetail { order, components })
+    }
+
+    /// Cancela uma ordem ainda planejada (nenhum estoque foi movimentado).
+    pub async fn cancel_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<ProductionOrder, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let order = self.get_planned_production_for_update(&mut tx, tenant_id, production_order_id).await?;
+        let order = self.inventory_repo
+            .cancel_production_order(&mut *tx, tenant_id, order.id, user_id)
+            .await?;
+
+        tx.commit().await?;
+        Ok(order)
+    }
+
+    pub async fn get_production_order<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+    ) -> Result<ProductionOrderDetail, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        // Snapshot consistente (cabeçalho + componentes)
+        let mut tx = executor.begin().await?;
+
+        let order = self.inventory_repo.get_production_order(&mut *tx, tenant_id, production_order_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Ordem de produção {}", production_order_id)))?;
+        let components = self.inventory_repo.get_production_components(&mut *tx, tenant_id, production_order_id).await?;
+
+        tx.commit().await?;
+        Ok(ProductionOrderDetail { order, components })
+    }
+
+    pub async fn list_production_orders<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<ProductionOrderStatus>,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<ProductionOrder>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.list_production_orders(executor, tenant_id, status, item_id, location_id).await
+    }
+
+    async fn get_planned_production_for_update(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        production_order_id: Uuid,
+    ) -> Result<ProductionOrder, AppError> {
+        let order = self.inventory_repo.get_production_order_for_update(&mut *conn, tenant_id, production_order_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Ordem de produção {}", production_order_id)))?;
+
+        if order.status != ProductionOrderStatus::Planned {
+            return Err(AppError::ProductionOrderNotPlanned(production_order_id.to_string()));
+        }
+
+        Ok(order)
+    }
+
+    // =========================================================================
+    //  RESERVAS DE ESTOQUE
+    // =========================================================================
+
+    /// Planeja de onde sai uma linha de pedido (`quantity` na unidade base), conforme a
+    /// estratégia do pedido: FIXED (tudo do local), POOL (um local do mesmo pool com saldo,
+    /// o escolhido tem preferência) ou POOL_SPLIT (divide entre os locais do pool).
+    /// Itens compostos e serviços sempre saem do local escolhido.
+    pub async fn plan_fulfilment<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        strategy: &str,
+    ) -> Result<Vec<FulfilmentAllocation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let allocations = match self.get_simple_product(&mut tx, tenant_id, item_id).await? {
+            Some(item) => self.allocate_in_pool(&mut tx, tenant_id, &item, location_id, quantity, strategy).await?,
+            None => vec![FulfilmentAllocation { location_id, quantity }],
+        };
+
+        tx.commit().await?;
+        Ok(allocations)
+    }
+
+    /// Venda compartilhada habilitada nas configurações da loja?
+    pub async fn shared_pool_selling<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<bool, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        Ok(self.settings_repo.get_settings(executor, tenant_id).await?.shared_pool_selling)
+    }
+
+    /// Saldo (físico, reservado e disponível) de cada item somado por pool, com a quebra por local.
+    pub async fn get_stock_pool_availability<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        stock_pool_id: Option<Uuid>,
+    ) -> Result<Vec<PoolAvailability>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.get_stock_pool_availability(executor, tenant_id, item_id, stock_pool_id).await
+    }
+
+    /// Venda avulsa. Com a venda compartilhada habilitada, o que faltar no local sai dos
+    /// outros locais do mesmo pool (o local da venda tem preferência). Lote ou posição
+    /// informados fixam a venda no local. Retorna o custo total da baixa.
+    pub async fn sell_item_from_pool<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        unit_price: Decimal,
+        unit_id: Option<Uuid>,
+        notes: Option<&str>,
+        specific_batch_number: Option<String>,
+        specific_position: Option<String>,
+        choices: &[CompositionChoice],
+    ) -> Result<Decimal, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let shared = self.shared_pool_selling(&mut *tx, tenant_id).await?;
+        if !shared || specific_batch_number.is_some() || specific_position.is_some() {
+            let total_cost = self.sell_item(
+                &mut *tx, tenant_id, item_id, location_id, quantity, unit_price, unit_id, false,
+                notes, specific_batch_number, specific_position, choices
+            ).await?;
+            tx.commit().await?;
+            return Ok(total_cost);
+        }
+
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        let factor = match unit_id.or(item.sale_unit_id) {
+            Some(unit_id) => self.get_unit_factor(&mut *tx, tenant_id, &item, unit_id).await?,
+            None => Decimal::ONE,
+        };
+        let quantity = Self::to_base_quantity(quantity, factor)?;
+
+        let allocations = self
+            .plan_fulfilment(&mut *tx, tenant_id, item_id, location_id, quantity, "POOL_SPLIT")
+            .await?;
+
+        // Cada parte já na unidade base
+        let mut total_cost = Decimal::ZERO;
+        for allocation in allocations {
+            total_cost += self.sell_item(
+                &mut *tx, tenant_id, item_id, allocation.location_id, allocation.quantity,
+                unit_price / factor, Some(item.unit_id), false, notes, None, None, choices
+            ).await?;
+        }
+
+        tx.commit().await?;
+        Ok(total_cost)
+    }
+
+    /// Reserva um item de pedido (`quantity` na unidade base) a partir do local escolhido,
+    /// seguindo a estratégia de atendimento (uma reserva por local). Só produtos simples
+    /// (sem COMPONENT) são reservados: itens compostos são montados na baixa e retornam
+    /// vazio. Se a linha já tem reservas ativas, elas são retornadas.
+    pub async fn reserve_order_item<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+        order_item_id: Uuid,
+        item_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        strategy: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let existing = self.inventory_repo
+            .list_active_line_reservations_for_update(&mut *tx, tenant_id, order_item_id)
+            .await?;
+        if !existing.is_empty() {
+            tx.commit().await?;
+            return Ok(existing);
+        }
+
+        let item = match self.get_simple_product(&mut tx, tenant_id, item_id).await? {
+            Some(item) if quantity > Decimal::ZERO => item,
+            _ => {
+                tx.commit().await?;
+                return Ok(vec![]);
+            }
+        };
+
+        let allocations = self.allocate_in_pool(&mut tx, tenant_id, &item, location_id, quantity, strategy).await?;
+
+        let mut reservations = Vec::with_capacity(allocations.len());
+        for allocation in allocations {
+            let level = self.inventory_repo
+                .get_inventory_level_for_update(&mut *tx, tenant_id, item_id, allocation.location_id)
+                .await?
+                .ok_or(AppError::InsufficientStock(format!("item {}", item.sku)))?;
+
+            let available = level.quantity - level.reserved_quantity;
+            if available < allocation.quantity {
+                return Err(AppError::InsufficientStock(format!("item {} (disponível {})", item.sku, available)));
+            }
+
+            self.inventory_repo.update_inventory_level(
+                &mut *tx, tenant_id, item_id, allocation.location_id, Decimal::ZERO,
+                Some(allocation.quantity), None, None, None
+            ).await?;
+
+            reservations.push(
+                self.inventory_repo.create_reservation(
+                    &mut *tx, tenant_id, order_id, order_item_id, item_id,
+                    allocation.location_id, allocation.quantity, expires_at
+                ).await?
+            );
+        }
+
+        tx.commit().await?;
+        Ok(reservations)
+    }
+
+    /// Marca as reservas ativas do item de pedido como CONSUMED, até `quantity` (a quantidade
+    /// atual da linha, que pode ter diminuído depois da reserva): o excedente volta ao disponível.
+    /// A baixa em si (e a saída do reservado) é do `sell_item` com `consume_reservation`,
+    /// no local de cada reserva.
+    pub async fn consume_order_item_reservations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_item_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let reservations = self.inventory_repo
+            .list_active_line_reservations_for_update(&mut *tx, tenant_id, order_item_id)
+            .await?;
+
+        let mut remaining = quantity;
+        let mut consumed = Vec::with_capacity(reservations.len());
+        for reservation in reservations {
+            let take = reservation.quantity.min(remaining.max(Decimal::ZERO));
+            remaining -= take;
+
+            if take.is_zero() {
+                self.return_reserved_stock(&mut tx, tenant_id, &reservation, StockReservationStatus::Released).await?;
+                continue;
+            }
+
+            if take < reservation.quantity {
+                // Só parte da reserva é usada: devolve o excedente e registra o consumido
+                self.inventory_repo.update_inventory_level(
+                    &mut *tx, tenant_id, reservation.item_id, reservation.location_id, Decimal::ZERO,
+                    Some(take - reservation.quantity), None, None, None
+                ).await?;
+                self.inventory_repo
+                    .update_reservation_quantity(&mut *tx, tenant_id, reservation.id, take)
+                    .await?;
+            }
+
+            consumed.push(
+                self.inventory_repo
+                    .close_reservation(&mut *tx, tenant_id, reservation.id, StockReservationStatus::Consumed)
+                    .await?
+            );
+        }
+
+        tx.commit().await?;
+        Ok(consumed)
+    }
+
+    /// Libera todas as reservas ativas do pedido (cancelamento).
+    pub async fn release_order_reservations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let reservations = self.inventory_repo
+            .list_order_reservations_for_update(&mut *tx, tenant_id, order_id)
+            .await?;
+
+        for reservation in &reservations {
+            self.return_reserved_stock(&mut tx, tenant_id, reservation, StockReservationStatus::Released).await?;
+        }
+
+        tx.commit().await?;
+        Ok(reservations.len() as u64)
+    }
+
+    /// Liberação manual de uma reserva ativa.
+    pub async fn release_reservation<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        reservation_id: Uuid,
+    ) -> Result<StockReservation, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let reservation = self.inventory_repo.get_reservation_for_update(&mut *tx, tenant_id, reservation_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Reserva {}", reservation_id)))?;
+
+        if reservation.status != StockReservationStatus::Active {
+            return Err(AppError::StockReservationNotActive(reservation_id.to_string()));
+        }
+
+        let reservation = self
+            .return_reserved_stock(&mut tx, tenant_id, &reservation, StockReservationStatus::Released)
+            .await?;
+
+        tx.commit().await?;
+        Ok(reservation)
+    }
+
+    /// Expira as reservas ativas com prazo vencido (rotina agendada).
+    /// Retorna quantas foram expiradas.
+    pub async fn expire_reservations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+    ) -> Result<u64, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let reservations = self.inventory_repo
+            .list_expired_reservations_for_update(&mut *tx, tenant_id)
+            .await?;
+
+        for reservation in &reservations {
+            self.return_reserved_stock(&mut tx, tenant_id, reservation, StockReservationStatus::Expired).await?;
+        }
+
+        tx.commit().await?;
+        Ok(reservations.len() as u64)
+    }
+
+    pub async fn list_reservations<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        status: Option<StockReservationStatus>,
+        order_id: Option<Uuid>,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+    ) -> Result<Vec<StockReservation>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.inventory_repo.list_reservations(executor, tenant_id, status, order_id, item_id, location_id).await
+    }
+
+    // O item, se for um produto sem componentes (o que pode ser reservado e dividido)
+    async fn get_simple_product(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<Item>, AppError> {
+        let item = self.inventory_repo.get_item(&mut *conn, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        if item.kind != ItemKind::Product {
+            return Ok(None);
+        }
+
+        let has_components = self.inventory_repo
+            .get_item_composition(&mut *conn, tenant_id, item_id).await?
+            .iter()
+            .any(|entry| entry.comp_type == CompositionType::Component);
+
+        Ok(if has_components { None } else { Some(item) })
+    }
+
+    // Distribui a quantidade entre os locais do pool conforme a estratégia (ver `plan_fulfilment`)
+    async fn allocate_in_pool(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        item: &Item,
+        location_id: Uuid,
+        quantity: Decimal,
+        strategy: &str,
+    ) -> Result<Vec<FulfilmentAllocation>, AppError> {
+        if strategy != "POOL" && strategy != "POOL_SPLIT" {
+            return Ok(vec![FulfilmentAllocation { location_id, quantity }]);
+        }
+
+        let availability = self.inventory_repo
+            .get_pool_availability(&mut *conn, tenant_id, item.id, location_id)
+            .await?;
+
+        if strategy == "POOL" {
+            return availability.iter()
+                .find(|(_, available)| *available >= quantity)
+                .map(|(location_id, _)| vec![FulfilmentAllocation { location_id: *location_id, quantity }])
+                .ok_or(AppError::InsufficientStock(format!("item {} no pool do local", item.sku)));
+        }
+
+        let mut allocations = Vec::new();
+        let mut remaining = quantity;
+        for (location_id, available) in availability {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            if available <= Decimal::ZERO {
+                continue;
+            }
+            let take = available.min(remaining);
+            allocations.push(FulfilmentAllocation { location_id, quantity: take });
+            remaining -= take;
+        }
+
+        if remaining > Decimal::ZERO {
+            return Err(AppError::InsufficientStock(format!("item {} (faltam {} no pool)", item.sku, remaining)));
+        }
+
+        Ok(allocations)
+    }
+
+    // Devolve a quantidade reservada ao disponível e encerra a reserva
+    async fn return_reserved_stock(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        reservation: &StockReservation,
+        status: StockReservationStatus,
+    ) -> Result<StockReservation, AppError> {
+        self.inventory_repo.update_inventory_level(
+            &mut *conn, tenant_id, reservation.item_id, reservation.location_id, Decimal::ZERO,
+            Some(-reservation.quantity), None, None, None
+        ).await?;
+
+        self.inventory_repo
+            .close_reservation(&mut *conn, tenant_id, reservation.id, status)
+            .await
+    }
+
+    // =========================================================================
+    //  HISTÓRICO DE MOVIMENTAÇÕES / KARDEX
+    // =========================================================================
+
+    /// Histórico paginado das movimentações (mais recentes primeiro).
+    pub async fn list_movements<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        reason: Option<StockMovementReason>,
+        position: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<StockMovementPage, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        if let (Some(start), Some(end)) = (start_date, end_date) {
+            if start > end {
+                let mut errors = ValidationErrors::new();
+                errors.add("startDate", ValidationError::new("InvalidDateRange"));
+                return Err(AppError::ValidationError(errors));
+            }
+        }
+
+        let page = page.unwrap_or(1).max(1);
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        // Mesma transação: total e página enxergam o mesmo estado
+        let mut tx = executor.begin().await?;
+
+        let total = self.inventory_repo.count_movements(
+            &mut *tx, tenant_id, item_id, location_id, reason.clone(), position, start_date, end_date
+        ).await?;
+
+        let items = self.inventory_repo.list_movements(
+            &mut *tx, tenant_id, item_id, location_id, reason, position, start_date, end_date,
+            page_size, (page - 1) * page_size
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(StockMovementPage { items, page, page_size, total })
+    }
+
+    /// Ficha de estoque (kardex) do item: cada movimentação do período com o saldo
+    /// e o custo médio logo após ela. O custo médio é refeito desde a primeira
+    /// movimentação: entradas recalculam a média ponderada (sem custo informado,
+    /// entram ao custo médio corrente); saídas não alteram a média.
+    /// Sem `location_id`, soma todos os locais.
+    pub async fn get_item_kardex<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        item_id: Uuid,
+        location_id: Option<Uuid>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<ItemKardex, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + sqlx::Acquire<'e, Database = Postgres>,
+    {
+        let end_date = end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let start_date = start_date.unwrap_or_else(|| end_date.with_day(1).unwrap_or(end_date));
+
+        if start_date > end_date {
+            let mut errors = ValidationErrors::new();
+            errors.add("startDate", ValidationError::new("InvalidDateRange"));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        let item = self.inventory_repo.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+
+        let movements = self.inventory_repo
+            .get_item_movements_until(&mut *tx, tenant_id, item_id, location_id, end_date)
+            .await?;
+
+        tx.commit().await?;
+
+        let mut quantity = Decimal::ZERO;
+        let mut average_cost = Decimal::ZERO;
+        let mut opening = None;
+        let mut total_in = Decimal::ZERO;
+        let mut total_out = Decimal::ZERO;
+        let mut lines = Vec::new();
+
+        for movement in movements {
+            let in_period = movement.created_at.date_naive() >= start_date;
+            if in_period && opening.is_none() {
+                opening = Some((quantity, average_cost));
+            }
+
+            let delta = movement.quantity_changed;
+            if delta > Decimal::ZERO {
+                let unit_cost = movement.unit_cost.unwrap_or(average_cost);
+                average_cost = self.calculate_new_average_cost(quantity, average_cost, delta, unit_cost).round_dp(2);
+            }
+            quantity += delta;
+
+            if !in_period { continue; }
+
+            if delta >= Decimal::ZERO {
+                total_in += delta;
+            } else {
+                total_out += delta;
+            }
+
+            lines.push(KardexLine {
+                movement,
+                running_quantity: quantity,
+                running_average_cost: average_cost,
+                running_value: (quantity * average_cost).round_dp(2),
+            });
+        }
+
+        let (opening_quantity, opening_average_cost) = opening.unwrap_or((quantity, average_cost));
+
+        Ok(ItemKardex {
+            item_id,
+            item_sku: item.sku,
+            item_name: item.name,
+            location_id,
+            start_date,
+            end_date,
+            opening_quantity,
+            opening_average_cost,
+            total_in,
+            total_out,
+            closing_quantity: quantity,
+            closing_average_cost: average_cost,
+            lines,
+        })
+    }
+}
@@ -1,14 +1,16 @@
 // src/services/operations_service.rs
 
 use std::sync::Arc;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use rust_decimal::Decimal;
 use sqlx::{Postgres, Executor, Acquire};
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 
 use crate::{
     common::error::AppError,
     db::OperationsRepository,
+    models::inventory::CompositionChoice,
     models::operations::{Order, OrderItem, Pipeline, PipelineStage, PipelineCategory},
     services::inventory_service::{InventoryService},
     services::finance_service::FinanceService
@@ -59,11 +61,17 @@ impl OperationsService {
         category: PipelineCategory,
         position: i32,
         stock_action: Option<&str>,
+        generates_receivable: bool,
+        payment_term_id: Option<Uuid>,
+        reservation_hours: Option<i32>,
     ) -> Result<PipelineStage, AppError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        self.repo.add_stage(executor, tenant_id, pipeline_id, name, category, position, stock_action).await
+        self.repo.add_stage(
+            executor, tenant_id, pipeline_id, name, category, position, stock_action,
+            generates_receivable, payment_term_id, reservation_hours
+        ).await
     }
 
     // --- PEDIDOS ---
@@ -75,12 +83,54 @@ impl OperationsService {
         customer_id: Option<Uuid>,
         pipeline_id: Uuid,
         notes: Option<&str>,
+        payment_term_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        fulfilment_strategy: Option<&str>,
     ) -> Result<Order, AppError>
     where
-        E: Executor<'e, Database = Postgres>,
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
     {
+        let mut tx = executor.begin().await?;
+
+        if let Some(location_id) = location_id {
+            self.ensure_location(&mut *tx, tenant_id, location_id).await?;
+        }
+
         // [CORREÇÃO] Chamamos o método otimizado do Repo
-        self.repo.create_order_initial(executor, tenant_id, customer_id, pipeline_id, notes).await
+        let order = self.repo.create_order_initial(
+            &mut *tx, tenant_id, customer_id, pipeline_id, notes, payment_term_id,
+            location_id, fulfilment_strategy
+        ).await?;
+
+        tx.commit().await?;
+        Ok(order)
+    }
+
+    /// Troca o local de atendimento e a estratégia do pedido.
+    /// Vale para as próximas reservas/baixas; o que já foi reservado não muda.
+    pub async fn set_order_fulfilment<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        order_id: Uuid,
+        location_id: Option<Uuid>,
+        fulfilment_strategy: &str,
+    ) -> Result<Order, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        if let Some(location_id) = location_id {
+            self.ensure_location(&mut *tx, tenant_id, location_id).await?;
+        }
+
+        let order = self.repo
+            .update_order_fulfilment(&mut *tx, tenant_id, order_id, location_id, fulfilment_strategy)
+            .await?;
+
+        tx.commit().await?;
+        Ok(order)
     }
 
     pub async fn add_item_to_order<'e, E>(
@@ -92,6 +142,9 @@ impl OperationsService {
         quantity: Decimal,
         unit_price: Decimal,
         unit_cost: Decimal,
+        unit_id: Option<Uuid>,
+        location_id: Option<Uuid>,
+        choices: &[CompositionChoice],
     ) -> Result<OrderItem, AppError>
     where
     // REMOVIDO: + Copy
@@ -101,17 +154,45 @@ impl OperationsService {
         // 1. Iniciamos uma transação para garantir que a inserção e o recálculo sejam atômicos
         let mut tx = executor.begin().await?;
 
+        if let Some(location_id) = location_id {
+            self.ensure_location(&mut *tx, tenant_id, location_id).await?;
+        }
+
+        // Acessórios/substitutos precisam existir na ficha técnica do item
+        self.inventory_service
+            .validate_composition_choices(&mut *tx, tenant_id, item_id, choices)
+            .await?;
+
+        // Lançado na unidade de venda (ou na informada): a quantidade vai para a unidade base,
+        // o preço fica na unidade lançada para o total bater com o digitado
+        let catalog_item = self.inventory_service.get_item(&mut *tx, tenant_id, item_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Item {}", item_id)))?;
+        let unit_id = unit_id.or(catalog_item.sale_unit_id).unwrap_or(catalog_item.unit_id);
+        let factor = self.inventory_service
+            .get_unit_factor(&mut *tx, tenant_id, &catalog_item, unit_id)
+            .await?;
+        let base_quantity = InventoryService::to_base_quantity(quantity, factor)?;
+
         // 2. Adiciona o item (passando a referência mutável da transação)
         let item = self.repo.add_order_item(
             &mut *tx, // Re-borrow seguro
             tenant_id,
             order_id,
             item_id,
-            quantity,
+            base_quantity,
             unit_price,
-            unit_cost
+            unit_cost,
+            Some(unit_id),
+            Some(quantity),
+            location_id
         ).await?;
 
+        for choice in choices {
+            self.repo.add_order_item_choice(
+                &mut *tx, tenant_id, item.id, choice.child_item_id, choice.replaces_item_id
+            ).await?;
+        }
+
         // 3. Atualiza o cache de total no pedido (passando a referência de novo)
         self.repo.recalculate_order_total(&mut *tx, tenant_id, order_id).await?;
 
@@ -142,43 +223,118 @@ impl OperationsService {
         // (Vou fazer uma query rápida aqui para não criar método novo no repo agora,
         // mas o ideal seria repo.get_order(&mut *tx, order_id))
         let order = sqlx::query!(
-            "SELECT display_id, total_amount, customer_id FROM orders WHERE id = $1 AND tenant_id = $2",
+            r#"
+            SELECT display_id, total_amount, customer_id, payment_term_id, location_id, fulfilment_strategy
+            FROM orders WHERE id = $1 AND tenant_id = $2
+            "#,
             order_id, tenant_id
         )
             .fetch_one(&mut *tx)
             .await?;
 
         // 2. Regra de Estoque (Já existia)
+        // Local de cada linha: o da linha, senão o do pedido, senão o único local da loja
         if let Some(action) = &new_stage.stock_action {
-            if action == "DEDUCT" {
+            if action == "RESERVE" {
                 let items = self.repo.list_order_items(&mut *tx, tenant_id, order_id).await?;
-                let location = sqlx::query!("SELECT id FROM locations WHERE tenant_id = $1 LIMIT 1", tenant_id)
-                    .fetch_optional(&mut *tx).await?;
-
-                if let Some(loc) = location {
-                    for item in items {
-                        self.inventory_service.sell_item(
-                            &mut *tx, tenant_id, item.item_id, loc.id,
-                            item.quantity, item.unit_price, false,
-                            Some(&format!("Pedido {}", order_id)), None, None
+                let default_location = self.default_location(&mut *tx, tenant_id, order.location_id, &items).await?;
+                let strategy = self.fulfilment_strategy(&mut *tx, tenant_id, &order.fulfilment_strategy).await?;
+
+                // Prazo da reserva vem da etapa (sem prazo = até a baixa ou o cancelamento)
+                let expires_at = new_stage.reservation_hours
+                    .map(|hours| Utc::now() + Duration::hours(hours as i64));
+
+                for item in items {
+                    let Some(location_id) = item.location_id.or(order.location_id).or(default_location) else {
+                        continue;
+                    };
+
+                    self.inventory_service.reserve_order_item(
+                        &mut *tx, tenant_id, order_id, item.id, item.item_id, location_id,
+                        item.quantity, &strategy, expires_at
+                    ).await?;
+                }
+            } else if action == "DEDUCT" {
+                let items = self.repo.list_order_items(&mut *tx, tenant_id, order_id).await?;
+                let default_location = self.default_location(&mut *tx, tenant_id, order.location_id, &items).await?;
+                let strategy = self.fulfilment_strategy(&mut *tx, tenant_id, &order.fulfilment_strategy).await?;
+
+                for item in items {
+                    let Some(location_id) = item.location_id.or(order.location_id).or(default_location) else {
+                        continue;
+                    };
+
+                    let choices = self.repo.list_order_item_choices(&mut *tx, tenant_id, item.id).await?;
+
+                    // O item do pedido já está na unidade base
+                    let catalog_item = self.inventory_service.get_item(&mut *tx, tenant_id, item.item_id).await?
+                        .ok_or(AppError::ResourceNotFound(format!("Item {}", item.item_id)))?;
+
+                    // O reservado baixa do local de cada reserva (até a quantidade da linha);
+                    // o restante segue a estratégia
+                    let reservations = self.inventory_service
+                        .consume_order_item_reservations(&mut *tx, tenant_id, item.id, item.quantity)
+                        .await?;
+                    let reserved: Decimal = reservations.iter().map(|r| r.quantity).sum();
+
+                    let mut parts: Vec<(Uuid, Decimal, bool)> = reservations.iter()
+                        .map(|r| (r.location_id, r.quantity, true))
+                        .collect();
+
+                    let remaining = item.quantity - reserved;
+                    if remaining > Decimal::ZERO {
+                        let allocations = self.inventory_service.plan_fulfilment(
+                            &mut *tx, tenant_id, item.item_id, location_id, remaining, &strategy
+                        ).await?;
+                        parts.extend(allocations.into_iter().map(|a| (a.location_id, a.quantity, false)));
+                    }
+
+                    // Preço por unidade base, só para registro no movimento
+                    let base_price = match item.unit_quantity {
+                        Some(unit_quantity) if item.quantity > Decimal::ZERO => {
+                            (item.unit_price * unit_quantity / item.quantity).round_dp(4)
+                        }
+                        _ => item.unit_price,
+                    };
+
+                    let mut consumed_cost = Decimal::ZERO;
+                    for (part_location_id, part_quantity, consume_reservation) in parts {
+                        consumed_cost += self.inventory_service.sell_item(
+                            &mut *tx, tenant_id, item.item_id, part_location_id,
+                            part_quantity, base_price, Some(catalog_item.unit_id), consume_reservation,
+                            Some(&format!("Pedido {}", order_id)), None, None,
+                            &choices
+                        ).await?;
+                    }
+
+                    // Custo real da baixa (soma dos componentes, se a ficha foi explodida)
+                    if item.quantity > Decimal::ZERO {
+                        self.repo.update_order_item_cost(
+                            &mut *tx, tenant_id, item.id, (consumed_cost / item.quantity).round_dp(4)
                         ).await?;
                     }
                 }
             }
         }
 
+        // Pedido cancelado devolve o que estava reservado
+        if new_stage.category == PipelineCategory::Cancelled {
+            self.inventory_service.release_order_reservations(&mut *tx, tenant_id, order_id).await?;
+        }
+
         // 3. [NOVO] Regra Financeira (Gera Contas a Receber)
         if new_stage.generates_receivable.unwrap_or(false) {
             // Verifica se o total > 0 para não gerar boleto zerado
             if order.total_amount > Decimal::ZERO {
-                // Chama o Financeiro!
+                // Chama o Financeiro! (Parcelamento: o do pedido vence o da etapa)
                 self.finance_service.create_receivable_for_order(
                     &mut *tx,
                     tenant_id,
                     order_id,
                     order.display_id,
                     order.total_amount,
-                    order.customer_id
+                    order.customer_id,
+                    order.payment_term_id.or(new_stage.payment_term_id)
                 ).await?;
             }
         }
@@ -194,4 +350,53 @@ impl OperationsService {
         tx.commit().await?;
         Ok(())
     }
+
+    // Linha sem local (nem no pedido) só usa o local padrão se a loja tiver um único local;
+    // com vários, escolher um por conta própria baixaria o estoque do lugar errado
+    async fn default_location(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        tenant_id: Uuid,
+        order_location_id: Option<Uuid>,
+        items: &[OrderItem],
+    ) -> Result<Option<Uuid>, AppError> {
+        if order_location_id.is_some() || items.iter().all(|item| item.location_id.is_some()) {
+            return Ok(None);
+        }
+
+        match self.repo.count_locations(&mut *conn, tenant_id).await? {
+            0 => Ok(None),
+            1 => self.repo.get_default_location(&mut *conn, tenant_id).await,
+            _ => {
+                let mut errors = ValidationErrors::new();
+                errors.add("locationId", ValidationError::new("FulfilmentLocationRequired"));
+                Err(AppError::ValidationError(errors))
+            }
+        }
+    }
+
+    async fn ensure_location(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        tenant_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<(), AppError> {
+        if !self.repo.location_exists(&mut *conn, tenant_id, location_id).await? {
+            return Err(AppError::ResourceNotFound(format!("Local {}", location_id)));
+        }
+        Ok(())
+    }
+
+    // Com a venda compartilhada da loja, pedido FIXED completa o que faltar pelo pool
+    async fn fulfilment_strategy(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        tenant_id: Uuid,
+        order_strategy: &str,
+    ) -> Result<String, AppError> {
+        if order_strategy == "FIXED" && self.inventory_service.shared_pool_selling(&mut *conn, tenant_id).await? {
+            return Ok("POOL_SPLIT".to_string());
+        }
+        Ok(order_strategy.to_string())
+    }
 }
\ No newline at end of file
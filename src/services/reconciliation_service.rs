@@ -0,0 +1,729 @@
+// src/services/reconciliation_service.rs
+
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, Postgres, Executor, Acquire};
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{
+    common::error::AppError,
+    db::{FinanceRepository, ReconciliationRepository},
+    models::finance::{
+        BankImportResult, BankLineResolution, BankLineStatus, BankStatementFormat,
+        BankStatementImport, BankStatementLine, TitleKind
+    },
+    services::finance_service::FinanceService,
+};
+
+// Janela (em dias) entre a data do banco e a data lançada no sistema
+const MOVEMENT_MATCH_WINDOW_DAYS: i32 = 3;
+// Janela entre a data do banco e o vencimento do título (pagamentos adiantados/atrasados)
+const TITLE_MATCH_WINDOW_DAYS: i32 = 5;
+
+/// Transação lida do arquivo do banco, antes de ir para o banco de dados.
+#[derive(Debug)]
+struct ParsedBankLine {
+    external_id: String,
+    posted_date: NaiveDate,
+    amount: Decimal,
+    description: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ReconciliationService {
+    repo: ReconciliationRepository,
+    finance_repo: FinanceRepository,
+    finance_service: FinanceService,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        repo: ReconciliationRepository,
+        finance_repo: FinanceRepository,
+        finance_service: FinanceService
+    ) -> Self {
+        Self { repo, finance_repo, finance_service }
+    }
+
+    // =========================================================================
+    //  IMPORTAÇÃO
+    // =========================================================================
+
+    /// Importa um extrato (OFX ou CSV) para a conta e tenta conciliar cada linha nova:
+    /// 1. Movimentação já lançada (mesmo valor, data próxima) -> MATCHED
+    /// 2. Título em aberto (mesmo valor, vencimento próximo) -> PENDING com sugestão
+    /// 3. Nada encontrado -> PENDING (fila de revisão)
+    ///
+    /// Linhas já importadas antes (mesmo FITID) são ignoradas.
+    pub async fn import_statement<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Uuid,
+        format: BankStatementFormat,
+        file_name: Option<&str>,
+        content: &str,
+        user_id: Option<Uuid>,
+    ) -> Result<BankImportResult, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let parsed = match format {
+            BankStatementFormat::Ofx => Self::parse_ofx(content)?,
+            BankStatementFormat::Csv => Self::parse_csv(content)?,
+        };
+
+        if parsed.is_empty() {
+            return Err(AppError::InvalidBankStatement("nenhuma transação encontrada".to_string()));
+        }
+
+        let mut tx = executor.begin().await?;
+
+        self.finance_repo.get_account(&mut *tx, tenant_id, account_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Conta {}", account_id)))?;
+
+        let import = self.repo.create_import(&mut *tx, tenant_id, account_id, format, file_name, user_id).await?;
+
+        let mut lines = Vec::new();
+        let mut duplicated = 0;
+        let mut matched = 0;
+
+        for entry in &parsed {
+            let inserted = self.repo.insert_line(
+                &mut *tx,
+                tenant_id,
+                import.id,
+                account_id,
+                &entry.external_id,
+                entry.posted_date,
+                entry.amount,
+                entry.description.as_deref(),
+            ).await?;
+
+            let Some(line) = inserted else {
+                duplicated += 1;
+                continue;
+            };
+
+            let line = self.auto_match(&mut tx, tenant_id, line).await?;
+            if line.status == BankLineStatus::Matched {
+                matched += 1;
+            }
+            lines.push(line);
+        }
+
+        let import = self.repo
+            .update_import_counts(&mut *tx, tenant_id, import.id, parsed.len() as i32, duplicated, matched)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(BankImportResult { import, lines })
+    }
+
+    pub async fn list_imports<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+    ) -> Result<Vec<BankStatementImport>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_imports(executor, tenant_id, account_id).await
+    }
+
+    pub async fn list_lines<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        account_id: Option<Uuid>,
+        import_id: Option<Uuid>,
+        status: Option<BankLineStatus>,
+    ) -> Result<Vec<BankStatementLine>, AppError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        self.repo.list_lines(executor, tenant_id, account_id, import_id, status).await
+    }
+
+    // =========================================================================
+    //  FILA DE REVISÃO
+    // =========================================================================
+
+    /// Concilia manualmente a linha com uma movimentação já lançada.
+    pub async fn match_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        movement_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<BankStatementLine, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let line = self.get_pending_line(&mut tx, tenant_id, line_id).await?;
+
+        let movement = self.finance_repo.get_movement_for_update(&mut *tx, tenant_id, movement_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Movimentação {}", movement_id)))?;
+
+        if movement.account_id != line.account_id {
+            return Err(Self::validation_error("movementId", "BankLineAccountMismatch"));
+        }
+        if movement.amount != line.amount {
+            return Err(Self::validation_error("movementId", "BankLineAmountMismatch"));
+        }
+
+        let line = self.repo.mark_line_matched(&mut *tx, tenant_id, line.id, movement.id, Some(user_id)).await?;
+
+        tx.commit().await?;
+
+        Ok(line)
+    }
+
+    /// Baixa um título em aberto com o valor da linha (na conta e data do banco)
+    /// e concilia a linha com a movimentação gerada.
+    /// Sem `title_id`, usa o título sugerido na importação.
+    pub async fn settle_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        title_id: Option<Uuid>,
+        user_id: Uuid,
+    ) -> Result<BankLineResolution, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let line = self.get_pending_line(&mut tx, tenant_id, line_id).await?;
+
+        let title_id = title_id
+            .or(line.suggested_title_id)
+            .ok_or_else(|| Self::validation_error("titleId", "NoTitleToSettle"))?;
+
+        let title = self.finance_repo.get_title(&mut *tx, tenant_id, title_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Título {}", title_id)))?;
+
+        if title.kind != Self::kind_for_amount(line.amount) {
+            return Err(Self::validation_error("titleId", "BankLineTitleKindMismatch"));
+        }
+
+        // Valor do banco maior que o saldo: quita o saldo e a diferença entra como juros.
+        // Nada é recalculado: a movimentação fica com o valor exato da linha.
+        let received = line.amount.abs();
+        let principal = received.min(title.amount_balance);
+
+        let settlement = self.finance_service.settle_title_with_charges(
+            &mut *tx,
+            tenant_id,
+            title.id,
+            line.account_id,
+            principal,
+            received - principal,
+            Decimal::ZERO,
+            Some(line.posted_date),
+            line.description.as_deref(),
+            Some(user_id),
+        ).await?;
+
+        debug_assert_eq!(settlement.movement.amount, line.amount);
+
+        let line = self.repo
+            .mark_line_matched(&mut *tx, tenant_id, line.id, settlement.movement.id, Some(user_id))
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(BankLineResolution { line, settlement })
+    }
+
+    /// Lançamento que não estava no sistema (ex: tarifa bancária, Pix recebido avulso):
+    /// cria o título já quitado na data do banco e concilia a linha.
+    pub async fn create_title_from_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        description: Option<&str>,
+        category_id: Option<Uuid>,
+        customer_id: Option<Uuid>,
+        user_id: Uuid,
+    ) -> Result<BankLineResolution, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let line = self.get_pending_line(&mut tx, tenant_id, line_id).await?;
+
+        let description = description
+            .or(line.description.as_deref())
+            .unwrap_or("Lançamento do extrato bancário")
+            .to_string();
+
+        let title = self.finance_service.create_manual_title(
+            &mut *tx,
+            tenant_id,
+            &description,
+            Self::kind_for_amount(line.amount),
+            line.amount.abs(),
+            line.posted_date,
+            Some(line.posted_date),
+            category_id,
+            customer_id,
+        ).await?;
+
+        let settlement = self.finance_service.settle_title_with_charges(
+            &mut *tx,
+            tenant_id,
+            title.id,
+            line.account_id,
+            line.amount.abs(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Some(line.posted_date),
+            line.description.as_deref(),
+            Some(user_id),
+        ).await?;
+
+        let line = self.repo
+            .mark_line_matched(&mut *tx, tenant_id, line.id, settlement.movement.id, Some(user_id))
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(BankLineResolution { line, settlement })
+    }
+
+    pub async fn ignore_line<'e, E>(
+        &self,
+        executor: E,
+        tenant_id: Uuid,
+        line_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<BankStatementLine, AppError>
+    where
+        E: Executor<'e, Database = Postgres> + Acquire<'e, Database = Postgres>,
+    {
+        let mut tx = executor.begin().await?;
+
+        let line = self.get_pending_line(&mut tx, tenant_id, line_id).await?;
+        let line = self.repo.mark_line_ignored(&mut *tx, tenant_id, line.id, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(line)
+    }
+
+    // =========================================================================
+    //  CONCILIAÇÃO AUTOMÁTICA
+    // =========================================================================
+
+    async fn auto_match(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        line: BankStatementLine,
+    ) -> Result<BankStatementLine, AppError> {
+        let description = line.description.as_deref().unwrap_or_default();
+
+        // 1. Movimentação já lançada na conta
+        let movements = self.repo.find_movement_candidates(
+            &mut *conn, tenant_id, line.account_id, line.amount, line.posted_date, MOVEMENT_MATCH_WINDOW_DAYS
+        ).await?;
+
+        let best = Self::pick_unambiguous(movements.iter().map(|m| {
+            let score = Self::match_score(
+                line.posted_date, m.movement_date, description, m.description.as_deref().unwrap_or_default()
+            );
+            (m.id, score)
+        }));
+
+        if let Some(movement_id) = best {
+            return self.repo.mark_line_matched(&mut *conn, tenant_id, line.id, movement_id, None).await;
+        }
+
+        // 2. Título em aberto (vira sugestão para a revisão)
+        let titles = self.repo.find_title_candidates(
+            &mut *conn, tenant_id, Self::kind_for_amount(line.amount), line.amount.abs(),
+            line.posted_date, TITLE_MATCH_WINDOW_DAYS
+        ).await?;
+
+        let best = Self::pick_unambiguous(titles.iter().map(|t| {
+            (t.id, Self::match_score(line.posted_date, t.due_date, description, &t.description))
+        }));
+
+        if let Some(title_id) = best {
+            return self.repo.set_line_suggestion(&mut *conn, tenant_id, line.id, title_id).await;
+        }
+
+        Ok(line)
+    }
+
+    /// Quanto maior, melhor: data mais próxima pesa mais, palavras em comum desempatam.
+    fn match_score(bank_date: NaiveDate, other_date: NaiveDate, bank_text: &str, other_text: &str) -> i64 {
+        let days_apart = (bank_date - other_date).num_days().abs();
+        let shared_words = Self::words(bank_text)
+            .iter()
+            .filter(|w| Self::words(other_text).contains(w))
+            .count() as i64;
+
+        shared_words - days_apart * 10
+    }
+
+    /// Melhor candidato, desde que não haja empate (empate = revisão humana).
+    fn pick_unambiguous(candidates: impl Iterator<Item = (Uuid, i64)>) -> Option<Uuid> {
+        let mut ranked: Vec<(Uuid, i64)> = candidates.collect();
+        ranked.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+        match ranked.as_slice() {
+            [] => None,
+            [(id, _)] => Some(*id),
+            [(id, best), (_, second), ..] if best > second => Some(*id),
+            _ => None,
+        }
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() >= 3)
+            .map(str::to_uppercase)
+            .collect()
+    }
+
+    // =========================================================================
+    //  AUXILIARES
+    // =========================================================================
+
+    async fn get_pending_line(
+        &self,
+        conn: &mut PgConnection,
+        tenant_id: Uuid,
+        line_id: Uuid,
+    ) -> Result<BankStatementLine, AppError> {
+        let line = self.repo.get_line_for_update(&mut *conn, tenant_id, line_id).await?
+            .ok_or(AppError::ResourceNotFound(format!("Linha do extrato {}", line_id)))?;
+
+        if line.status != BankLineStatus::Pending {
+            return Err(AppError::BankLineAlreadyReviewed(line_id.to_string()));
+        }
+
+        Ok(line)
+    }
+
+    /// Crédito no banco = A Receber; Débito = A Pagar
+    fn kind_for_amount(amount: Decimal) -> TitleKind {
+        if amount >= Decimal::ZERO { TitleKind::Receivable } else { TitleKind::Payable }
+    }
+
+    fn validation_error(field: &'static str, code: &'static str) -> AppError {
+        let mut errors = ValidationErrors::new();
+        errors.add(field, ValidationError::new(code));
+        AppError::ValidationError(errors)
+    }
+
+    // =========================================================================
+    //  LEITURA DOS ARQUIVOS (OFX / CSV)
+    // =========================================================================
+
+    /// OFX 1.x (SGML, tags sem fechamento) e 2.x (XML).
+    /// Cada <STMTTRN> vira uma linha: DTPOSTED, TRNAMT, FITID e MEMO/NAME.
+    fn parse_ofx(content: &str) -> Result<Vec<ParsedBankLine>, AppError> {
+        let mut parsed = Vec::new();
+        let mut occurrences = HashMap::new();
+
+        for (index, block) in content.split("<STMTTRN>").skip(1).enumerate() {
+            let block = block.split("</STMTTRN>").next().unwrap_or(block);
+            let position = index + 1;
+
+            let raw_date = Self::ofx_tag(block, "DTPOSTED")
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("transação {} sem DTPOSTED", position)))?;
+
+            // YYYYMMDD[HHMMSS[.XXX][TZ]]
+            let posted_date = raw_date.get(..8)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("transação {}: data '{}' inválida", position, raw_date)))?;
+
+            let raw_amount = Self::ofx_tag(block, "TRNAMT")
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("transação {} sem TRNAMT", position)))?;
+
+            let amount = Self::parse_amount(raw_amount)
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("transação {}: valor '{}' inválido", position, raw_amount)))?;
+
+            let description = Self::ofx_tag(block, "MEMO")
+                .or_else(|| Self::ofx_tag(block, "NAME"))
+                .map(Self::clean_description);
+
+            let external_id = match Self::ofx_tag(block, "FITID") {
+                Some(fit_id) => fit_id.to_string(),
+                None => Self::synthetic_id(&mut occurrences, posted_date, amount, description.as_deref()),
+            };
+
+            parsed.push(ParsedBankLine { external_id, posted_date, amount, description });
+        }
+
+        Ok(parsed)
+    }
+
+    /// Valor da tag no bloco. No SGML o valor vai até a próxima tag ou quebra de linha.
+    fn ofx_tag<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+        let open = format!("<{}>", name);
+        let start = block.find(&open)? + open.len();
+        let rest = &block[start..];
+        let end = rest.find('<').unwrap_or(rest.len());
+        let value = rest[..end].lines().next().unwrap_or_default().trim();
+
+        (!value.is_empty()).then_some(value)
+    }
+
+    /// Planilha com as colunas data, descrição e valor (separador `;` ou `,`).
+    /// Se a primeira linha for cabeçalho, as colunas são localizadas pelo nome
+    /// (data, descrição/histórico, valor); senão, assume essa ordem.
+    fn parse_csv(content: &str) -> Result<Vec<ParsedBankLine>, AppError> {
+        let rows: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+        let Some(first) = rows.first() else {
+            return Ok(Vec::new());
+        };
+
+        let delimiter = if first.contains(';') { ';' } else { ',' };
+        let header = Self::split_csv_row(first, delimiter);
+
+        let is_header = header.first().is_some_and(|c| Self::parse_date(c).is_none());
+        let (date_col, desc_col, amount_col) = if is_header {
+            let find = |names: &[&str]| {
+                header.iter().position(|h| {
+                    let h = h.trim().to_lowercase();
+                    names.iter().any(|n| h.starts_with(n))
+                })
+            };
+
+            (
+                find(&["data", "date"]).unwrap_or(0),
+                find(&["descri", "hist", "memo"]).unwrap_or(1),
+                find(&["valor", "amount", "value"]).unwrap_or(2),
+            )
+        } else {
+            (0, 1, 2)
+        };
+
+        let mut parsed = Vec::new();
+        let mut occurrences = HashMap::new();
+        let skip = if is_header { 1 } else { 0 };
+
+        for (index, row) in rows.iter().enumerate().skip(skip) {
+            let position = index + 1;
+            let columns = Self::split_csv_row(row, delimiter);
+            let column = |i: usize| columns.get(i).map(|c| c.trim()).unwrap_or_default();
+
+            let posted_date = Self::parse_date(column(date_col))
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("linha {}: data '{}' inválida", position, column(date_col))))?;
+
+            let amount = Self::parse_amount(column(amount_col))
+                .ok_or_else(|| AppError::InvalidBankStatement(format!("linha {}: valor '{}' inválido", position, column(amount_col))))?;
+
+            let description = Some(column(desc_col))
+                .filter(|d| !d.is_empty())
+                .map(Self::clean_description);
+
+            let external_id = Self::synthetic_id(&mut occurrences, posted_date, amount, description.as_deref());
+
+            parsed.push(ParsedBankLine { external_id, posted_date, amount, description });
+        }
+
+        Ok(parsed)
+    }
+
+    /// Divide a linha respeitando campos entre aspas ("a;b" e aspas escapadas "").
+    fn split_csv_row(row: &str, delimiter: char) -> Vec<String> {
+        let mut columns = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        let mut chars = row.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    current.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = !in_quotes,
+                c if c == delimiter && !in_quotes => columns.push(std::mem::take(&mut current)),
+                c => current.push(c),
+            }
+        }
+        columns.push(current);
+
+        columns
+    }
+
+    fn parse_date(value: &str) -> Option<NaiveDate> {
+        ["%d/%m/%Y", "%Y-%m-%d", "%d-%m-%Y", "%d/%m/%y"]
+            .iter()
+            .find_map(|fmt| NaiveDate::parse_from_str(value.trim(), fmt).ok())
+    }
+
+    /// Aceita "1234.56", "-1234,56", "1.234,56", "1,234.56", "1.234" e "R$ 10,00".
+    /// O último separador (ponto ou vírgula) só é o decimal se vier seguido de 1 ou 2 dígitos
+    /// ou se for diferente dos anteriores; senão é separador de milhar ("1.234" = 1234).
+    fn parse_amount(value: &str) -> Option<Decimal> {
+        let cleaned: String = value
+            .chars()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | ','))
+            .collect();
+        let without_separators = |text: &str| -> String {
+            text.chars().filter(|c| !matches!(c, '.' | ',')).collect()
+        };
+
+        let normalized = match cleaned.rfind([',', '.']) {
+            Some(pos) => {
+                let (int_part, dec_part) = cleaned.split_at(pos);
+                let separator = &dec_part[..1];
+                let decimals = dec_part.len() - 1;
+                let mixed = int_part.contains(if separator == "." { ',' } else { '.' });
+
+                if (1..=2).contains(&decimals) || mixed {
+                    format!("{}.{}", without_separators(int_part), &dec_part[1..])
+                } else {
+                    without_separators(&cleaned)
+                }
+            }
+            None => cleaned,
+        };
+
+        normalized.parse::<Decimal>().ok().map(|d| d.round_dp(2))
+    }
+
+    fn clean_description(value: &str) -> String {
+        let collapsed = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        collapsed.chars().take(255).collect()
+    }
+
+    /// Chave estável para linhas sem FITID: data|valor|descrição|ocorrência.
+    /// A ocorrência diferencia lançamentos idênticos no mesmo arquivo
+    /// (ex: duas tarifas iguais no mesmo dia) sem quebrar a deduplicação na reimportação.
+    fn synthetic_id(
+        occurrences: &mut HashMap<String, u32>,
+        posted_date: NaiveDate,
+        amount: Decimal,
+        description: Option<&str>,
+    ) -> String {
+        let description: String = description.unwrap_or_default().chars().take(120).collect();
+        let base = format!("{}|{:.2}|{}", posted_date, amount, description.to_uppercase());
+
+        let count = occurrences.entry(base.clone()).or_insert(0);
+        *count += 1;
+
+        format!("{}|{}", base, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(value: &str) -> Option<Decimal> {
+        ReconciliationService::parse_amount(value)
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_amount_brazilian_format() {
+        assert_eq!(amount("1.234,56"), Some(Decimal::new(123456, 2)));
+        assert_eq!(amount("-1.234,56"), Some(Decimal::new(-123456, 2)));
+        assert_eq!(amount("R$ 10,00"), Some(Decimal::new(1000, 2)));
+        assert_eq!(amount("10,5"), Some(Decimal::new(105, 1)));
+        assert_eq!(amount("1.234.567,89"), Some(Decimal::new(123456789, 2)));
+        // Só separador de milhar
+        assert_eq!(amount("1.234"), Some(Decimal::new(1234, 0)));
+        assert_eq!(amount("1.234.567"), Some(Decimal::new(1234567, 0)));
+    }
+
+    #[test]
+    fn parse_amount_international_format() {
+        assert_eq!(amount("1234.56"), Some(Decimal::new(123456, 2)));
+        assert_eq!(amount("-1,234.56"), Some(Decimal::new(-123456, 2)));
+        assert_eq!(amount("+0.5"), Some(Decimal::new(5, 1)));
+        assert_eq!(amount("1,234,567.89"), Some(Decimal::new(123456789, 2)));
+        assert_eq!(amount("1,234"), Some(Decimal::new(1234, 0)));
+        assert_eq!(amount("1,234,567"), Some(Decimal::new(1234567, 0)));
+        assert_eq!(amount("150"), Some(Decimal::new(150, 0)));
+    }
+
+    #[test]
+    fn parse_amount_rejects_garbage() {
+        assert_eq!(amount(""), None);
+        assert_eq!(amount("abc"), None);
+    }
+
+    #[test]
+    fn parse_ofx_sgml_and_xml() {
+        let sgml = "OFXHEADER:100\n<OFX><BANKTRANLIST>\n\
+            <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20260210120000[-3:BRT]\n<TRNAMT>-1234.56\n<FITID>A1\n<MEMO>PAGTO  FORNECEDOR\n\
+            <STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20260211\n<TRNAMT>150.00\n<NAME>PIX RECEBIDO\n\
+            </BANKTRANLIST></OFX>";
+        let lines = ReconciliationService::parse_ofx(sgml).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].external_id, "A1");
+        assert_eq!(lines[0].posted_date, date(2026, 2, 10));
+        assert_eq!(lines[0].amount, Decimal::new(-123456, 2));
+        assert_eq!(lines[0].description.as_deref(), Some("PAGTO FORNECEDOR"));
+        // Sem FITID: chave sintética
+        assert_eq!(lines[1].external_id, "2026-02-11|150.00|PIX RECEBIDO|1");
+        assert_eq!(lines[1].description.as_deref(), Some("PIX RECEBIDO"));
+
+        let xml = "<OFX><STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20260212</DTPOSTED>\
+            <TRNAMT>-9.90</TRNAMT><FITID>X9</FITID><MEMO>TARIFA</MEMO></STMTTRN></OFX>";
+        let lines = ReconciliationService::parse_ofx(xml).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].external_id, "X9");
+        assert_eq!(lines[0].amount, Decimal::new(-990, 2));
+        assert_eq!(lines[0].description.as_deref(), Some("TARIFA"));
+    }
+
+    #[test]
+    fn parse_ofx_rejects_invalid_transaction() {
+        let without_amount = "<OFX><STMTTRN><DTPOSTED>20260212</DTPOSTED><FITID>X9</FITID></STMTTRN></OFX>";
+        assert!(matches!(ReconciliationService::parse_ofx(without_amount), Err(AppError::InvalidBankStatement(_))));
+
+        let bad_date = "<STMTTRN>\n<DTPOSTED>2026-02\n<TRNAMT>1.00\n";
+        assert!(matches!(ReconciliationService::parse_ofx(bad_date), Err(AppError::InvalidBankStatement(_))));
+    }
+
+    #[test]
+    fn parse_csv_with_header_in_any_order() {
+        let csv = "Valor;Data;Histórico\n\"-1.234,56\";10/02/2026;Pagto fornecedor\n1.234;11/02/2026;\"Depósito; agência\"\n";
+        let lines = ReconciliationService::parse_csv(csv).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].posted_date, date(2026, 2, 10));
+        assert_eq!(lines[0].amount, Decimal::new(-123456, 2));
+        assert_eq!(lines[0].description.as_deref(), Some("Pagto fornecedor"));
+        assert_eq!(lines[1].amount, Decimal::new(1234, 0));
+        assert_eq!(lines[1].description.as_deref(), Some("Depósito; agência"));
+    }
+
+    #[test]
+    fn parse_csv_without_header_keeps_duplicates_apart() {
+        let csv = "2026-02-10,Tarifa,\"-9.90\"\n2026-02-10,Tarifa,\"-9.90\"\n";
+        let lines = ReconciliationService::parse_csv(csv).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].amount, Decimal::new(-990, 2));
+        assert_eq!(lines[0].external_id, "2026-02-10|-9.90|TARIFA|1");
+        assert_eq!(lines[1].external_id, "2026-02-10|-9.90|TARIFA|2");
+    }
+
+    #[test]
+    fn parse_csv_rejects_invalid_row() {
+        let csv = "data;descricao;valor\n10/02/2026;Tarifa;abc\n";
+        assert!(matches!(ReconciliationService::parse_csv(csv), Err(AppError::InvalidBankStatement(_))));
+    }
+}
@@ -0,0 +1,130 @@
+// src/services/scheduler.rs
+
+use std::{env, time::Duration};
+use uuid::Uuid;
+
+use crate::{
+    common::{db_utils::begin_tenant_transaction, error::AppError},
+    config::AppState,
+};
+
+// Padrão: uma varredura por hora
+const DEFAULT_SWEEP_INTERVAL_SECS: u64 = 3600;
+
+/// Sobe as rotinas em segundo plano do servidor.
+/// Intervalo configurável via `SCHEDULER_INTERVAL_SECS`.
+pub fn spawn(app_state: AppState) {
+    let interval_secs = env::var("SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_SWEEP_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        loop {
+            // O primeiro tick é imediato (varre logo ao subir o servidor)
+            ticker.tick().await;
+
+            // Gera as recorrências antes: ocorrências atrasadas já saem como vencidas
+            if let Err(e) = run_recurring_generation(&app_state).await {
+                tracing::error!("❌ Falha na geração de títulos recorrentes: {:?}", e);
+            }
+
+            if let Err(e) = run_overdue_sweep(&app_state).await {
+                tracing::error!("❌ Falha na varredura de títulos vencidos: {:?}", e);
+            }
+
+            if let Err(e) = run_reservation_expiry(&app_state).await {
+                tracing::error!("❌ Falha na expiração de reservas de estoque: {:?}", e);
+            }
+        }
+    });
+
+    tracing::info!("⏱️ Rotinas em segundo plano iniciadas (a cada {}s)", interval_secs);
+}
+
+/// Gera os títulos recorrentes que entraram na antecedência, loja por loja.
+/// Uma loja com erro não impede as demais.
+async fn run_recurring_generation(app_state: &AppState) -> Result<(), AppError> {
+    let tenant_ids = app_state.tenant_repo.list_all_tenant_ids().await?;
+
+    for tenant_id in tenant_ids {
+        match generate_tenant(app_state, tenant_id).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("🔁 Loja {}: {} título(s) recorrente(s) gerado(s)", tenant_id, count),
+            Err(e) => tracing::error!("❌ Loja {}: falha ao gerar recorrências: {:?}", tenant_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn generate_tenant(app_state: &AppState, tenant_id: Uuid) -> Result<u64, AppError> {
+    let mut tx = begin_tenant_transaction(&app_state.db_pool, tenant_id).await?;
+
+    let count = app_state.finance_service
+        .generate_recurring_titles(&mut *tx, tenant_id)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(count)
+}
+
+/// Marca como OVERDUE os títulos vencidos, loja por loja.
+/// Uma loja com erro não impede as demais.
+async fn run_overdue_sweep(app_state: &AppState) -> Result<(), AppError> {
+    let tenant_ids = app_state.tenant_repo.list_all_tenant_ids().await?;
+
+    for tenant_id in tenant_ids {
+        match sweep_tenant(app_state, tenant_id).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("📅 Loja {}: {} título(s) marcado(s) como vencido(s)", tenant_id, count),
+            Err(e) => tracing::error!("❌ Loja {}: falha ao marcar vencidos: {:?}", tenant_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn sweep_tenant(app_state: &AppState, tenant_id: Uuid) -> Result<u64, AppError> {
+    let mut tx = begin_tenant_transaction(&app_state.db_pool, tenant_id).await?;
+
+    let count = app_state.finance_service
+        .mark_overdue_titles(&mut *tx, tenant_id)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(count)
+}
+
+/// Expira as reservas de estoque com prazo vencido, loja por loja.
+/// Uma loja com erro não impede as demais.
+async fn run_reservation_expiry(app_state: &AppState) -> Result<(), AppError> {
+    let tenant_ids = app_state.tenant_repo.list_all_tenant_ids().await?;
+
+    for tenant_id in tenant_ids {
+        match expire_tenant_reservations(app_state, tenant_id).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("📦 Loja {}: {} reserva(s) de estoque expirada(s)", tenant_id, count),
+            Err(e) => tracing::error!("❌ Loja {}: falha ao expirar reservas: {:?}", tenant_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn expire_tenant_reservations(app_state: &AppState, tenant_id: Uuid) -> Result<u64, AppError> {
+    let mut tx = begin_tenant_transaction(&app_state.db_pool, tenant_id).await?;
+
+    let count = app_state.inventory_service
+        .expire_reservations(&mut *tx, tenant_id)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(count)
+}
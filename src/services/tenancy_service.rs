@@ -4,6 +4,7 @@ use crate::{
     common::error::AppError,
     db::{TenantRepository, RbacRepository}, // O repositório que acabámos de atualizar
     models::tenancy::Tenant,
+    services::finance_service::FinanceService,
 };
 use sqlx::{Acquire, Executor, PgPool, Postgres};
 use uuid::Uuid;
@@ -13,6 +14,7 @@ use crate::models::tenancy::{Location, StockPool};
 pub struct TenantService {
     tenant_repo: TenantRepository,
     rbac_repo: RbacRepository,
+    finance_service: FinanceService, // Plano de contas padrão da loja nova
     pool: PgPool, // Usamos a pool para iniciar transações
 }
 
@@ -21,9 +23,10 @@ impl TenantService {
     pub fn new(
         tenant_repo: TenantRepository,
         rbac_repo: RbacRepository,
+        finance_service: FinanceService,
         pool: PgPool
     ) -> Self {
-        Self { tenant_repo, rbac_repo, pool }
+        Self { tenant_repo, rbac_repo, finance_service, pool }
     }
 
     /// LÓGICA DE NEGÓCIO: Cria um novo Estabelecimento e, atomicamente,
@@ -81,7 +84,12 @@ impl TenantService {
             )
             .await?;
 
-        // 7. Commit
+        // 7. Plano de contas padrão (Receitas / Despesas com subcategorias)
+        self.finance_service
+            .seed_default_categories(&mut *tx, new_tenant.id)
+            .await?;
+
+        // 8. Commit
         tx.commit().await?;
 
         Ok(new_tenant)